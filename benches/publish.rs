@@ -0,0 +1,62 @@
+// Timings of the publish hot path, run with cargo bench. Each case is run for a fixed number of iterations after a warm-up,
+// and the mean time per iteration is printed.
+extern crate event;
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use event::{Event, EventPublisher};
+
+const WARM_UP: u32 = 10_000;
+const ITERATIONS: u32 = 1_000_000;
+
+fn bench<F>(name: &str, mut run: F) where F: FnMut() {
+    for _ in 0..WARM_UP {
+        run();
+    }
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        run();
+    }
+    println!("{:<40} {:>8.1} ns/iter", name, start.elapsed().as_nanos() as f64 / ITERATIONS as f64);
+}
+
+fn publisher_with(handlers: usize) -> EventPublisher<u64> {
+    let publisher = EventPublisher::new();
+    for _ in 0..handlers {
+        publisher.subscribe_args(|args: &u64| { black_box(args); }).unwrap();
+    }
+    publisher
+}
+
+fn main() {
+    for &handlers in &[0, 1, 2, 8] {
+        let publisher = publisher_with(handlers);
+        let event = Event::Args(42);
+        bench(&format!("publish_event, {} handlers", handlers), || publisher.publish_event(black_box(&event)));
+    }
+
+    // Only publishes reaching an ordered handler take the lock of the in-flight set; compare with 2 plain handlers.
+    let publisher = publisher_with(1);
+    publisher.subscribe_ordered(|event: &Event<u64>| { black_box(event); }).unwrap();
+    let event = Event::Args(42);
+    bench("publish_event, 1 handler and 1 ordered", || publisher.publish_event(black_box(&event)));
+
+    let publisher = EventPublisher::new();
+    publisher.subscribe_filtered(|event: &Event<u64>| *event == Event::Args(42), |event| { black_box(event); }).unwrap();
+    publisher.subscribe_filtered(|event: &Event<u64>| *event != Event::Args(42), |event| { black_box(event); }).unwrap();
+    let event = Event::Args(42);
+    bench("publish_event, 2 filtered handlers", || publisher.publish_event(black_box(&event)));
+
+    let publisher = EventPublisher::new();
+    for handler in 0..8 {
+        publisher.subscribe_filtered(move |event: &Event<u64>| (*event == Event::Args(42)) == (handler % 2 == 0), |event| { black_box(event); }).unwrap();
+    }
+    bench("publish_event, 4 of 8 filtered handlers", || publisher.publish_event(black_box(&event)));
+
+    let publisher = publisher_with(2);
+    bench("subscribe and unsubscribe, 2 handlers", || {
+        let id = publisher.subscribe_args(|args: &u64| { black_box(args); }).unwrap();
+        publisher.unsubscribe(id);
+    });
+}
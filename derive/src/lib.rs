@@ -0,0 +1,99 @@
+extern crate proc_macro;
+extern crate proc_macro2;
+extern crate quote;
+extern crate syn;
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, Ident};
+
+/// Derives event::EventKind for an enum event type, and generates a marker type per variant for
+/// EventPublisher::subscribe_variant. The markers are put in a module named after the enum in snake case, so the marker of
+/// MyEvent::Clicked is my_event::Clicked. A run of capitals counts as one word: the markers of HTTPEvent are in http_event.
+#[proc_macro_derive(EventKind)]
+pub fn derive_event_kind(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let data = match input.data {
+        Data::Enum(ref data) => data,
+        _ => return Err(syn::Error::new_spanned(&input.ident, "EventKind can only be derived for enums")),
+    };
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(&input.generics, "EventKind can't be derived for generic enums"));
+    }
+
+    let name = &input.ident;
+    let vis = &input.vis;
+    let module = Ident::new(&snake_case(&name.to_string()), Span::call_site());
+    let mut names = Vec::new();
+    let mut markers = Vec::new();
+    for variant in &data.variants {
+        let variant_name = &variant.ident;
+        let label = variant_name.to_string();
+        let pattern = match variant.fields {
+            Fields::Named(_) => quote!(#name::#variant_name { .. }),
+            Fields::Unnamed(_) => quote!(#name::#variant_name(..)),
+            Fields::Unit => quote!(#name::#variant_name),
+        };
+        names.push(quote!(#pattern => #label));
+        let doc = format!("Marker of {}::{}, for EventPublisher::subscribe_variant.", name, variant_name);
+        markers.push(quote! {
+            #[doc = #doc]
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+            pub struct #variant_name;
+
+            impl ::event::EventVariant<super::#name> for #variant_name {
+                const NAME: &'static str = #label;
+
+                fn matches(event: &super::#name) -> bool {
+                    match *event {
+                        super::#pattern => true,
+                        #[allow(unreachable_patterns)]
+                        _ => false,
+                    }
+                }
+            }
+        });
+    }
+
+    let module_doc = format!("Variant markers of {}, generated by derive(EventKind).", name);
+    Ok(quote! {
+        impl ::event::EventKind for #name {
+            fn variant_name(&self) -> &'static str {
+                match *self {
+                    #(#names,)*
+                }
+            }
+        }
+
+        #[doc = #module_doc]
+        #vis mod #module {
+            #(#markers)*
+        }
+    })
+}
+
+// A capital starts a new word after a lower case letter or digit, and a run of capitals is one word, whose last capital
+// starts the next word if a lower case letter follows: HTTPError becomes http_error.
+fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut snake = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            let previous = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+            if (!previous.is_uppercase() && previous != '_') || (previous.is_uppercase() && next_is_lower) {
+                snake.push('_');
+            }
+        }
+        snake.extend(c.to_lowercase());
+    }
+    snake
+}
@@ -0,0 +1,36 @@
+use std::any::Any;
+
+use {Event, EventPublisher, SubscribeError, SubscriptionId};
+
+/// Type-erased payload of an AnyEventPublisher.
+pub type AnyPayload = Box<dyn Any + Send + Sync>;
+
+/// Publisher of payloads of any type, for plugin hosts that can't know every event type at compile time. Handlers subscribe
+/// to one concrete type with subscribe_any and are only called with payloads of that type; publish_any publishes a payload.
+/// Everything else works as for any EventPublisher.
+pub type AnyEventPublisher = EventPublisher<AnyPayload>;
+
+impl EventPublisher<AnyPayload> {
+    /// Subscribes a handler to the payloads of type T. Like subscribe_filtered, the handler doesn't count as a subscriber of
+    ///     payloads of other types, see set_dead_event_handler.
+    /// INPUT:  handler: Fn(&T) + Send + Sync + 'static   handler is called with every published payload of type T.
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    pub fn subscribe_any<T, F>(&self, handler: F) -> Result<SubscriptionId, SubscribeError> where T: Any, F: Fn(&T) + Send + Sync + 'static {
+        self.subscribe_filtered(|event: &Event<AnyPayload>| match *event {
+            Event::Args(ref payload) => payload.is::<T>(),
+            Event::Missing => false,
+        }, move |event: &Event<AnyPayload>| {
+            if let Event::Args(ref payload) = *event {
+                if let Some(payload) = payload.downcast_ref::<T>() {
+                    handler(payload);
+                }
+            }
+        })
+    }
+
+    /// Publishes a payload to the handlers subscribed to its type, and to the handlers taking every event.
+    /// INPUT:  payload: T   payload of the event, boxed by the publisher.
+    pub fn publish_any<T>(&self, payload: T) where T: Any + Send + Sync {
+        self.publish_event(&Event::Args(Box::new(payload)));
+    }
+}
@@ -0,0 +1,35 @@
+use time::SystemTime;
+use SubscriptionId;
+
+/// Operation recorded in the audit trail of an EventPublisher.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuditOperation {
+    /// A handler was subscribed.
+    Subscribe { subscription: SubscriptionId },
+    /// A subscription was refused because the publisher was at its subscriber limit.
+    SubscribeRejected,
+    /// A handler was unsubscribed, either explicitly or by returning ControlFlow::Break(Unsubscribe).
+    /// removed is false when an explicit unsubscribe did not find the subscription.
+    Unsubscribe { subscription: SubscriptionId, removed: bool },
+    /// An event was published and delivered to the given number of handlers.
+    Publish { handlers: usize },
+}
+
+/// Single entry of the audit trail: what happened and when.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditRecord {
+    pub time: SystemTime,
+    pub operation: AuditOperation,
+}
+
+/// Destination of the audit trail. Records are handed to the sink in the order the operations happened and are never
+/// revisited, so a sink only ever has to append. Sinks are shared by every thread using the publisher.
+pub trait AuditSink: Send + Sync {
+    fn record(&self, record: &AuditRecord);
+}
+
+impl<F> AuditSink for F where F: Fn(&AuditRecord) + Send + Sync {
+    fn record(&self, record: &AuditRecord) {
+        self(record)
+    }
+}
@@ -0,0 +1,103 @@
+use std::cmp;
+use std::mem;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use sync;
+use time::Instant;
+
+/// Sink adapter that collects event payloads and hands them to a downstream handler in batches.
+/// A batch is flushed as soon as it holds max_size payloads, or when a payload arrives (or poll is called) and the oldest
+/// payload of the batch has waited max_delay or longer. There is no timer behind the sink, so a batch that stops growing is
+/// only flushed by poll, flush or by dropping the sink.
+/// Subscribe it to a publisher with EventPublisher::subscribe_batching.
+pub struct BatchingSink<E> {
+    max_size: usize,
+    max_delay: Duration,
+    downstream: Box<dyn Fn(Vec<E>) + Send + Sync + 'static>,
+    pending: Mutex<Pending<E>>,
+}
+
+struct Pending<E> {
+    items: Vec<E>,
+    oldest: Option<Instant>,
+}
+
+impl<E> BatchingSink<E> {
+    /// Batching sink constructor.
+    /// INPUT:  max_size: usize   number of payloads that triggers a flush. A max_size of 0 is treated as 1.
+    ///         max_delay: Duration   longest time a payload should wait in the batch.
+    ///         downstream: Fn(Vec<E>) + Send + Sync + 'static   handler receiving each flushed batch, oldest payload first.
+    pub fn new<F>(max_size: usize, max_delay: Duration, downstream: F) -> BatchingSink<E> where F: Fn(Vec<E>) + Send + Sync + 'static {
+        BatchingSink {
+            max_size: cmp::max(max_size, 1),
+            max_delay,
+            downstream: Box::new(downstream),
+            pending: Mutex::new(Pending { items: Vec::new(), oldest: None }),
+        }
+    }
+
+    /// Adds a payload to the current batch, flushing it if it is now full or has been waiting for max_delay.
+    /// INPUT:  item: E
+    /// OUTPUT: void
+    pub fn push(&self, item: E) {
+        let full = {
+            let mut pending = sync::lock(&self.pending);
+            pending.items.push(item);
+            if pending.oldest.is_none() {
+                pending.oldest = Some(Instant::now());
+            }
+            pending.items.len() >= self.max_size || self.is_overdue(&pending)
+        };
+        if full {
+            self.flush();
+        }
+    }
+
+    /// Flushes the current batch if its oldest payload has been waiting for max_delay or longer. Call this periodically when
+    ///     events may stop arriving for a while.
+    /// OUTPUT: void
+    pub fn poll(&self) {
+        let overdue = self.is_overdue(&sync::lock(&self.pending));
+        if overdue {
+            self.flush();
+        }
+    }
+
+    /// Hands the current batch to the downstream handler straight away. Does nothing when the batch is empty.
+    ///     The downstream handler runs without the sink locked, so payloads pushed meanwhile start the next batch.
+    /// OUTPUT: void
+    pub fn flush(&self) {
+        let batch = {
+            let mut pending = sync::lock(&self.pending);
+            pending.oldest = None;
+            mem::take(&mut pending.items)
+        };
+        if !batch.is_empty() {
+            (self.downstream)(batch);
+        }
+    }
+
+    /// Number of payloads waiting in the current batch.
+    pub fn len(&self) -> usize {
+        sync::lock(&self.pending).items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        sync::lock(&self.pending).items.is_empty()
+    }
+
+    fn is_overdue(&self, pending: &Pending<E>) -> bool {
+        match pending.oldest {
+            Some(oldest) => oldest.elapsed() >= self.max_delay,
+            None => false,
+        }
+    }
+}
+
+// Whatever is still pending is flushed, so no payload is lost when the sink goes away.
+impl<E> Drop for BatchingSink<E> {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
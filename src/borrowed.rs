@@ -0,0 +1,152 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, RwLock};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use sync;
+use {Event, HandlerPanic, SubscribeError, SubscriptionId};
+
+/// Boxed event handler function, as accepted by BorrowedEventPublisher::subscribe_handler. It has to accept a payload
+/// borrowed for any lifetime, so it can't hold on to the payload once it returns:
+///
+/// ```compile_fail
+/// # use std::sync::Mutex;
+/// # use event::{BorrowedHandlerBox, Event};
+/// let kept: &'static Mutex<Option<&str>> = Box::leak(Box::new(Mutex::new(None)));
+/// let handler: BorrowedHandlerBox<str> = Box::new(move |event: &Event<&str>| {
+///     if let Event::Args(word) = *event {
+///         *kept.lock().unwrap() = Some(word);
+///     }
+/// });
+/// ```
+pub type BorrowedHandlerBox<T> = Box<dyn for<'a> Fn(&Event<&'a T>) + Send + Sync + 'static>;
+type BorrowedPanicHook = dyn Fn(&HandlerPanic) + Send + Sync + 'static;
+type BorrowedPanicHookBox = Box<BorrowedPanicHook>;
+
+struct BorrowedEntry<T: ?Sized> {
+    handler: BorrowedHandlerBox<T>,
+    priority: i32,
+}
+
+// Copy-on-write like the handler list of EventPublisher, so a publish can hold on to the list it started with.
+type BorrowedHandlerList<T> = Arc<Vec<(SubscriptionId, Arc<BorrowedEntry<T>>)>>;
+
+/// Publisher of events that borrow their payload, such as Event<&str> for the words of a line being parsed, so payloads
+/// don't have to be cloned into owned types first. An EventPublisher<&'a str> would tie every publish to the one lifetime
+/// 'a; here each publish may borrow for as long as it runs, since handlers accept a payload borrowed for any lifetime and
+/// so can't keep it. Publishing is synchronous, as for EventPublisher::publish_event: handlers are called in order of
+/// descending priority, each publish delivers to the handlers subscribed when it started, and a panicking handler doesn't
+/// keep the event from the others.
+pub struct BorrowedEventPublisher<T: ?Sized> {
+    handlers: RwLock<BorrowedHandlerList<T>>,
+    next_id: AtomicU64,
+    max_subscribers: Option<usize>,
+    // Cloned out before it is called, so the hook may replace itself.
+    panic_hook: RwLock<Option<Arc<BorrowedPanicHook>>>,
+}
+
+impl<T: ?Sized> BorrowedEventPublisher<T> {
+    /// Borrowed event publisher constructor.
+    pub fn new() -> BorrowedEventPublisher<T> {
+        BorrowedEventPublisher {
+            handlers: RwLock::new(Arc::new(Vec::new())),
+            next_id: AtomicU64::new(0),
+            max_subscribers: None,
+            panic_hook: RwLock::new(None),
+        }
+    }
+
+    /// Borrowed event publisher constructor limiting the number of subscriptions, see EventPublisher::with_max_subscribers.
+    /// INPUT:  max_subscribers: usize   maximum number of handlers subscribed at the same time.
+    pub fn with_max_subscribers(max_subscribers: usize) -> BorrowedEventPublisher<T> {
+        BorrowedEventPublisher { max_subscribers: Some(max_subscribers), ..BorrowedEventPublisher::new() }
+    }
+
+    /// Sets a hook called with the panics of handlers, see EventPublisher::set_panic_hook.
+    /// INPUT:  hook: Box<dyn Fn(&HandlerPanic) + Send + Sync + 'static>
+    pub fn set_panic_hook(&self, hook: BorrowedPanicHookBox) {
+        *sync::write(&self.panic_hook) = Some(Arc::from(hook));
+    }
+
+    /// Subscribes event handler functions to the BorrowedEventPublisher.
+    /// INPUT:  handler_box: Box<dyn for<'a> Fn(&Event<&'a T>) + Send + Sync + 'static>   function to handle events borrowing a T.
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    pub fn subscribe_handler(&self, handler_box: BorrowedHandlerBox<T>) -> Result<SubscriptionId, SubscribeError> {
+        self.insert_handler(handler_box, 0)
+    }
+
+    /// Subscribes event handler functions with a priority, see EventPublisher::subscribe_with_priority.
+    /// INPUT:  handler_box: Box<dyn for<'a> Fn(&Event<&'a T>) + Send + Sync + 'static>   function to handle events borrowing a T.
+    ///         priority: i32   handlers with a higher priority are called first.
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    pub fn subscribe_with_priority(&self, handler_box: BorrowedHandlerBox<T>, priority: i32) -> Result<SubscriptionId, SubscribeError> {
+        self.insert_handler(handler_box, priority)
+    }
+
+    /// Subscribes a handler that is only called with the borrowed payload of events, skipping Event::Missing.
+    /// INPUT:  handler: Fn(&T) + Send + Sync + 'static   handler is called with the payload of every published event.
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    pub fn subscribe_args<F>(&self, handler: F) -> Result<SubscriptionId, SubscribeError> where F: Fn(&T) + Send + Sync + 'static {
+        self.subscribe_handler(Box::new(move |event: &Event<&T>| {
+            if let Event::Args(args) = *event {
+                handler(args);
+            }
+        }))
+    }
+
+    /// Unsubscribes a handler, see EventPublisher::unsubscribe.
+    /// INPUT:  id: SubscriptionId   id returned when the handler was subscribed.
+    /// OUTPUT: bool   whether a handler was removed.
+    pub fn unsubscribe(&self, id: SubscriptionId) -> bool {
+        let mut handlers = sync::write(&self.handlers);
+        match handlers.iter().position(|&(subscribed, _)| subscribed == id) {
+            Some(position) => {
+                Arc::make_mut(&mut *handlers).remove(position);
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Number of handlers currently subscribed.
+    pub fn subscriber_count(&self) -> usize {
+        sync::read(&self.handlers).len()
+    }
+
+    /// Whether no handler is subscribed.
+    pub fn is_empty(&self) -> bool {
+        self.subscriber_count() == 0
+    }
+
+    /// Publishes an event borrowing its payload, pushing it to all handler functions stored by the publisher.
+    /// INPUT: event: &Event<&T>   the payload only has to live until publish_event returns.
+    pub fn publish_event(&self, event: &Event<&T>) {
+        let handlers = sync::read(&self.handlers).clone();
+        for &(id, ref entry) in handlers.iter() {
+            if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| (entry.handler)(event))) {
+                let hook = sync::read(&self.panic_hook).clone();
+                if let Some(hook) = hook {
+                    hook(&HandlerPanic::new(id, payload));
+                }
+            }
+        }
+    }
+
+    fn insert_handler(&self, handler: BorrowedHandlerBox<T>, priority: i32) -> Result<SubscriptionId, SubscribeError> {
+        let mut handlers = sync::write(&self.handlers);
+        if let Some(max_subscribers) = self.max_subscribers {
+            if handlers.len() >= max_subscribers {
+                return Err(SubscribeError::Full);
+            }
+        }
+        let id = SubscriptionId(self.next_id.fetch_add(1, Ordering::SeqCst) + 1);
+        let position = handlers.iter().position(|(_, entry)| entry.priority < priority).unwrap_or(handlers.len());
+        Arc::make_mut(&mut *handlers).insert(position, (id, Arc::new(BorrowedEntry { handler, priority })));
+        Ok(id)
+    }
+}
+
+impl<T: ?Sized> Default for BorrowedEventPublisher<T> {
+    fn default() -> BorrowedEventPublisher<T> {
+        BorrowedEventPublisher::new()
+    }
+}
@@ -0,0 +1,138 @@
+use std::sync::Arc;
+
+#[cfg(not(target_arch = "wasm32"))]
+use Dispatcher;
+use {AuditSink, Backpressure, ChaosConfig, Event, EventHistory, EventPublisher, HandlerPanic, HandlerTimeout, QueuedPublisher, TimeoutPolicy};
+
+/// Builder configuring an EventPublisher in one place, returned by EventPublisher::builder. Each method does what the
+/// corresponding set_* function of EventPublisher does. The build functions pick how events are dispatched: build for
+/// publishing on the calling thread, build_queued for queueing events until dispatch_pending, build_threaded for a
+/// dispatcher thread.
+pub struct EventPublisherBuilder<E> {
+    publisher: EventPublisher<E>,
+    queue_capacity: Option<(usize, Backpressure)>,
+}
+
+impl<E> EventPublisherBuilder<E> {
+    pub(crate) fn new() -> EventPublisherBuilder<E> {
+        EventPublisherBuilder { publisher: EventPublisher::new(), queue_capacity: None }
+    }
+
+    /// Limits the number of subscriptions, see EventPublisher::with_max_subscribers.
+    /// INPUT:  max_subscribers: usize   maximum number of handlers subscribed at the same time.
+    pub fn max_subscribers(mut self, max_subscribers: usize) -> EventPublisherBuilder<E> {
+        self.publisher.max_subscribers = Some(max_subscribers);
+        self
+    }
+
+    /// Bounds the queue of build_queued and build_threaded, which is unbounded otherwise.
+    /// INPUT:  capacity: usize   maximum number of queued events. A capacity of 0 is treated as 1.
+    ///         backpressure: Backpressure   what publishing does while the queue is full.
+    pub fn queue_capacity(mut self, capacity: usize, backpressure: Backpressure) -> EventPublisherBuilder<E> {
+        self.queue_capacity = Some((capacity, backpressure));
+        self
+    }
+
+    /// Sets the hook told about handlers that panic, see EventPublisher::set_panic_hook.
+    /// INPUT:  hook: Fn(&HandlerPanic) + Send + Sync + 'static
+    pub fn panic_hook<F>(self, hook: F) -> EventPublisherBuilder<E> where F: Fn(&HandlerPanic) + Send + Sync + 'static {
+        self.publisher.set_panic_hook(Box::new(hook));
+        self
+    }
+
+    /// Sets the source identifier stamped on envelopes, see EventPublisher::set_source.
+    /// INPUT:  source: &str
+    pub fn source(self, source: &str) -> EventPublisherBuilder<E> {
+        self.publisher.set_source(Some(String::from(source)));
+        self
+    }
+
+    /// Adds an interceptor wrapping every publish, see EventPublisher::add_interceptor.
+    /// INPUT:  interceptor: Fn(&Event<E>, &dyn Fn(&Event<E>)) + Send + Sync + 'static
+    pub fn interceptor<F>(self, interceptor: F) -> EventPublisherBuilder<E> where F: Fn(&Event<E>, &dyn Fn(&Event<E>)) + Send + Sync + 'static {
+        self.publisher.add_interceptor(interceptor);
+        self
+    }
+
+    /// Sets the handler told about dead events, see EventPublisher::set_dead_event_handler.
+    /// INPUT:  handler: Fn(&Event<E>) + Send + Sync + 'static
+    pub fn dead_event_handler<F>(self, handler: F) -> EventPublisherBuilder<E> where F: Fn(&Event<E>) + Send + Sync + 'static {
+        self.publisher.set_dead_event_handler(Box::new(handler));
+        self
+    }
+
+    /// Sets the sink receiving the audit trail, see EventPublisher::set_audit_sink.
+    /// INPUT:  sink: Box<dyn AuditSink>
+    pub fn audit_sink(self, sink: Box<dyn AuditSink>) -> EventPublisherBuilder<E> {
+        self.publisher.set_audit_sink(sink);
+        self
+    }
+
+    /// Sets how long publish_event_with_timeout waits for handlers, see EventPublisher::set_handler_timeout.
+    /// INPUT:  policy: TimeoutPolicy
+    pub fn handler_timeout(self, policy: TimeoutPolicy) -> EventPublisherBuilder<E> {
+        self.publisher.set_handler_timeout(Some(policy));
+        self
+    }
+
+    /// Sets the hook told about handlers exceeding the handler timeout, see EventPublisher::set_timeout_hook.
+    /// INPUT:  hook: Fn(&HandlerTimeout) + Send + Sync + 'static
+    pub fn timeout_hook<F>(self, hook: F) -> EventPublisherBuilder<E> where F: Fn(&HandlerTimeout) + Send + Sync + 'static {
+        self.publisher.set_timeout_hook(Box::new(hook));
+        self
+    }
+
+    /// Switches metrics on, see EventPublisher::set_metrics.
+    pub fn metrics(self) -> EventPublisherBuilder<E> {
+        self.publisher.set_metrics(true);
+        self
+    }
+
+    /// Switches leak detection on, see EventPublisher::set_leak_detection.
+    pub fn leak_detection(self) -> EventPublisherBuilder<E> {
+        self.publisher.set_leak_detection(true);
+        self
+    }
+
+    /// Switches chaos mode on, see EventPublisher::set_chaos. Meant for tests only.
+    /// INPUT:  config: ChaosConfig
+    pub fn chaos(self, config: ChaosConfig) -> EventPublisherBuilder<E> {
+        self.publisher.set_chaos(Some(config));
+        self
+    }
+
+    /// Records the published events in a history, see EventPublisher::subscribe_history. The history counts towards the
+    ///     subscriber limit, so it records nothing if max_subscribers was set to 0.
+    /// INPUT:  history: Arc<EventHistory<E>>   history keeping the last events, e.g. Arc::new(EventHistory::new(100)).
+    pub fn history(self, history: Arc<EventHistory<E>>) -> EventPublisherBuilder<E> where E: Clone + Send + 'static {
+        let _ = self.publisher.subscribe_history(history);
+        self
+    }
+
+    /// Builds a publisher dispatching events on the thread that publishes them.
+    /// OUTPUT: EventPublisher<E>
+    pub fn build(self) -> EventPublisher<E> {
+        self.publisher
+    }
+
+    /// Builds a publisher queueing events until dispatch_pending is called.
+    /// OUTPUT: QueuedPublisher<E>   subscribe handlers on its publisher.
+    pub fn build_queued(self) -> QueuedPublisher<E> {
+        match self.queue_capacity {
+            Some((capacity, backpressure)) => QueuedPublisher::with_capacity(self.publisher, capacity, backpressure),
+            None => QueuedPublisher::from_publisher(self.publisher),
+        }
+    }
+
+    /// Builds a publisher dispatching events on a thread of its own, see EventPublisher::spawn_dispatcher.
+    /// OUTPUT: (Arc<EventPublisher<E>>, Dispatcher<E>)   the publisher, for subscribing handlers, and the dispatcher to publish through.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn build_threaded(self) -> (Arc<EventPublisher<E>>, Dispatcher<E>) where E: Send + 'static {
+        let publisher = Arc::new(self.publisher);
+        let dispatcher = match self.queue_capacity {
+            Some((capacity, backpressure)) => publisher.clone().spawn_dispatcher_with_capacity(capacity, backpressure),
+            None => publisher.clone().spawn_dispatcher(),
+        };
+        (publisher, dispatcher)
+    }
+}
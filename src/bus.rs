@@ -0,0 +1,188 @@
+use std::any::{self, Any, TypeId};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::Duration;
+
+use sync;
+use time::{Instant, SystemTime};
+use {Event, EventPublisher, SubscriptionId};
+
+/// Diagnostics of one event type on an EventBus, as returned by EventBus::diagnostics.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventTypeStats {
+    /// Name of the event type, as given by std::any::type_name.
+    pub type_name: &'static str,
+    /// Handlers currently subscribed to the type.
+    pub subscribers: usize,
+    /// Events of the type published through EventBus::publish.
+    pub published: u64,
+    /// Mean time EventBus::publish took to deliver an event of the type to its handlers, zero if none was published.
+    pub average_dispatch: Duration,
+    pub last_published: Option<SystemTime>,
+}
+
+// Publisher of one event type, with what the diagnostics need to know about it without knowing the type.
+struct Route {
+    // An Arc<EventPublisher<T>> for the type T the route is keyed by.
+    publisher: Box<dyn Any + Send + Sync>,
+    type_name: &'static str,
+    subscribers: fn(&(dyn Any + Send + Sync)) -> usize,
+    stats: SharedStats,
+}
+
+type SharedStats = Arc<Mutex<RouteStats>>;
+
+#[derive(Default)]
+struct RouteStats {
+    published: u64,
+    dispatch_time: Duration,
+    last_published: Option<SystemTime>,
+}
+
+impl Route {
+    fn new<T>() -> Route where T: 'static {
+        Route {
+            publisher: Box::new(Arc::new(EventPublisher::<T>::new())),
+            type_name: any::type_name::<T>(),
+            subscribers: subscriber_count::<T>,
+            stats: Arc::new(Mutex::new(RouteStats::default())),
+        }
+    }
+
+    fn publisher<T>(&self) -> Option<Arc<EventPublisher<T>>> where T: 'static {
+        self.publisher.downcast_ref::<Arc<EventPublisher<T>>>().cloned()
+    }
+
+    fn diagnostics(&self) -> EventTypeStats {
+        let stats = sync::lock(&self.stats);
+        EventTypeStats {
+            type_name: self.type_name,
+            subscribers: (self.subscribers)(&*self.publisher),
+            published: stats.published,
+            average_dispatch: match stats.published {
+                0 => Duration::ZERO,
+                published => stats.dispatch_time.div_f64(published as f64),
+            },
+            last_published: stats.last_published,
+        }
+    }
+}
+
+fn subscriber_count<T>(publisher: &(dyn Any + Send + Sync)) -> usize where T: 'static {
+    publisher.downcast_ref::<Arc<EventPublisher<T>>>().map_or(0, |publisher| publisher.subscriber_count())
+}
+
+/// Routes events of any number of types through one object. The bus keeps one EventPublisher per event type, keyed by the
+/// TypeId of the type and created when the first handler for it subscribes. See diagnostics and dump for what is going on
+/// in a running bus.
+pub struct EventBus {
+    routes: RwLock<HashMap<TypeId, Route>>,
+}
+
+impl EventBus {
+    /// Event bus constructor.
+    pub fn new() -> EventBus {
+        EventBus { routes: RwLock::new(HashMap::new()) }
+    }
+
+    /// Subscribes a handler to the events of type T, e.g. bus.subscribe::<MyEvent>(|event| ...).
+    /// INPUT:  handler: Fn(&T) + Send + Sync + 'static   handler is called with a reference to every event of type T published on the bus.
+    /// OUTPUT: SubscriptionId   id of the new subscription, for unsubscribe::<T>.
+    pub fn subscribe<T>(&self, handler: impl Fn(&T) + Send + Sync + 'static) -> SubscriptionId where T: 'static {
+        // A bus publisher has no subscriber limit.
+        self.publisher::<T>().subscribe_args(handler).unwrap()
+    }
+
+    /// Unsubscribes a handler from the events of type T.
+    /// INPUT:  id: SubscriptionId   id returned by subscribe::<T>.
+    /// OUTPUT: bool   true if the handler was subscribed and has been removed.
+    pub fn unsubscribe<T>(&self, id: SubscriptionId) -> bool where T: 'static {
+        match self.existing::<T>() {
+            Some(publisher) => publisher.unsubscribe(id),
+            None => false,
+        }
+    }
+
+    /// Publishes an event to the handlers subscribed to its type, as publisher::<T>().publish_event(&Event::Args(event))
+    ///     would, so pausing that publisher holds bus events too. Does nothing when no handler was ever subscribed to it.
+    ///     Counted in the published events of diagnostics even while the publisher is paused.
+    /// INPUT:  event: T
+    pub fn publish<T>(&self, event: T) where T: 'static {
+        let (publisher, stats) = match self.route::<T>() {
+            Some(route) => route,
+            None => return,
+        };
+        let published_at = SystemTime::now();
+        let started = Instant::now();
+        publisher.publish_event(&Event::Args(event));
+        let elapsed = started.elapsed();
+        let mut stats = sync::lock(&stats);
+        stats.published += 1;
+        stats.dispatch_time += elapsed;
+        stats.last_published = Some(published_at);
+    }
+
+    /// Publisher of the events of type T, created if needed, for the parts of the EventPublisher API the bus does not wrap.
+    ///     Events published on it directly don't show up in the published counts of diagnostics.
+    pub fn publisher<T>(&self) -> Arc<EventPublisher<T>> where T: 'static {
+        if let Some(publisher) = self.existing::<T>() {
+            return publisher;
+        }
+        sync::write(&self.routes)
+            .entry(TypeId::of::<T>())
+            .or_insert_with(Route::new::<T>)
+            .publisher::<T>()
+            .expect("bus publisher stored under the TypeId of another type")
+    }
+
+    /// Statistics of every event type on the bus, i.e. every type a handler was ever subscribed to, sorted by type name.
+    /// OUTPUT: Vec<EventTypeStats>
+    pub fn diagnostics(&self) -> Vec<EventTypeStats> {
+        let mut diagnostics: Vec<EventTypeStats> = sync::read(&self.routes).values().map(Route::diagnostics).collect();
+        diagnostics.sort_by_key(|stats| stats.type_name);
+        diagnostics
+    }
+
+    /// Renders the diagnostics as text, one line per event type, e.g. for logging the topology of a running bus.
+    /// OUTPUT: String
+    pub fn dump(&self) -> String {
+        let diagnostics = self.diagnostics();
+        let now = SystemTime::now();
+        let mut dump = format!("EventBus with {} event types\n", diagnostics.len());
+        for stats in diagnostics {
+            let _ = write!(dump, "  {}: {} subscribers, {} published", stats.type_name, stats.subscribers, stats.published);
+            if let Some(last_published) = stats.last_published {
+                let ago = now.duration_since(last_published).unwrap_or(Duration::ZERO);
+                let _ = write!(dump, ", {:?} average dispatch, last published {:?} ago", stats.average_dispatch, ago);
+            }
+            dump.push('\n');
+        }
+        dump
+    }
+
+    fn existing<T>(&self) -> Option<Arc<EventPublisher<T>>> where T: 'static {
+        self.route::<T>().map(|(publisher, _)| publisher)
+    }
+
+    // Hands out clones of the Arcs, so the map is not locked while handlers run and they may use the bus themselves.
+    fn route<T>(&self) -> Option<(Arc<EventPublisher<T>>, SharedStats)> where T: 'static {
+        let routes = sync::read(&self.routes);
+        let route = routes.get(&TypeId::of::<T>())?;
+        Some((route.publisher::<T>()?, route.stats.clone()))
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> EventBus {
+        EventBus::new()
+    }
+}
+
+/// Application-wide event bus, created on first use. Lets small applications publish and subscribe from anywhere without
+/// handing a bus to every component; libraries should take a bus from their caller instead. Handlers subscribed to it stay
+/// subscribed for the rest of the program unless they are unsubscribed.
+pub fn global_bus() -> &'static EventBus {
+    static GLOBAL_BUS: OnceLock<EventBus> = OnceLock::new();
+    GLOBAL_BUS.get_or_init(EventBus::new)
+}
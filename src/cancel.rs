@@ -0,0 +1,76 @@
+use std::fmt;
+use std::mem;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use sync;
+
+type CancelCallback = Box<dyn FnOnce() + Send + 'static>;
+
+/// Token for shutting down dispatcher threads, schedulers and async publishes from anywhere, e.g. from a signal handler
+/// thread. Hand it to Dispatcher::cancel_on, Scheduler::cancel_on or PublishFuture::cancel_on; cancelling it shuts all of
+/// them down at once. Clones share the same state, so cancelling one cancels them all.
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    state: Arc<TokenState>,
+}
+
+#[derive(Default)]
+struct TokenState {
+    cancelled: AtomicBool,
+    // Run once, by cancel.
+    callbacks: Mutex<Vec<CancelCallback>>,
+}
+
+impl CancellationToken {
+    /// Cancellation token constructor.
+    pub fn new() -> CancellationToken {
+        CancellationToken::default()
+    }
+
+    /// Cancels the token, shutting down everything it was handed to. Does nothing if it is already cancelled.
+    /// OUTPUT: void
+    pub fn cancel(&self) {
+        let callbacks = {
+            let mut callbacks = sync::lock(&self.state.callbacks);
+            if self.state.cancelled.swap(true, Ordering::SeqCst) {
+                return;
+            }
+            mem::take(&mut *callbacks)
+        };
+        for callback in callbacks {
+            callback();
+        }
+    }
+
+    /// Whether cancel has been called.
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancelled.load(Ordering::SeqCst)
+    }
+
+    // Runs callback once the token is cancelled, straight away if it already is.
+    pub(crate) fn on_cancel<F>(&self, callback: F) where F: FnOnce() + Send + 'static {
+        let mut callbacks = sync::lock(&self.state.callbacks);
+        if self.is_cancelled() {
+            drop(callbacks);
+            callback();
+        } else {
+            callbacks.push(Box::new(callback));
+        }
+    }
+}
+
+impl fmt::Debug for CancellationToken {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CancellationToken").field("cancelled", &self.is_cancelled()).finish()
+    }
+}
+
+/// What shutting down a Dispatcher or Scheduler does with the events it hasn't published yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shutdown {
+    /// Publish them first. A scheduler publishes its one-off events at their scheduled times and discards repeating ones.
+    Drain,
+    /// Discard them.
+    Discard,
+}
@@ -0,0 +1,91 @@
+use std::time::Duration;
+
+/// Chaos configuration for an EventPublisher, for testing that handlers cope with unreliable delivery.
+/// With a configuration set, every delivery of a published event to a handler may independently be dropped, delayed or
+/// duplicated with the given probabilities (0.0 to 1.0), and the order in which handlers see an event may be shuffled.
+/// All decisions are drawn from a pseudo random generator seeded with seed, so a failing run can be reproduced.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChaosConfig {
+    pub seed: u64,
+    pub drop_probability: f64,
+    pub duplicate_probability: f64,
+    pub delay_probability: f64,
+    /// Upper bound of the delay applied to a delayed delivery. The actual delay is uniformly distributed up to it.
+    pub max_delay: Duration,
+    pub reorder: bool,
+}
+
+impl ChaosConfig {
+    /// Chaos configuration constructor. Starts out with every kind of chaos switched off.
+    pub fn new(seed: u64) -> ChaosConfig {
+        ChaosConfig {
+            seed,
+            drop_probability: 0.0,
+            duplicate_probability: 0.0,
+            delay_probability: 0.0,
+            max_delay: Duration::from_millis(0),
+            reorder: false,
+        }
+    }
+}
+
+pub(crate) struct Chaos {
+    config: ChaosConfig,
+    state: u64,
+}
+
+impl Chaos {
+    pub(crate) fn new(config: ChaosConfig) -> Chaos {
+        // xorshift gets stuck on a zero state.
+        let state = if config.seed == 0 { 0x9E37_79B9_7F4A_7C15 } else { config.seed };
+        Chaos { config, state }
+    }
+
+    pub(crate) fn shuffle<T>(&mut self, items: &mut [T]) {
+        if !self.config.reorder {
+            return;
+        }
+        for i in (1..items.len()).rev() {
+            let j = (self.next_u64() % (i as u64 + 1)) as usize;
+            items.swap(i, j);
+        }
+    }
+
+    pub(crate) fn should_drop(&mut self) -> bool {
+        let probability = self.config.drop_probability;
+        self.roll(probability)
+    }
+
+    pub(crate) fn should_duplicate(&mut self) -> bool {
+        let probability = self.config.duplicate_probability;
+        self.roll(probability)
+    }
+
+    pub(crate) fn delay(&mut self) -> Option<Duration> {
+        let probability = self.config.delay_probability;
+        if !self.roll(probability) {
+            return None;
+        }
+        let max_nanos = self.config.max_delay.as_nanos() as u64;
+        if max_nanos == 0 {
+            return None;
+        }
+        Some(Duration::from_nanos(self.next_u64() % (max_nanos + 1)))
+    }
+
+    fn roll(&mut self, probability: f64) -> bool {
+        if probability <= 0.0 {
+            return false;
+        }
+        let sample = (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+        sample < probability
+    }
+
+    // xorshift64*
+    fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+}
@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::mem;
+use std::sync::Mutex;
+
+use sync;
+use {Event, EventPublisher};
+
+type KeyBox<E, K> = Box<dyn Fn(&E) -> K + Send + Sync + 'static>;
+type ReduceBox<E> = Box<dyn Fn(E, E) -> E + Send + Sync + 'static>;
+
+struct Pending<E, K> {
+    // In the order the first event of each key was published.
+    events: Vec<Event<E>>,
+    positions: HashMap<K, usize>,
+}
+
+/// Publisher that coalesces bursts of duplicate events, so handlers aren't called hundreds of times for the same "dirty"
+/// notification. Like QueuedPublisher, publish_event only queues the event and dispatch_pending publishes the queue; events
+/// with the same key queued in between are merged into one, which is published where the first of them was queued.
+/// Event::Missing is never merged. Handlers are subscribed on the underlying EventPublisher, see publisher.
+pub struct CoalescingPublisher<E, K> {
+    publisher: EventPublisher<E>,
+    key: KeyBox<E, K>,
+    reduce: ReduceBox<E>,
+    pending: Mutex<Pending<E, K>>,
+}
+
+impl<E, K> CoalescingPublisher<E, K> where K: Eq + Hash {
+    /// Coalescing publisher constructor keeping the latest event of every key.
+    /// INPUT:  key: Fn(&E) -> K + Send + Sync + 'static   key of the payload of an event; events with equal keys are merged.
+    pub fn new<F>(key: F) -> CoalescingPublisher<E, K> where F: Fn(&E) -> K + Send + Sync + 'static {
+        CoalescingPublisher::with_reducer(key, |_, latest| latest)
+    }
+
+    /// Coalescing publisher constructor folding the events of every key with a reducer.
+    /// INPUT:  key: Fn(&E) -> K + Send + Sync + 'static   key of the payload of an event; events with equal keys are merged.
+    ///         reduce: Fn(E, E) -> E + Send + Sync + 'static   called with the merged payload so far and the payload of the next
+    ///         event of the same key. Runs while the queue is locked, so it must not publish to this publisher.
+    pub fn with_reducer<F, R>(key: F, reduce: R) -> CoalescingPublisher<E, K> where F: Fn(&E) -> K + Send + Sync + 'static, R: Fn(E, E) -> E + Send + Sync + 'static {
+        CoalescingPublisher {
+            publisher: EventPublisher::new(),
+            key: Box::new(key),
+            reduce: Box::new(reduce),
+            pending: Mutex::new(Pending { events: Vec::new(), positions: HashMap::new() }),
+        }
+    }
+
+    /// Publisher the coalesced events are dispatched on, for subscribing handlers.
+    pub fn publisher(&self) -> &EventPublisher<E> {
+        &self.publisher
+    }
+
+    /// Queues an event for the next dispatch_pending, merging it into the queued event with the same key, if any.
+    /// INPUT:  event: Event<E>
+    pub fn publish_event(&self, event: Event<E>) {
+        let mut pending = sync::lock(&self.pending);
+        let args = match event {
+            Event::Args(args) => args,
+            Event::Missing => return pending.events.push(Event::Missing),
+        };
+        let key = (self.key)(&args);
+        if let Some(&position) = pending.positions.get(&key) {
+            if let Event::Args(merged) = mem::replace(&mut pending.events[position], Event::Missing) {
+                pending.events[position] = Event::Args((self.reduce)(merged, args));
+            }
+            return;
+        }
+        let position = pending.events.len();
+        pending.events.push(Event::Args(args));
+        pending.positions.insert(key, position);
+    }
+
+    /// Publishes the queued events in the order their first event was queued. Events queued by handlers meanwhile are left
+    ///     for the next call.
+    /// OUTPUT: usize   number of events dispatched after merging.
+    pub fn dispatch_pending(&self) -> usize {
+        let events = {
+            let mut pending = sync::lock(&self.pending);
+            pending.positions.clear();
+            mem::take(&mut pending.events)
+        };
+        let dispatched = events.len();
+        for event in events {
+            self.publisher.publish_event(&event);
+        }
+        dispatched
+    }
+
+    /// Number of events waiting for dispatch_pending, after merging.
+    pub fn len(&self) -> usize {
+        sync::lock(&self.pending).events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
@@ -0,0 +1,82 @@
+use std::error::Error;
+use std::fmt;
+
+use bincode;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json;
+
+use BoxError;
+
+/// Payload types that can be sent across process boundaries or persisted, i.e. published with
+/// EventPublisher::publish_serialized. Implemented for every type that is Serialize and DeserializeOwned.
+pub trait SerializableEvent: Serialize + DeserializeOwned {}
+
+impl<T> SerializableEvent for T where T: Serialize + DeserializeOwned {}
+
+/// Wire format events are encoded in. JsonCodec and BincodeCodec are provided; implement it for other formats.
+pub trait Codec: Send + Sync {
+    /// Encodes a value.
+    /// INPUT:  value: &T   value to encode, usually an &Event<E>.
+    /// OUTPUT: Result<Vec<u8>, CodecError>   the encoded bytes.
+    fn encode<T>(&self, value: &T) -> Result<Vec<u8>, CodecError> where T: Serialize;
+
+    /// Decodes a value encoded with encode.
+    /// INPUT:  bytes: &[u8]
+    /// OUTPUT: Result<T, CodecError>   the decoded value, or an error if bytes aren't a valid encoding of a T.
+    fn decode<T>(&self, bytes: &[u8]) -> Result<T, CodecError> where T: DeserializeOwned;
+}
+
+/// Codec encoding events as JSON, for readable logs and interop with other languages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JsonCodec;
+
+impl Codec for JsonCodec {
+    fn encode<T>(&self, value: &T) -> Result<Vec<u8>, CodecError> where T: Serialize {
+        serde_json::to_vec(value).map_err(CodecError::new)
+    }
+
+    fn decode<T>(&self, bytes: &[u8]) -> Result<T, CodecError> where T: DeserializeOwned {
+        serde_json::from_slice(bytes).map_err(CodecError::new)
+    }
+}
+
+/// Codec encoding events with bincode, compact and fast but only readable by this crate's Rust types.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BincodeCodec;
+
+impl Codec for BincodeCodec {
+    fn encode<T>(&self, value: &T) -> Result<Vec<u8>, CodecError> where T: Serialize {
+        bincode::serialize(value).map_err(CodecError::new)
+    }
+
+    fn decode<T>(&self, bytes: &[u8]) -> Result<T, CodecError> where T: DeserializeOwned {
+        bincode::deserialize(bytes).map_err(CodecError::new)
+    }
+}
+
+/// Error returned by a Codec when a value could not be encoded or decoded.
+#[derive(Debug)]
+pub struct CodecError {
+    pub error: BoxError,
+}
+
+impl CodecError {
+    /// Codec error constructor, for Codec implementations.
+    /// INPUT:  error: Into<BoxError>   error of the underlying format.
+    pub fn new<T>(error: T) -> CodecError where T: Into<BoxError> {
+        CodecError { error: error.into() }
+    }
+}
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "failed to encode or decode event: {}", self.error)
+    }
+}
+
+impl Error for CodecError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&*self.error)
+    }
+}
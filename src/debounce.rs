@@ -0,0 +1,98 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use sync;
+use time::Instant;
+use Event;
+
+struct Debounce<E, F> {
+    delay: Duration,
+    handler: F,
+    pending: Mutex<Pending<E>>,
+    changed: Condvar,
+}
+
+struct Pending<E> {
+    latest: Option<Event<E>>,
+    deadline: Instant,
+    // Whether the timer thread has been started.
+    started: bool,
+    // Set once the subscription has been dropped. The timer thread delivers the burst still pending, if any, and ends.
+    closed: bool,
+}
+
+// Owned by the subscribed closure, so the timer thread learns when the subscription is gone.
+struct Subscribed<E, F>(Arc<Debounce<E, F>>);
+
+impl<E, F> Drop for Subscribed<E, F> {
+    fn drop(&mut self) {
+        sync::lock(&self.0.pending).closed = true;
+        self.0.changed.notify_all();
+    }
+}
+
+// Wraps handler so it is called with the last event of every burst, once no event arrived for delay. A single timer thread,
+// started with the first event, waits for the bursts for as long as the subscription lives.
+pub(crate) fn debounce<E, F>(delay: Duration, handler: F) -> impl Fn(&Event<E>) + Send + Sync + 'static where E: Clone + Send + 'static, F: Fn(&Event<E>) + Send + Sync + 'static {
+    let subscribed = Subscribed(Arc::new(Debounce {
+        delay,
+        handler,
+        pending: Mutex::new(Pending { latest: None, deadline: Instant::now(), started: false, closed: false }),
+        changed: Condvar::new(),
+    }));
+    move |event: &Event<E>| {
+        let debounce = &subscribed.0;
+        let mut pending = sync::lock(&debounce.pending);
+        let idle = pending.latest.is_none();
+        pending.latest = Some(event.clone());
+        pending.deadline = Instant::now() + debounce.delay;
+        if !pending.started {
+            let timer = debounce.clone();
+            let spawned = thread::Builder::new()
+                .name(String::from("event-debounce"))
+                .spawn(move || timer.run());
+            if spawned.is_err() {
+                // Delivered straight away rather than never; the next event tries to start the thread again.
+                let latest = pending.latest.take();
+                drop(pending);
+                if let Some(event) = latest {
+                    (debounce.handler)(&event);
+                }
+                return;
+            }
+            pending.started = true;
+        } else if idle {
+            debounce.changed.notify_all();
+        }
+    }
+}
+
+impl<E, F> Debounce<E, F> where F: Fn(&Event<E>) {
+    fn run(&self) {
+        let mut pending = sync::lock(&self.pending);
+        loop {
+            if pending.latest.is_none() {
+                if pending.closed {
+                    return;
+                }
+                pending = sync::wait(&self.changed, pending);
+                continue;
+            }
+            let now = Instant::now();
+            if now < pending.deadline {
+                let timeout = pending.deadline - now;
+                pending = sync::wait_timeout(&self.changed, pending, timeout);
+                continue;
+            }
+            let latest = pending.latest.take();
+            drop(pending);
+            if let Some(event) = latest {
+                // Caught so the thread lives on for the next burst.
+                let _ = panic::catch_unwind(AssertUnwindSafe(|| (self.handler)(&event)));
+            }
+            pending = sync::lock(&self.pending);
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use error::PublishError;
+use queue::EventQueue;
+use {CancellationToken, Event, EventPublisher, Shutdown};
+
+/// Handle of a dispatcher thread started with EventPublisher::spawn_dispatcher. Publishing through it queues the event and
+/// returns straight away; the dispatcher thread publishes the events on the publisher in the order they were queued,
+/// except that events queued with publish_with_priority go ahead of the ones of a lower priority.
+/// Dropping the handle shuts the dispatcher down like shutdown does.
+pub struct Dispatcher<E> {
+    queue: Arc<EventQueue<E>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl<E> Dispatcher<E> where E: Send + 'static {
+    pub(crate) fn spawn(publisher: Arc<EventPublisher<E>>, queue: EventQueue<E>) -> Dispatcher<E> {
+        let queue = Arc::new(queue);
+        let events = queue.clone();
+        let thread = thread::Builder::new()
+            .name(String::from("event-dispatcher"))
+            .spawn(move || {
+                // Ends once the queue is closed and drained.
+                while let Some(event) = events.pop_blocking() {
+                    publisher.publish_event(&event);
+                }
+            })
+            .expect("failed to spawn the event dispatcher thread");
+        Dispatcher { queue, thread: Some(thread) }
+    }
+
+    /// Shuts the dispatcher down once token is cancelled: from then on publishing is refused, and the events already queued
+    ///     are delivered or discarded as shutdown says, after which the dispatcher thread ends. Does so straight away if
+    ///     the token is already cancelled. shutdown_with or dropping the handle waits for the thread to end.
+    /// INPUT:  token: &CancellationToken
+    ///         shutdown: Shutdown   what to do with the queued events.
+    /// OUTPUT: void
+    pub fn cancel_on(&self, token: &CancellationToken, shutdown: Shutdown) {
+        let queue = Arc::downgrade(&self.queue);
+        token.on_cancel(move || {
+            if let Some(queue) = queue.upgrade() {
+                queue.close(shutdown);
+            }
+        });
+    }
+}
+
+impl<E> Dispatcher<E> {
+    /// Queues an event for the dispatcher thread without waiting for the handlers. With a bounded queue, waits for room
+    ///     when the queue is full and its backpressure is Block; handlers publishing through their own dispatcher would wait forever.
+    /// INPUT:  event: Event<E>
+    /// OUTPUT: Result<(), PublishError<E>>   Err(PublishError::Full) with the event if the queue is full and
+    ///     its backpressure is Reject, Err(PublishError::Closed) with the event if the dispatcher was shut down through a
+    ///     CancellationToken.
+    pub fn publish_event(&self, event: Event<E>) -> Result<(), PublishError<E>> {
+        self.queue.push(event)
+    }
+
+    /// Queues an event for the dispatcher thread ahead of the queued events of a lower priority, see
+    ///     QueuedPublisher::publish_with_priority. publish_event queues with priority 0.
+    /// INPUT:  event: Event<E>
+    ///         priority: u8   events with a higher priority are dispatched first.
+    /// OUTPUT: Result<(), PublishError<E>>   Err(PublishError::Full) with the event if the queue is full and
+    ///     its backpressure is Reject, Err(PublishError::Closed) with the event if the dispatcher was shut down through a
+    ///     CancellationToken.
+    pub fn publish_with_priority(&self, event: Event<E>, priority: u8) -> Result<(), PublishError<E>> {
+        self.queue.push_with_priority(event, priority)
+    }
+
+    /// Queues an event for the dispatcher thread that is dropped instead if the thread hasn't got to it within ttl, see
+    ///     QueuedPublisher::publish_with_ttl.
+    /// INPUT:  event: Event<E>
+    ///         ttl: Duration   how long the event stays deliverable.
+    /// OUTPUT: Result<(), PublishError<E>>   Err(PublishError::Full) with the event if the queue is full and
+    ///     its backpressure is Reject, Err(PublishError::Closed) with the event if the dispatcher was shut down through a
+    ///     CancellationToken.
+    pub fn publish_with_ttl(&self, event: Event<E>, ttl: Duration) -> Result<(), PublishError<E>> {
+        self.queue.push_with_ttl(event, ttl)
+    }
+
+    /// Number of events waiting for the dispatcher thread, including expired ones not dropped yet.
+    pub fn pending(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Stops the dispatcher once the events already queued have been delivered, and waits for that to happen.
+    /// OUTPUT: void
+    pub fn shutdown(mut self) {
+        self.stop(Shutdown::Drain);
+    }
+
+    /// Stops the dispatcher, delivering or discarding the events already queued as shutdown says, and waits for the
+    ///     dispatcher thread to end. An event being delivered meanwhile is delivered in full.
+    /// INPUT:  shutdown: Shutdown
+    /// OUTPUT: usize   number of queued events discarded, including the ones dropped because their time-to-live passed and the
+    ///     ones discarded when a token passed to cancel_on was cancelled.
+    pub fn shutdown_with(mut self, shutdown: Shutdown) -> usize {
+        self.stop(shutdown)
+    }
+
+    // Counted once the thread has ended, as events draining meanwhile may still expire.
+    fn stop(&mut self, shutdown: Shutdown) -> usize {
+        self.queue.close(shutdown);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        self.queue.discarded()
+    }
+}
+
+impl<E> Drop for Dispatcher<E> {
+    fn drop(&mut self) {
+        self.stop(Shutdown::Drain);
+    }
+}
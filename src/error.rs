@@ -0,0 +1,91 @@
+use std::error::Error;
+use std::fmt;
+
+use {Event, SubscriptionId};
+
+/// Error returned by the EventPublisher subscribe functions when a handler could not be subscribed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubscribeError {
+    /// The publisher already holds the maximum number of subscriptions it was configured with.
+    Full,
+}
+
+impl fmt::Display for SubscribeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SubscribeError::Full => write!(f, "publisher is at its subscriber limit"),
+        }
+    }
+}
+
+impl Error for SubscribeError {}
+
+/// Error returned by a handler subscribed with EventPublisher::subscribe_fallible.
+pub type BoxError = Box<dyn Error + Send + Sync + 'static>;
+
+/// Error one fallible handler returned during EventPublisher::publish_event_fallible.
+#[derive(Debug)]
+pub struct HandlerError {
+    pub subscription: SubscriptionId,
+    pub error: BoxError,
+}
+
+impl fmt::Display for HandlerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "handler {:?} failed: {}", self.subscription, self.error)
+    }
+}
+
+impl Error for HandlerError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&*self.error)
+    }
+}
+
+/// What EventPublisher::publish_event_fallible does when a handler returns an error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorPolicy {
+    /// Deliver the event to every handler and collect all errors.
+    CollectAll,
+    /// Stop delivering the event after the first error.
+    StopAtFirst,
+}
+
+/// Error returned when a queued publisher or dispatcher refuses an event. Hands the event back, so the caller may retry
+/// later or elsewhere.
+pub enum PublishError<E> {
+    /// The bounded queue is full and its backpressure is Backpressure::Reject. Publishing may succeed once it has drained.
+    Full(Event<E>),
+    /// The dispatcher was shut down through a CancellationToken; it refuses every event from now on.
+    Closed(Event<E>),
+}
+
+impl<E> PublishError<E> {
+    /// The refused event.
+    pub fn into_event(self) -> Event<E> {
+        match self {
+            PublishError::Full(event) | PublishError::Closed(event) => event,
+        }
+    }
+}
+
+// Event payloads need not be Debug.
+impl<E> fmt::Debug for PublishError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PublishError::Full(_) => f.debug_tuple("Full").finish_non_exhaustive(),
+            PublishError::Closed(_) => f.debug_tuple("Closed").finish_non_exhaustive(),
+        }
+    }
+}
+
+impl<E> fmt::Display for PublishError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PublishError::Full(_) => write!(f, "event queue is full"),
+            PublishError::Closed(_) => write!(f, "event queue is closed"),
+        }
+    }
+}
+
+impl<E> Error for PublishError<E> {}
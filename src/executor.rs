@@ -0,0 +1,112 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::{Mutex, OnceLock};
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::mpsc::{self, Receiver};
+#[cfg(not(target_arch = "wasm32"))]
+use std::thread;
+
+#[cfg(not(target_arch = "wasm32"))]
+use sync;
+
+/// Call of a handler handed to an Executor, to be run once on whichever thread the executor picks.
+pub type Task = Box<dyn FnOnce() + Send + 'static>;
+
+/// Runs the handler calls of subscriptions made with EventPublisher::subscribe_on and RunOn::Executor, e.g. by posting them
+/// to the event loop of a UI thread. Closures taking a Task are executors.
+pub trait Executor: Send + Sync {
+    fn execute(&self, task: Task);
+}
+
+impl<F> Executor for F where F: Fn(Task) + Send + Sync {
+    fn execute(&self, task: Task) {
+        self(task)
+    }
+}
+
+/// Where the handlers subscribed with EventPublisher::subscribe_on are called.
+#[derive(Clone)]
+pub enum RunOn {
+    /// On the publishing thread, as for subscribe_handler.
+    Inline,
+    /// On a pool of worker threads shared by every publisher, one per available CPU. Calls may run concurrently and in any
+    /// order.
+    #[cfg(not(target_arch = "wasm32"))]
+    Pool,
+    /// On a thread of its own, started when the handler is subscribed and stopping once it is unsubscribed. Calls are made one
+    /// at a time, in the order the events were published.
+    #[cfg(not(target_arch = "wasm32"))]
+    Dedicated,
+    /// Wherever the given executor runs them.
+    Executor(Arc<dyn Executor>),
+}
+
+// Hands the handler calls of a subscription to where it runs. None for RunOn::Inline, which needs no task.
+pub(crate) fn submitter(run_on: RunOn) -> Option<Box<dyn Fn(Task) + Send + Sync>> {
+    match run_on {
+        RunOn::Inline => None,
+        #[cfg(not(target_arch = "wasm32"))]
+        RunOn::Pool => Some(Box::new(|task| shared_pool().execute(task))),
+        #[cfg(not(target_arch = "wasm32"))]
+        RunOn::Dedicated => {
+            let (sender, receiver) = mpsc::channel();
+            thread::Builder::new()
+                .name(String::from("event-handler"))
+                .spawn(move || run_tasks(&Mutex::new(receiver)))
+                .expect("failed to spawn handler thread");
+            // The thread stops once the subscription, and with it the sender, is dropped.
+            Some(Box::new(move |task| {
+                let _ = sender.send(task);
+            }))
+        },
+        RunOn::Executor(executor) => Some(Box::new(move |task| executor.execute(task))),
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn run_tasks(receiver: &Mutex<Receiver<Task>>) {
+    loop {
+        // Only held while waiting, so the other workers of the pool take the next tasks while this one runs.
+        let task = sync::lock(receiver).recv();
+        match task {
+            // A panicking handler mustn't take down the thread.
+            Ok(task) => {
+                let _ = panic::catch_unwind(AssertUnwindSafe(task));
+            },
+            Err(_) => return,
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+struct WorkerPool {
+    sender: Mutex<mpsc::Sender<Task>>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl WorkerPool {
+    fn new(workers: usize) -> WorkerPool {
+        let (sender, receiver) = mpsc::channel();
+        let receiver = Arc::new(Mutex::new(receiver));
+        for _ in 0..workers {
+            let receiver = receiver.clone();
+            thread::Builder::new()
+                .name(String::from("event-worker"))
+                .spawn(move || run_tasks(&receiver))
+                .expect("failed to spawn worker thread");
+        }
+        WorkerPool { sender: Mutex::new(sender) }
+    }
+
+    fn execute(&self, task: Task) {
+        let _ = sync::lock(&self.sender).send(task);
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn shared_pool() -> &'static WorkerPool {
+    static POOL: OnceLock<WorkerPool> = OnceLock::new();
+    POOL.get_or_init(|| WorkerPool::new(thread::available_parallelism().map(|workers| workers.get()).unwrap_or(4)))
+}
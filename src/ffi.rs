@@ -0,0 +1,113 @@
+// C interface, for C and C++ plugins subscribing to a publisher of the host. Payloads are passed as bytes, so the
+// publisher seen from C is an EventPublisher<Vec<u8>>: the host creates it with events_publisher_new, or hands out a pointer
+// to one of its own, and encodes its events e.g. with a Codec. The C declarations are:
+//
+//     typedef struct EventPublisher EventPublisher;
+//     typedef void (*events_callback)(const uint8_t *data, size_t len, void *user_data);
+//
+//     EventPublisher *events_publisher_new(void);
+//     void events_publisher_free(EventPublisher *publisher);
+//     uint64_t events_subscribe(const EventPublisher *publisher, events_callback callback, void *user_data);
+//     bool events_unsubscribe(const EventPublisher *publisher, uint64_t subscription);
+//     void events_publish(const EventPublisher *publisher, const uint8_t *data, size_t len);
+
+use std::os::raw::c_void;
+use std::ptr;
+use std::slice;
+
+use {Event, EventPublisher, SubscriptionId};
+
+/// Callback subscribed with events_subscribe. Called with the payload of every published event, which is only valid
+/// during the call, and the user_data it was subscribed with. Event::Missing is passed as a null data pointer.
+pub type EventsCallback = extern "C" fn(data: *const u8, len: usize, user_data: *mut c_void);
+
+// The user data is only ever handed back to the callback. Whoever subscribes the callback is responsible for it being
+// safe to call from any thread that publishes.
+struct UserData(*mut c_void);
+
+// SAFETY: the pointer is never dereferenced on the Rust side, only passed back to the callback. events_subscribe requires
+// the caller to make user_data safe to send to, and use from, every thread publishing on the publisher.
+unsafe impl Send for UserData {}
+// SAFETY: as for Send; concurrent publishes may hand the same user_data to the callback on several threads at once, which
+// events_subscribe requires the caller to allow for.
+unsafe impl Sync for UserData {}
+
+/// Creates a publisher for events_subscribe and events_publish, to be freed with events_publisher_free.
+/// OUTPUT: *mut EventPublisher<Vec<u8>>
+#[no_mangle]
+pub extern "C" fn events_publisher_new() -> *mut EventPublisher<Vec<u8>> {
+    Box::into_raw(Box::new(EventPublisher::new()))
+}
+
+/// Frees a publisher created with events_publisher_new. Does nothing if publisher is null.
+/// INPUT:  publisher: *mut EventPublisher<Vec<u8>>   must not be used afterwards.
+///
+/// # Safety
+/// publisher must be null or returned by events_publisher_new and not freed yet, and no other thread may be using it.
+#[no_mangle]
+pub unsafe extern "C" fn events_publisher_free(publisher: *mut EventPublisher<Vec<u8>>) {
+    if !publisher.is_null() {
+        drop(Box::from_raw(publisher));
+    }
+}
+
+/// Subscribes a C callback to a publisher.
+/// INPUT:  publisher: *const EventPublisher<Vec<u8>>
+///         callback: EventsCallback   called with the payload of every published event.
+///         user_data: *mut c_void   passed to every call of callback, e.g. the plugin's state.
+/// OUTPUT: u64   id of the subscription for events_unsubscribe, or 0 if the publisher is null or at its subscriber limit.
+///
+/// # Safety
+/// publisher must be null or point to a live publisher. callback and user_data must stay valid until the subscription is
+/// removed or the publisher is freed, and must be safe to use from every thread publishing on the publisher.
+#[no_mangle]
+pub unsafe extern "C" fn events_subscribe(publisher: *const EventPublisher<Vec<u8>>, callback: EventsCallback, user_data: *mut c_void) -> u64 {
+    let publisher = match publisher.as_ref() {
+        Some(publisher) => publisher,
+        None => return 0,
+    };
+    let user_data = UserData(user_data);
+    let subscribed = publisher.subscribe_handler(Box::new(move |event: &Event<Vec<u8>>| {
+        match *event {
+            Event::Args(ref payload) => callback(payload.as_ptr(), payload.len(), user_data.0),
+            Event::Missing => callback(ptr::null(), 0, user_data.0),
+        }
+    }));
+    subscribed.map(|SubscriptionId(id)| id).unwrap_or(0)
+}
+
+/// Removes a subscription made with events_subscribe.
+/// INPUT:  publisher: *const EventPublisher<Vec<u8>>
+///         subscription: u64   id returned by events_subscribe.
+/// OUTPUT: bool   whether the subscription existed.
+///
+/// # Safety
+/// publisher must be null or point to a live publisher.
+#[no_mangle]
+pub unsafe extern "C" fn events_unsubscribe(publisher: *const EventPublisher<Vec<u8>>, subscription: u64) -> bool {
+    match publisher.as_ref() {
+        Some(publisher) => publisher.unsubscribe(SubscriptionId(subscription)),
+        None => false,
+    }
+}
+
+/// Publishes an event with a copy of the payload, as publish_event does. A null data pointer publishes Event::Missing.
+///     Does nothing if publisher is null.
+/// INPUT:  publisher: *const EventPublisher<Vec<u8>>
+///         data: *const u8   payload of the event.
+///         len: usize   length of the payload in bytes.
+///
+/// # Safety
+/// publisher must be null or point to a live publisher, and data must be null or point to len readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn events_publish(publisher: *const EventPublisher<Vec<u8>>, data: *const u8, len: usize) {
+    let publisher = match publisher.as_ref() {
+        Some(publisher) => publisher,
+        None => return,
+    };
+    if data.is_null() {
+        publisher.publish_event(&Event::Missing);
+    } else {
+        publisher.publish_event(&Event::Args(slice::from_raw_parts(data, len).to_vec()));
+    }
+}
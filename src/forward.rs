@@ -0,0 +1,34 @@
+use std::cell::RefCell;
+
+// Publishers an event has passed through on this thread while being forwarded, identified by the address of their
+// registry. Forwarding stays on the publishing thread, so a loop of forwards always shows up here.
+thread_local! {
+    static VISITED: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+// Calls publish unless the event already passed through target on its way here.
+pub(crate) fn forward<F>(source: usize, target: usize, publish: F) where F: FnOnce() {
+    let depth = VISITED.with(|visited| {
+        let mut visited = visited.borrow_mut();
+        if source == target || visited.contains(&target) {
+            return None;
+        }
+        let depth = visited.len();
+        visited.push(source);
+        visited.push(target);
+        Some(depth)
+    });
+    if let Some(depth) = depth {
+        let _restore = Restore(depth);
+        publish();
+    }
+}
+
+// Pops what forward pushed, also when publish unwinds.
+struct Restore(usize);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        VISITED.with(|visited| visited.borrow_mut().truncate(self.0));
+    }
+}
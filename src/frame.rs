@@ -0,0 +1,31 @@
+use std::io::{self, Read, Write};
+
+// Frames longer than this are taken for a corrupt stream rather than allocated.
+const MAX_FRAME_LEN: usize = 64 * 1024 * 1024;
+
+// Frames are the length of the encoded event as a big-endian u32, followed by the encoded event.
+pub(crate) fn write_frame<W>(writer: &mut W, frame: &[u8]) -> io::Result<()> where W: Write {
+    if frame.len() > MAX_FRAME_LEN {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "encoded event is too large for a frame"));
+    }
+    writer.write_all(&(frame.len() as u32).to_be_bytes())?;
+    writer.write_all(frame)?;
+    writer.flush()
+}
+
+// Ok(None) if the stream ended cleanly between two frames.
+pub(crate) fn read_frame<R>(reader: &mut R) -> io::Result<Option<Vec<u8>>> where R: Read {
+    let mut len = [0; 4];
+    match reader.read_exact(&mut len) {
+        Ok(()) => {},
+        Err(ref error) if error.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(error) => return Err(error),
+    }
+    let len = u32::from_be_bytes(len) as usize;
+    if len > MAX_FRAME_LEN {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "frame length exceeds the limit"));
+    }
+    let mut frame = vec![0; len];
+    reader.read_exact(&mut frame)?;
+    Ok(Some(frame))
+}
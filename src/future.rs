@@ -0,0 +1,92 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+use sync;
+use CancellationToken;
+
+/// Future returned by a handler subscribed with EventPublisher::subscribe_async, boxed.
+pub type BoxFuture = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+
+/// Future returned by EventPublisher::publish_event_async and publish_event_concurrent. Completes once the futures of all
+/// async handlers have completed; they are polled concurrently, on the task awaiting this future. A panic in one of them is
+/// not caught.
+#[must_use = "async handlers only make progress while the future is polled"]
+pub struct PublishFuture {
+    pending: Vec<Option<BoxFuture>>,
+    // Number of futures polled at once: the earliest ones that haven't completed yet.
+    max_in_flight: usize,
+    cancellation: Option<Cancellation>,
+}
+
+struct Cancellation {
+    token: CancellationToken,
+    // Task to wake once the token is cancelled, registered with the token on the first poll.
+    waker: Option<Arc<Mutex<Option<Waker>>>>,
+}
+
+impl PublishFuture {
+    pub(crate) fn new(futures: Vec<BoxFuture>) -> PublishFuture {
+        PublishFuture { pending: futures.into_iter().map(Some).collect(), max_in_flight: usize::MAX, cancellation: None }
+    }
+
+    pub(crate) fn with_max_in_flight(mut self, max_in_flight: usize) -> PublishFuture {
+        self.max_in_flight = max_in_flight;
+        self
+    }
+
+    /// Makes the future complete as soon as token is cancelled, dropping the futures of the async handlers that haven't
+    ///     completed yet, so a shutdown doesn't have to wait for slow handlers.
+    /// INPUT:  token: &CancellationToken
+    /// OUTPUT: PublishFuture
+    pub fn cancel_on(mut self, token: &CancellationToken) -> PublishFuture {
+        self.cancellation = Some(Cancellation { token: token.clone(), waker: None });
+        self
+    }
+}
+
+impl Future for PublishFuture {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, context: &mut Context) -> Poll<()> {
+        if let Some(ref mut cancellation) = self.cancellation {
+            if cancellation.token.is_cancelled() {
+                self.pending.clear();
+                return Poll::Ready(());
+            }
+            match cancellation.waker {
+                Some(ref waker) => *sync::lock(waker) = Some(context.waker().clone()),
+                None => {
+                    let waker = Arc::new(Mutex::new(Some(context.waker().clone())));
+                    let wake = waker.clone();
+                    cancellation.token.on_cancel(move || {
+                        if let Some(waker) = sync::lock(&wake).take() {
+                            waker.wake();
+                        }
+                    });
+                    cancellation.waker = Some(waker);
+                },
+            }
+        }
+
+        // A future completing frees its place for the next one, which is polled straight away.
+        let max_in_flight = self.max_in_flight;
+        let mut in_flight = 0;
+        for slot in self.pending.iter_mut() {
+            if in_flight == max_in_flight {
+                break;
+            }
+            let finished = match *slot {
+                Some(ref mut future) => future.as_mut().poll(context).is_ready(),
+                None => continue,
+            };
+            if finished {
+                *slot = None;
+            } else {
+                in_flight += 1;
+            }
+        }
+        if in_flight == 0 { Poll::Ready(()) } else { Poll::Pending }
+    }
+}
@@ -0,0 +1,34 @@
+use std::any::Any;
+
+use {Event, SubscriptionId};
+
+/// Handler of the events of an EventPublisher, for subscribing your own types with EventPublisher::subscribe. Closures taking
+/// a reference to the event implement it too. Handlers are shared by every thread publishing to the publisher.
+pub trait EventHandler<E>: Send + Sync {
+    fn handle(&self, event: &Event<E>);
+}
+
+impl<E, F> EventHandler<E> for F where F: Fn(&Event<E>) + Send + Sync {
+    fn handle(&self, event: &Event<E>) {
+        self(event)
+    }
+}
+
+/// Panic raised by a handler while an event was delivered to it. The publisher catches it, carries on with the other handlers
+/// and hands it to the panic hook set with EventPublisher::set_panic_hook.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandlerPanic {
+    pub subscription: SubscriptionId,
+    /// Message the handler panicked with, if it was a string.
+    pub message: Option<String>,
+}
+
+impl HandlerPanic {
+    pub(crate) fn new(subscription: SubscriptionId, payload: Box<dyn Any + Send>) -> HandlerPanic {
+        let message = match payload.downcast::<String>() {
+            Ok(message) => Some(*message),
+            Err(payload) => payload.downcast_ref::<&str>().map(|message| message.to_string()),
+        };
+        HandlerPanic { subscription, message }
+    }
+}
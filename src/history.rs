@@ -0,0 +1,100 @@
+use std::cmp;
+use std::collections::VecDeque;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use sync;
+use time::{Instant, SystemTime};
+use {Event, EventEnvelope};
+
+/// Event published on an EventPublisher, as kept by an EventHistory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedEvent<E> {
+    pub sequence: u64,
+    pub published_at: SystemTime,
+    pub source: Option<String>,
+    pub event: Event<E>,
+}
+
+/// Ring buffer of the last events published on a publisher, so subscribers that come late or reconnect can catch up on what
+/// they missed. Keeps a clone of every event; once capacity events are held, each new one pushes out the oldest. A history
+/// constructed with with_ttl also drops events once they are older than its time-to-live.
+/// Subscribe it to a publisher with EventPublisher::subscribe_history.
+pub struct EventHistory<E> {
+    capacity: usize,
+    ttl: Option<Duration>,
+    // With the time each event was recorded at, for the time-to-live.
+    events: Mutex<VecDeque<(Instant, RecordedEvent<E>)>>,
+}
+
+impl<E> EventHistory<E> where E: Clone {
+    /// Event history constructor.
+    /// INPUT:  capacity: usize   number of events kept. A capacity of 0 is treated as 1.
+    pub fn new(capacity: usize) -> EventHistory<E> {
+        let capacity = cmp::max(capacity, 1);
+        EventHistory { capacity, ttl: None, events: Mutex::new(VecDeque::with_capacity(capacity)) }
+    }
+
+    /// Event history constructor for a history that forgets events once they are older than ttl, so catching up doesn't
+    ///     hand out stale data.
+    /// INPUT:  capacity: usize   number of events kept. A capacity of 0 is treated as 1.
+    ///         ttl: Duration   how long an event is kept after it was recorded.
+    pub fn with_ttl(capacity: usize, ttl: Duration) -> EventHistory<E> {
+        EventHistory { ttl: Some(ttl), ..EventHistory::new(capacity) }
+    }
+
+    pub(crate) fn record(&self, envelope: &EventEnvelope<E>) {
+        let now = Instant::now();
+        let mut events = self.unexpired(now);
+        if events.len() == self.capacity {
+            events.pop_front();
+        }
+        // Concurrent publishes may finish out of order; keep the buffer sorted by sequence.
+        let position = events.iter().rposition(|(_, recorded)| recorded.sequence < envelope.sequence).map_or(0, |position| position + 1);
+        events.insert(position, (now, RecordedEvent {
+            sequence: envelope.sequence,
+            published_at: envelope.published_at,
+            source: envelope.source.map(String::from),
+            event: envelope.event.clone(),
+        }));
+    }
+
+    // Locks the events after dropping the expired ones.
+    fn unexpired<'a>(&'a self, now: Instant) -> MutexGuard<'a, VecDeque<(Instant, RecordedEvent<E>)>> {
+        let mut events = sync::lock(&self.events);
+        if let Some(ttl) = self.ttl {
+            events.retain(|&(recorded_at, _)| now.duration_since(recorded_at) < ttl);
+        }
+        events
+    }
+
+    /// Calls a handler with every event in the history, oldest first. The history is not locked while the handler runs.
+    /// INPUT:  handler: Fn(&EventEnvelope<E>)   handler is called with each recorded event as it was published.
+    /// OUTPUT: void
+    pub fn replay_to<F>(&self, handler: F) where F: Fn(&EventEnvelope<E>) {
+        for recorded in self.replay_since(0) {
+            handler(&EventEnvelope { sequence: recorded.sequence, published_at: recorded.published_at, source: recorded.source.as_deref(), event: &recorded.event });
+        }
+    }
+
+    /// Events in the history published after a given sequence number, oldest first. Pass the last sequence number a
+    ///     subscriber saw to get what it missed; if the history has been overwritten meanwhile, the first returned sequence
+    ///     number is more than one above it.
+    /// INPUT:  sequence: u64   sequence number of the last event already seen, or 0 for the whole history.
+    /// OUTPUT: Vec<RecordedEvent<E>>
+    pub fn replay_since(&self, sequence: u64) -> Vec<RecordedEvent<E>> {
+        self.unexpired(Instant::now()).iter()
+            .filter(|(_, recorded)| recorded.sequence > sequence)
+            .map(|(_, recorded)| recorded.clone())
+            .collect()
+    }
+
+    /// Number of events in the history.
+    pub fn len(&self) -> usize {
+        self.unexpired(Instant::now()).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
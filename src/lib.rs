@@ -1,21 +1,694 @@
 #![allow(dead_code)]
 
-use std::rc::Rc;
+#[cfg(feature = "serde")]
+extern crate bincode;
+#[cfg(feature = "async")]
+extern crate futures_core;
+#[cfg(feature = "derive")]
+extern crate rust_events_derive;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "serde")]
+extern crate serde_json;
+#[cfg(feature = "tracing")]
+extern crate tracing;
+#[cfg(all(feature = "async", target_arch = "wasm32", target_os = "unknown"))]
+extern crate wasm_bindgen_futures;
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+extern crate web_time;
+
+use std::any::Any;
+use std::sync::{Arc, Mutex, RwLock, Weak};
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::Condvar;
+use std::sync::mpsc::{self, Sender};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::borrow::Borrow;
+use std::cell::RefCell;
+use std::cmp;
+use std::mem;
 use std::collections::BTreeMap;
+#[cfg(all(feature = "serde", not(target_arch = "wasm32")))]
+use std::io;
+#[cfg(feature = "async")]
+use std::future::Future;
+use std::ops::{ControlFlow, Deref};
+use std::panic::{self, AssertUnwindSafe};
+use std::thread;
+use std::time::Duration;
+
+mod any;
+mod audit;
+mod batch;
+mod borrowed;
+mod builder;
+mod bus;
+mod cancel;
+mod chaos;
+mod coalesce;
+#[cfg(feature = "serde")]
+mod codec;
+#[cfg(not(target_arch = "wasm32"))]
+mod debounce;
+#[cfg(not(target_arch = "wasm32"))]
+mod dispatcher;
+mod error;
+mod executor;
+#[cfg(feature = "ffi")]
+mod ffi;
+mod forward;
+#[cfg(feature = "serde")]
+mod frame;
+#[cfg(feature = "async")]
+mod future;
+mod handler;
+mod history;
+mod local;
+mod metrics;
+mod ordered;
+mod pause;
+#[cfg(feature = "serde")]
+mod persist;
+mod queue;
+mod recipients;
+mod recording;
+#[cfg(all(feature = "serde", not(target_arch = "wasm32")))]
+mod remote;
+mod retry;
+#[cfg(not(target_arch = "wasm32"))]
+mod scheduler;
+#[cfg(feature = "serde")]
+mod schema;
+#[cfg(feature = "async")]
+mod stream;
+mod sync;
+mod time;
+mod timeout;
+mod topic;
+mod variant;
+
+pub use any::{AnyEventPublisher, AnyPayload};
+pub use audit::{AuditOperation, AuditRecord, AuditSink};
+pub use batch::BatchingSink;
+pub use borrowed::{BorrowedEventPublisher, BorrowedHandlerBox};
+pub use builder::EventPublisherBuilder;
+pub use bus::{global_bus, EventBus, EventTypeStats};
+pub use cancel::{CancellationToken, Shutdown};
+pub use chaos::ChaosConfig;
+pub use coalesce::CoalescingPublisher;
+#[cfg(feature = "serde")]
+pub use codec::{BincodeCodec, Codec, CodecError, JsonCodec, SerializableEvent};
+#[cfg(not(target_arch = "wasm32"))]
+pub use dispatcher::Dispatcher;
+pub use executor::{Executor, RunOn, Task};
+#[cfg(feature = "async")]
+pub use future::{BoxFuture, PublishFuture};
+#[cfg(feature = "ffi")]
+pub use ffi::{events_publish, events_publisher_free, events_publisher_new, events_subscribe, events_unsubscribe, EventsCallback};
+pub use error::{BoxError, ErrorPolicy, HandlerError, PublishError, SubscribeError};
+pub use handler::{EventHandler, HandlerPanic};
+pub use history::{EventHistory, RecordedEvent};
+pub use local::{LocalEventPublisher, LocalHandlerBox};
+pub use metrics::{HandlerMetrics, PublisherMetrics};
+pub use pause::WhilePaused;
+#[cfg(feature = "serde")]
+pub use persist::PersistentPublisher;
+pub use queue::{Backpressure, QueuedPublisher};
+pub use recording::{EventRecord, RecordingPublisher};
+#[cfg(all(feature = "serde", not(target_arch = "wasm32")))]
+pub use remote::{Endpoint, RemotePublisher, RemoteSubscriber};
+pub use retry::{Backoff, RetryPolicy};
+#[cfg(not(target_arch = "wasm32"))]
+pub use scheduler::{ScheduleHandle, Scheduler};
+#[cfg(feature = "serde")]
+pub use schema::Schema;
+#[cfg(feature = "async")]
+pub use stream::{EventStream, WaitFor};
+pub use timeout::{HandlerTimeout, TimeoutPolicy};
+pub use topic::TopicPublisher;
+pub use variant::{EventKind, EventVariant};
+#[cfg(feature = "derive")]
+pub use rust_events_derive::EventKind;
+
+use chaos::Chaos;
+use metrics::{CallStats, Metrics};
+use ordered::{InFlight, OrderedHandler, OrderedQueue};
+use pause::{PauseBuffer, Paused};
+#[cfg(not(target_arch = "wasm32"))]
+use queue::EventQueue;
+use recipients::Recipients;
+use sync::Exclusive;
+use time::{Instant, SystemTime};
 
 /// Enumerator of the Event type. Whatever type E of Event::Args you implement here is the type E that will be used for the EventPublisher.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Event<E> {
     Args(E),
     Missing,
 }
 
-// To deal with handler functions - F: Rc<Box<Fn(&event<E>)>>
+/// Envelope wrapping a published event together with data stamped on it by the publisher. Handed to handlers subscribed with
+/// EventPublisher::subscribe_envelope.
+pub struct EventEnvelope<'a, E: 'a> {
+    /// Per-publisher sequence number of the publish, starting at 1 and increasing by one with every published event, so handlers
+    ///     can detect gaps and duplicates or remember where they left off.
+    pub sequence: u64,
+    /// Time the event was published at.
+    pub published_at: SystemTime,
+    /// Source the publisher was given with EventPublisher::set_source, if any.
+    pub source: Option<&'a str>,
+    pub event: &'a Event<E>,
+}
+
+/// Context of a published event, handed mutably to handlers subscribed with EventPublisher::subscribe_cancellable.
+pub struct EventContext<'a, E: 'a> {
+    /// Sequence number of the publish, as in EventEnvelope.
+    pub sequence: u64,
+    pub event: &'a Event<E>,
+    stopped: &'a AtomicBool,
+}
+
+impl<'a, E> EventContext<'a, E> {
+    /// Stops the event from being delivered to the handlers after this one. The handler itself runs to completion.
+    pub fn stop_propagation(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether a handler has stopped the propagation of this event.
+    pub fn is_propagation_stopped(&self) -> bool {
+        self.stopped.load(Ordering::SeqCst)
+    }
+}
+
+/// Marker returned by handlers subscribed through EventPublisher::subscribe_until, as ControlFlow::Break(Unsubscribe),
+/// to remove themselves from the publisher once the current event has been delivered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Unsubscribe;
+
+/// Identifies a subscription on an EventPublisher. Returned by the subscribe functions and passed to
+/// EventPublisher::unsubscribe to remove the handler again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SubscriptionId(u64);
+
+/// Boxed event handler function, as accepted by EventPublisher::subscribe_handler.
+pub type HandlerBox<E> = Box<dyn Fn(&Event<E>) + Send + Sync + 'static>;
+// Handler functions are kept in an Arc of their own, shared with the subscriptions of forked publishers, so calling one is a
+//     single indirection from its entry.
+type HandlerFn<E> = Arc<dyn Fn(&Event<E>) + Send + Sync + 'static>;
+type UntilHandlerFn<E> = Arc<dyn Fn(&Event<E>) -> ControlFlow<Unsubscribe> + Send + Sync + 'static>;
+type OwnedHandlerFn<E> = Arc<dyn Fn(Arc<E>) + Send + Sync + 'static>;
+type EnvelopeHandlerFn<E> = Arc<dyn Fn(&EventEnvelope<E>) + Send + Sync + 'static>;
+type ContextHandlerFn<E> = Arc<dyn Fn(&mut EventContext<E>) + Send + Sync + 'static>;
+type FilterFn<E> = Arc<dyn Fn(&Event<E>) -> bool + Send + Sync + 'static>;
+type FallibleHandlerFn<E> = Arc<dyn Fn(&Event<E>) -> Result<(), BoxError> + Send + Sync + 'static>;
+type ResponderHandlerFn<E> = Arc<dyn Fn(&Event<E>) -> Box<dyn Any> + Send + Sync + 'static>;
+#[cfg(feature = "async")]
+type AsyncHandlerFn<E> = Arc<dyn Fn(&Event<E>) -> BoxFuture + Send + Sync + 'static>;
+type Interceptor<E> = dyn Fn(&Event<E>, &dyn Fn(&Event<E>)) + Send + Sync + 'static;
+type DeadEventHandler<E> = dyn Fn(&Event<E>) + Send + Sync + 'static;
+type DeadEventHandlerBox<E> = Box<DeadEventHandler<E>>;
+type PanicHook = dyn Fn(&HandlerPanic) + Send + Sync + 'static;
+type PanicHookBox = Box<PanicHook>;
+type TimeoutHook = dyn Fn(&HandlerTimeout) + Send + Sync + 'static;
+type TimeoutHookBox = Box<TimeoutHook>;
+type SubscriptionHookBox = Box<dyn Fn(SubscriptionId, usize) + Send + Sync + 'static>;
+type QuarantineHook = dyn Fn(SubscriptionId, u32) + Send + Sync + 'static;
+type QuarantineHookBox = Box<QuarantineHook>;
+type Handler<E> = Arc<Entry<E>>;
+
+enum HandlerKind<E> {
+    Plain(HandlerFn<E>),
+    Filtered(FilterFn<E>, HandlerFn<E>),
+    Until(UntilHandlerFn<E>),
+    Owned(OwnedHandlerFn<E>),
+    Envelope(EnvelopeHandlerFn<E>),
+    Context(ContextHandlerFn<E>),
+    Fallible(FallibleHandlerFn<E>),
+    Responder(ResponderHandlerFn<E>),
+    Shared(Arc<dyn EventHandler<E>>),
+    Ordered(Arc<dyn OrderedHandler<E>>),
+    #[cfg(feature = "async")]
+    Async(AsyncHandlerFn<E>),
+}
+
+impl<E> HandlerKind<E> {
+    // Same handler for the subscription of a forked publisher. Ordered handlers get a queue of their own, as the fork numbers
+    //     its events separately.
+    fn fork(&self) -> HandlerKind<E> {
+        match *self {
+            HandlerKind::Plain(ref handler) => HandlerKind::Plain(handler.clone()),
+            HandlerKind::Filtered(ref predicate, ref handler) => HandlerKind::Filtered(predicate.clone(), handler.clone()),
+            HandlerKind::Until(ref handler) => HandlerKind::Until(handler.clone()),
+            HandlerKind::Owned(ref handler) => HandlerKind::Owned(handler.clone()),
+            HandlerKind::Envelope(ref handler) => HandlerKind::Envelope(handler.clone()),
+            HandlerKind::Context(ref handler) => HandlerKind::Context(handler.clone()),
+            HandlerKind::Fallible(ref handler) => HandlerKind::Fallible(handler.clone()),
+            HandlerKind::Responder(ref handler) => HandlerKind::Responder(handler.clone()),
+            HandlerKind::Shared(ref handler) => HandlerKind::Shared(handler.clone()),
+            HandlerKind::Ordered(ref handler) => HandlerKind::Ordered(handler.fork()),
+            #[cfg(feature = "async")]
+            HandlerKind::Async(ref handler) => HandlerKind::Async(handler.clone()),
+        }
+    }
+}
+
+impl<E> HandlerKind<E> {
+    fn call(&self, envelope: &EventEnvelope<E>, stopped: &AtomicBool) -> ControlFlow<Unsubscribe> {
+        match *self {
+            HandlerKind::Plain(ref handler) => {
+                handler(envelope.event);
+                ControlFlow::Continue(())
+            },
+            // The predicate was checked when the publish took its snapshot, see accepts.
+            HandlerKind::Filtered(_, ref handler) => {
+                handler(envelope.event);
+                ControlFlow::Continue(())
+            },
+            HandlerKind::Until(ref handler) => handler(envelope.event),
+            HandlerKind::Shared(ref handler) => {
+                handler.handle(envelope.event);
+                ControlFlow::Continue(())
+            },
+            HandlerKind::Envelope(ref handler) => {
+                handler(envelope);
+                ControlFlow::Continue(())
+            },
+            HandlerKind::Context(ref handler) => {
+                handler(&mut EventContext { sequence: envelope.sequence, event: envelope.event, stopped });
+                ControlFlow::Continue(())
+            },
+            // Errors are only collected by publish_event_fallible, through call_fallible.
+            HandlerKind::Fallible(ref handler) => {
+                let _ = handler(envelope.event);
+                ControlFlow::Continue(())
+            },
+            // Replies are only collected by publish_and_collect, through call_responder.
+            HandlerKind::Responder(ref handler) => {
+                handler(envelope.event);
+                ControlFlow::Continue(())
+            },
+            // Only kept here; deliver_isolated calls the handler once it is the event's turn, see drain_ordered.
+            HandlerKind::Ordered(ref handler) => {
+                handler.push(envelope.sequence, envelope.event);
+                ControlFlow::Continue(())
+            },
+            // Only reachable through publish_owned.
+            HandlerKind::Owned(_) => ControlFlow::Continue(()),
+            // Only reachable through publish_event_async, through call_async.
+            #[cfg(feature = "async")]
+            HandlerKind::Async(_) => ControlFlow::Continue(()),
+        }
+    }
+
+    #[cfg(feature = "async")]
+    fn call_async(&self, event: &Event<E>) -> Option<BoxFuture> {
+        match *self {
+            HandlerKind::Async(ref handler) => Some(handler(event)),
+            _ => None,
+        }
+    }
+
+    fn call_fallible(&self, envelope: &EventEnvelope<E>, stopped: &AtomicBool) -> Result<ControlFlow<Unsubscribe>, BoxError> {
+        match *self {
+            HandlerKind::Fallible(ref handler) => handler(envelope.event).map(ControlFlow::Continue),
+            _ => Ok(self.call(envelope, stopped)),
+        }
+    }
+
+    fn call_responder(&self, event: &Event<E>) -> Option<Box<dyn Any>> {
+        match *self {
+            HandlerKind::Responder(ref handler) => Some(handler(event)),
+            _ => None,
+        }
+    }
+
+    fn call_owned(&self, args: &Arc<E>) -> ControlFlow<Unsubscribe> {
+        if let HandlerKind::Owned(ref handler) = *self {
+            handler(args.clone());
+        }
+        ControlFlow::Continue(())
+    }
+
+    // Whether the handler wants the event at all. Checked once per publish, when the snapshot is taken.
+    fn accepts(&self, event: &Event<E>) -> bool {
+        match *self {
+            HandlerKind::Filtered(ref predicate, _) => predicate(event),
+            _ => true,
+        }
+    }
+
+    fn is_owned(&self) -> bool {
+        matches!(*self, HandlerKind::Owned(_))
+    }
+
+    fn is_ordered(&self) -> bool {
+        matches!(*self, HandlerKind::Ordered(_))
+    }
+
+    // Whether the handler is called with a reference to the event by the blocking publish functions.
+    fn is_synchronous(&self) -> bool {
+        match *self {
+            HandlerKind::Owned(_) => false,
+            #[cfg(feature = "async")]
+            HandlerKind::Async(_) => false,
+            _ => true,
+        }
+    }
+}
+
+struct Entry<E> {
+    handler: HandlerKind<E>,
+    priority: i32,
+    group: Option<String>,
+    subscribed_at: Instant,
+    last_delivered: Mutex<Option<Instant>>,
+    stats: Mutex<CallStats>,
+    timeouts: AtomicU32,
+    // Failures since the handler last succeeded, see set_quarantine_threshold.
+    failures: AtomicU32,
+    quarantined: AtomicBool,
+}
+
+impl<E> Entry<E> {
+    fn new(handler: HandlerKind<E>, priority: i32, group: Option<String>) -> Entry<E> {
+        Entry { handler, priority, group, subscribed_at: Instant::now(), last_delivered: Mutex::new(None), stats: Mutex::new(CallStats::default()), timeouts: AtomicU32::new(0), failures: AtomicU32::new(0), quarantined: AtomicBool::new(false) }
+    }
+
+    fn info(&self, id: SubscriptionId) -> SubscriptionInfo {
+        SubscriptionInfo {
+            id,
+            priority: self.priority,
+            group: self.group.clone(),
+            subscribed_at: self.subscribed_at,
+            last_delivered: *sync::lock(&self.last_delivered),
+        }
+    }
+}
+
+/// Metadata of a subscription, as listed by EventPublisher::subscriptions and suspected_leaks and inspected by retain_subscriptions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubscriptionInfo {
+    pub id: SubscriptionId,
+    pub priority: i32,
+    /// Group the handler was subscribed to with subscribe_named, if any.
+    pub group: Option<String>,
+    pub subscribed_at: Instant,
+    /// Last delivery seen while leak detection was on, if any.
+    pub last_delivered: Option<Instant>,
+}
+
+// Subscriptions in dispatch order: descending priority, then subscription order. The list is copy-on-write: publishing
+// takes a clone of the Arc and never waits for a subscribe, while subscribing and unsubscribing change a copy whenever
+// a publish still holds the current list.
+type HandlerList<E> = Arc<Vec<(SubscriptionId, Handler<E>)>>;
+
+// State shared between a publisher and the Subscription guards handed out by subscribe_scoped.
+struct Registry<E> {
+    handlers: RwLock<HandlerList<E>>,
+    audit_sink: RwLock<Option<Box<dyn AuditSink>>>,
+    subscribe_hook: RwLock<Option<SubscriptionHookBox>>,
+    unsubscribe_hook: RwLock<Option<SubscriptionHookBox>>,
+}
+
+impl<E> Registry<E> {
+    fn new() -> Registry<E> {
+        Registry {
+            handlers: RwLock::new(Arc::new(Vec::new())),
+            audit_sink: RwLock::new(None),
+            subscribe_hook: RwLock::new(None),
+            unsubscribe_hook: RwLock::new(None),
+        }
+    }
+
+    // The read lock is only held for cloning the Arc.
+    fn load(&self) -> HandlerList<E> {
+        sync::read(&self.handlers).clone()
+    }
+
+    // Writers take turns through the write lock; publishes working on an older list keep it until they are done.
+    fn update<F, T>(&self, change: F) -> T where F: FnOnce(&mut Vec<(SubscriptionId, Handler<E>)>) -> T {
+        change(Arc::make_mut(&mut *sync::write(&self.handlers)))
+    }
+
+    fn remove(&self, id: SubscriptionId) -> bool {
+        self.remove_all(&[id]) == 1
+    }
+
+    // Removes the handlers in one change of the list, so no publish sees only some of them gone.
+    fn remove_all(&self, ids: &[SubscriptionId]) -> usize {
+        let (removed, count) = self.update(|handlers| {
+            let removed: Vec<bool> = ids.iter().map(|&id| {
+                let position = handlers.iter().position(|&(handler_id, _)| handler_id == id);
+                position.map(|position| handlers.remove(position)).is_some()
+            }).collect();
+            (removed, handlers.len())
+        });
+        let removed_count = removed.iter().filter(|&&removed| removed).count();
+        let mut remaining = removed_count;
+        for (&id, removed) in ids.iter().zip(removed) {
+            self.audit(AuditOperation::Unsubscribe { subscription: id, removed });
+            if removed {
+                remaining -= 1;
+                if let Some(ref hook) = *sync::read(&self.unsubscribe_hook) {
+                    hook(id, count + remaining);
+                }
+            }
+        }
+        removed_count
+    }
+
+    fn audit(&self, operation: AuditOperation) {
+        if let Some(ref sink) = *sync::read(&self.audit_sink) {
+            sink.record(&AuditRecord { time: SystemTime::now(), operation });
+        }
+    }
+}
+
+/// Guard returned by EventPublisher::subscribe_scoped. The handler stays subscribed for as long as the guard is alive and is
+/// unsubscribed when the guard is dropped, so components living shorter than the publisher can't leave handlers behind.
+pub struct Subscription<E> {
+    id: SubscriptionId,
+    registry: Weak<Registry<E>>,
+}
+
+impl<E> Subscription<E> {
+    /// Id of the guarded subscription.
+    pub fn id(&self) -> SubscriptionId {
+        self.id
+    }
+
+    /// Gives up the guard without unsubscribing. The handler then stays subscribed until it is unsubscribed by id.
+    /// OUTPUT: SubscriptionId   id of the subscription.
+    pub fn detach(mut self) -> SubscriptionId {
+        self.registry = Weak::new();
+        self.id
+    }
+}
+
+impl<E> Drop for Subscription<E> {
+    fn drop(&mut self) {
+        if let Some(registry) = self.registry.upgrade() {
+            registry.remove(self.id);
+        }
+    }
+}
+
+/// Guard returned by EventPublisher::subscribe_all, holding a set of handlers that were subscribed together. Dropping it, or
+/// calling unsubscribe, removes all of them at once: a publish sees either all of the handlers or none of them.
+pub struct CompositeSubscription<E> {
+    ids: Vec<SubscriptionId>,
+    registry: Weak<Registry<E>>,
+}
+
+impl<E> CompositeSubscription<E> {
+    /// Ids of the guarded subscriptions, in the order the handlers were passed to subscribe_all.
+    pub fn ids(&self) -> &[SubscriptionId] {
+        &self.ids
+    }
+
+    /// Unsubscribes all of the handlers, as dropping the guard does.
+    /// OUTPUT: usize   number of handlers removed; handlers unsubscribed by id meanwhile are not counted.
+    pub fn unsubscribe(mut self) -> usize {
+        self.remove()
+    }
+
+    /// Gives up the guard without unsubscribing. The handlers then stay subscribed until they are unsubscribed by id.
+    /// OUTPUT: Vec<SubscriptionId>   ids of the subscriptions.
+    pub fn detach(mut self) -> Vec<SubscriptionId> {
+        self.registry = Weak::new();
+        mem::take(&mut self.ids)
+    }
+
+    fn remove(&mut self) -> usize {
+        match self.registry.upgrade() {
+            Some(registry) => registry.remove_all(&mem::take(&mut self.ids)),
+            None => 0,
+        }
+    }
+}
+
+impl<E> Drop for CompositeSubscription<E> {
+    fn drop(&mut self) {
+        self.remove();
+    }
+}
+
 /// EventPublisher. Works similarly to C#'s event publishing pattern. Event handling functions are subscribed to the publisher.
 /// Whenever the publisher fires an event it calls all subscribed event handler functions.
+/// Handlers are called in order of descending priority (see subscribe_with_priority), then in the order they subscribed.
 /// Use event::EventPublisher::<E>::new() to construct
+/// The publisher is Send and Sync: put it in an Arc to subscribe, unsubscribe and publish from several threads at once.
+/// Handlers are called on the thread that publishes, without any of the publisher's locks held, so they may subscribe,
+/// unsubscribe or publish on the same publisher themselves. Every publish delivers to the handlers subscribed when it started:
+/// a handler subscribed from inside a handler first sees the next event, and a handler unsubscribed from inside a handler
+/// still sees the event being published. Publishes started afterwards, including ones made from within the handler, see the change.
+/// The set_* functions take &self as well, so a shared publisher can be configured at any time; a publish uses the settings
+/// it started with.
+/// The payload type E needs no bounds of its own: payloads holding an Rc or raw pointers can be published synchronously.
+/// Only the functions handing events to other threads or keeping them for later (such as publish_event_multithreaded,
+/// spawn_dispatcher, publish_sticky and pause) require E to be Send or Sync, and say so in their signatures.
+/// On wasm32 targets, which have no threads, the functions that start threads of their own (publish_event_multithreaded,
+/// publish_event_with_timeout, subscribe_debounced, spawn_dispatcher, spawn_scheduler and the remote transport) are left
+/// out. Nor can the publishing thread be blocked there, so subscribe_fallible_with_retry retries without waiting for the
+/// backoff and chaos delays (ChaosConfig::delay_probability) are skipped.
 pub struct EventPublisher<E> {
-    //handlers: Vec<Rc<Box<Fn(&Event<E>) + 'static>>>,
-    handlers: BTreeMap<usize, Rc<Box<Fn(&Event<E>) + 'static>>>,
+    registry: Arc<Registry<E>>,
+    next_id: AtomicU64,
+    max_subscribers: Option<usize>,
+    settings: RwLock<Arc<Settings<E>>>,
+    sequence: AtomicU64,
+    sticky: RwLock<Option<StickyBox<E>>>,
+    paused: RwLock<Option<PausedBox<E>>>,
+    in_flight: InFlight<(SubscriptionId, Handler<E>)>,
+}
+
+// What the set_* functions configure. Copy-on-write like the handler list: a publish loads the settings once and works with
+//     what it loaded, so the setters take &self, and changing a setting neither waits for a publish nor is half seen by one.
+struct Settings<E> {
+    chaos: Option<Arc<Mutex<Chaos>>>,
+    leak_detection_since: Option<Instant>,
+    metrics: Option<Arc<Metrics>>,
+    panic_hook: Option<Arc<PanicHook>>,
+    handler_timeout: Option<TimeoutPolicy>,
+    timeout_hook: Option<Arc<TimeoutHook>>,
+    quarantine_threshold: Option<u32>,
+    quarantine_hook: Option<Arc<QuarantineHook>>,
+    source: Option<String>,
+    interceptors: Vec<Arc<Interceptor<E>>>,
+    dead_event_handler: Option<Arc<DeadEventHandler<E>>>,
+}
+
+// What a publish does with the event once the interceptors passed it on, given the settings the publish loaded.
+type Deliver<'a, E> = dyn Fn(&Arc<Settings<E>>, &Event<E>) + 'a;
+
+impl<E> Settings<E> {
+    fn new() -> Settings<E> {
+        Settings {
+            chaos: None,
+            leak_detection_since: None,
+            metrics: None,
+            panic_hook: None,
+            handler_timeout: None,
+            timeout_hook: None,
+            quarantine_threshold: None,
+            quarantine_hook: None,
+            source: None,
+            interceptors: Vec::new(),
+            dead_event_handler: None,
+        }
+    }
+}
+
+// Not derived, which would require E: Clone. The chaos generator and the metrics counters are shared with the copy, so they
+//     carry on where they were.
+impl<E> Clone for Settings<E> {
+    fn clone(&self) -> Settings<E> {
+        Settings {
+            chaos: self.chaos.clone(),
+            leak_detection_since: self.leak_detection_since,
+            metrics: self.metrics.clone(),
+            panic_hook: self.panic_hook.clone(),
+            handler_timeout: self.handler_timeout,
+            timeout_hook: self.timeout_hook.clone(),
+            quarantine_threshold: self.quarantine_threshold,
+            quarantine_hook: self.quarantine_hook.clone(),
+            source: self.source.clone(),
+            interceptors: self.interceptors.clone(),
+            dead_event_handler: self.dead_event_handler.clone(),
+        }
+    }
+}
+
+// Kept behind a trait object so only publish_sticky needs E: Send + Sync, not every publisher.
+type StickyBox<E> = Arc<dyn Borrow<StickyEvent<E>> + Send + Sync>;
+type PausedBox<E> = Box<dyn Paused<E> + Send + Sync>;
+
+struct StickyEvent<E> {
+    sequence: u64,
+    published_at: SystemTime,
+    // No longer replayed from then on.
+    expires_at: Option<Instant>,
+    event: Event<E>,
+}
+
+// Publish handed to the threads of publish_event_with_timeout. If tracked, it is in flight until the last of them is done.
+#[cfg(not(target_arch = "wasm32"))]
+struct DetachedPublish<E> {
+    publisher: Arc<EventPublisher<E>>,
+    settings: Arc<Settings<E>>,
+    sequence: u64,
+    tracked: bool,
+    published_at: SystemTime,
+    event: Event<E>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<E> DetachedPublish<E> {
+    fn envelope(&self) -> EventEnvelope<'_, E> {
+        EventEnvelope { sequence: self.sequence, published_at: self.published_at, source: self.settings.source.as_deref(), event: &self.event }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<E> Drop for DetachedPublish<E> {
+    fn drop(&mut self) {
+        if self.tracked {
+            self.publisher.finish(&self.settings, self.sequence);
+        }
+    }
+}
+
+// Envelope of a publish that, if tracked, is in flight until the envelope is dropped, see subscribe_ordered.
+struct Publishing<'a, E: 'a> {
+    publisher: &'a EventPublisher<E>,
+    settings: &'a Settings<E>,
+    tracked: bool,
+    envelope: EventEnvelope<'a, E>,
+}
+
+impl<'a, E> Deref for Publishing<'a, E> {
+    type Target = EventEnvelope<'a, E>;
+
+    fn deref(&self) -> &EventEnvelope<'a, E> {
+        &self.envelope
+    }
+}
+
+impl<'a, E> Drop for Publishing<'a, E> {
+    fn drop(&mut self) {
+        if self.tracked {
+            self.publisher.finish(self.settings, self.envelope.sequence);
+        }
+    }
+}
+
+impl<E> StickyEvent<E> {
+    fn envelope<'a>(&'a self, source: Option<&'a str>) -> EventEnvelope<'a, E> {
+        EventEnvelope { sequence: self.sequence, published_at: self.published_at, source, event: &self.event }
+    }
 }
 
 impl<E> EventPublisher<E> {
@@ -23,40 +696,1546 @@ impl<E> EventPublisher<E> {
     /// Event publisher constructor.
     pub fn new() -> EventPublisher<E> {
         EventPublisher{ 
-            //handlers: Vec::<Rc<Box<Fn(&Event<E>) + 'static>>>::new() 
-            handlers: BTreeMap::<usize, Rc<Box<Fn(&Event<E>) + 'static>>>::new()
+            registry: Arc::new(Registry::new()),
+            next_id: AtomicU64::new(0),
+            max_subscribers: None,
+            settings: RwLock::new(Arc::new(Settings::new())),
+            sequence: AtomicU64::new(0),
+            sticky: RwLock::new(None),
+            paused: RwLock::new(None),
+            in_flight: InFlight::new(),
         }
     }
+
+    /// Event publisher constructor for a publisher that accepts at most max_subscribers subscriptions. Subscribing beyond
+    ///     that returns SubscribeError::Full.
+    /// INPUT:  max_subscribers: usize   maximum number of handlers subscribed at the same time.
+    pub fn with_max_subscribers(max_subscribers: usize) -> EventPublisher<E> {
+        let mut publisher = EventPublisher::new();
+        publisher.max_subscribers = Some(max_subscribers);
+        publisher
+    }
+
+    /// Builder for configuring a publisher in one place and picking how it dispatches events, see EventPublisherBuilder.
+    ///     An EventPublisher is shared by putting it in an Arc, whose clones are cheap handles to the same publisher.
+    /// OUTPUT: EventPublisherBuilder<E>
+    pub fn builder() -> EventPublisherBuilder<E> {
+        EventPublisherBuilder::new()
+    }
+
+    /// Sets the sink receiving the audit trail of the publisher: every subscribe, unsubscribe and publish is recorded
+    ///     with the time it happened. Replaces any previously set sink.
+    /// INPUT:  sink: Box<dyn AuditSink>   destination of the audit records. Closures taking &AuditRecord implement AuditSink.
+    /// OUTPUT: void
+    pub fn set_audit_sink(&self, sink: Box<dyn AuditSink>) {
+        *sync::write(&self.registry.audit_sink) = Some(sink);
+    }
+
+    /// Sets the hook told about handlers that panic. A panicking handler never stops a publish: the panic is caught, the
+    ///     event is still delivered to the remaining handlers and the hook, if any, is called with the panic. Replaces any
+    ///     previously set hook.
+    /// INPUT:  hook: Box<dyn Fn(&HandlerPanic) + Send + Sync + 'static>   called for every caught panic, on the thread the handler ran on.
+    /// OUTPUT: void
+    pub fn set_panic_hook(&self, hook: PanicHookBox) {
+        self.configure(|settings| settings.panic_hook = Some(Arc::from(hook)));
+    }
+
+    /// Sets the hook told about every new subscription, e.g. to start an expensive upstream source once the first handler
+    ///     subscribes. Called after the handler was added, on the subscribing thread, with the handler list unlocked so the hook
+    ///     may subscribe and unsubscribe itself. Hooks of changes made on several threads at once may run in any order.
+    ///     Replaces any previously set hook.
+    /// INPUT:  hook: Box<dyn Fn(SubscriptionId, usize) + Send + Sync + 'static>   called with the id of the new subscription and
+    ///     the number of subscriptions right after it was added.
+    /// OUTPUT: void
+    pub fn set_subscribe_hook(&self, hook: SubscriptionHookBox) {
+        *sync::write(&self.registry.subscribe_hook) = Some(hook);
+    }
+
+    /// Sets the hook told about every removed subscription, whether it was unsubscribed explicitly, by its Subscription guard
+    ///     or by the handler itself, e.g. to stop an upstream source once the count drops to 0. Called after the handler was
+    ///     removed, like the subscribe hook. Replaces any previously set hook.
+    /// INPUT:  hook: Box<dyn Fn(SubscriptionId, usize) + Send + Sync + 'static>   called with the id of the removed subscription
+    ///     and the number of subscriptions right after it was removed.
+    /// OUTPUT: void
+    pub fn set_unsubscribe_hook(&self, hook: SubscriptionHookBox) {
+        *sync::write(&self.registry.unsubscribe_hook) = Some(hook);
+    }
+
+    /// Sets the source identifier stamped on the envelope of every event published from now on, e.g. the name of the component
+    ///     owning the publisher, so handlers keeping an audit trail can tell where events came from.
+    /// INPUT:  source: Option<String>   source identifier, or None to stop stamping one.
+    /// OUTPUT: void
+    pub fn set_source(&self, source: Option<String>) {
+        self.configure(|settings| settings.source = source);
+    }
+
+    /// Adds an interceptor wrapping every publish, for logging, metrics or dropping events in one place instead of in every
+    ///     handler. The interceptor is called with the event and the rest of the chain: calling next delivers the event, not calling
+    ///     it drops the event, and calling it with another event delivers that one instead. Interceptors run in the order they
+    ///     were added, the first one outermost. publish_owned and publish_sticky bypass them, as they take ownership of the event.
+    /// INPUT:  interceptor: Fn(&Event<E>, &dyn Fn(&Event<E>)) + Send + Sync + 'static   called with the event and next.
+    /// OUTPUT: void
+    pub fn add_interceptor<F>(&self, interceptor: F) where F: Fn(&Event<E>, &dyn Fn(&Event<E>)) + Send + Sync + 'static {
+        self.configure(|settings| settings.interceptors.push(Arc::new(interceptor)));
+    }
+
+    /// Sets the handler told about dead events: events published while no handler would receive them, either because nothing
+    ///     is subscribed or because every filtered handler rejected them. Makes wiring mistakes visible instead of dropping the
+    ///     events silently. Replaces any previously set handler.
+    /// INPUT:  handler: Box<dyn Fn(&Event<E>) + Send + Sync + 'static>   called with every dead event, on the publishing thread.
+    /// OUTPUT: void
+    pub fn set_dead_event_handler(&self, handler: DeadEventHandlerBox<E>) {
+        self.configure(|settings| settings.dead_event_handler = Some(Arc::from(handler)));
+    }
+
+    /// Sets how long publish_event_with_timeout waits for handlers. A handler running late is reported to the timeout hook
+    ///     once the timeout passed and left to finish on its thread, and may be unsubscribed after repeated timeouts so it
+    ///     doesn't tie up a thread for every later publish. The other publish functions ignore it.
+    /// INPUT:  policy: Option<TimeoutPolicy>   timeout policy, or None to let handlers take as long as they like.
+    /// OUTPUT: void
+    pub fn set_handler_timeout(&self, policy: Option<TimeoutPolicy>) {
+        self.configure(|settings| settings.handler_timeout = policy);
+    }
+
+    /// Sets the hook told about handlers exceeding the timeout set with set_handler_timeout. Replaces any previously set hook.
+    /// INPUT:  hook: Box<dyn Fn(&HandlerTimeout) + Send + Sync + 'static>   called for every timeout, on the publishing thread.
+    /// OUTPUT: void
+    pub fn set_timeout_hook(&self, hook: TimeoutHookBox) {
+        self.configure(|settings| settings.timeout_hook = Some(Arc::from(hook)));
+    }
+
+    /// Sets how many times in a row a handler may fail before it is quarantined: skipped by every publish, while staying
+    ///     subscribed, until release_quarantine is called. A handler fails by panicking, or by returning an error to
+    ///     publish_event_fallible; any other delivery starts the count again. None, the default, never quarantines.
+    /// INPUT:  failures: Option<u32>   failures in a row a handler is quarantined after. Some(0) is treated as Some(1).
+    /// OUTPUT: void
+    pub fn set_quarantine_threshold(&self, failures: Option<u32>) {
+        self.configure(|settings| settings.quarantine_threshold = failures.map(|failures| cmp::max(failures, 1)));
+    }
+
+    /// Sets the hook told about handlers being quarantined, so their owner can replace them or release them again.
+    ///     Replaces any previously set hook.
+    /// INPUT:  hook: Box<dyn Fn(SubscriptionId, u32) + Send + Sync + 'static>   called with the quarantined subscription and
+    ///     the number of failures in a row, on the thread whose delivery failed last.
+    /// OUTPUT: void
+    pub fn set_quarantine_hook(&self, hook: QuarantineHookBox) {
+        self.configure(|settings| settings.quarantine_hook = Some(Arc::from(hook)));
+    }
+
+    /// Switches chaos mode on or off. While on, deliveries are randomly dropped, delayed, duplicated or reordered as described
+    ///     by the configuration, so tests can check that handlers tolerate unreliable delivery. Meant for tests only.
+    /// INPUT:  config: Option<ChaosConfig>   chaos configuration, or None to go back to reliable delivery.
+    /// OUTPUT: void
+    pub fn set_chaos(&self, config: Option<ChaosConfig>) {
+        self.configure(|settings| settings.chaos = config.map(|config| Arc::new(Mutex::new(Chaos::new(config)))));
+    }
+
+    /// Switches leak detection on or off. While on, the publisher remembers when each subscription last had an event delivered
+    ///     to it so suspected_leaks can report subscriptions that look forgotten.
+    /// INPUT:  enabled: bool
+    /// OUTPUT: void
+    pub fn set_leak_detection(&self, enabled: bool) {
+        self.configure(|settings| {
+            if !enabled {
+                settings.leak_detection_since = None;
+            } else if settings.leak_detection_since.is_none() {
+                settings.leak_detection_since = Some(Instant::now());
+            }
+        });
+    }
+
+    /// Switches metrics on or off. While on, the publisher counts published events and handler calls and times every call,
+    ///     to find the handlers slowing publishing down. Timing each call has a cost, so metrics are off by default.
+    /// INPUT:  enabled: bool
+    /// OUTPUT: void
+    pub fn set_metrics(&self, enabled: bool) {
+        self.configure(|settings| {
+            if !enabled {
+                settings.metrics = None;
+            } else if settings.metrics.is_none() {
+                settings.metrics = Some(Arc::new(Metrics::new()));
+            }
+        });
+    }
+
+    /// Metrics gathered since they were switched on.
+    /// OUTPUT: Option<PublisherMetrics>   the metrics; None while metrics are off.
+    pub fn metrics(&self) -> Option<PublisherMetrics> {
+        self.settings().metrics.as_ref().map(|metrics| {
+            let mut handlers: Vec<HandlerMetrics> = self.registry.load().iter()
+                .map(|(id, subscription)| sync::lock(&subscription.stats).report(*id, subscription.group.clone()))
+                .collect();
+            handlers.sort_by_key(|handler| handler.subscription);
+            metrics.report(handlers)
+        })
+    }
+
+    /// Reports subscriptions that had no event delivered to them for at least idle, counted from their last delivery, or from
+    ///     when they were subscribed or leak detection was switched on, whichever is later. These are likely handlers whose owner
+    ///     forgot to unsubscribe them.
+    /// INPUT:  idle: Duration   how long a subscription may go without deliveries before it is reported.
+    /// OUTPUT: Vec<SubscriptionInfo>   suspected leaks in subscription order; always empty while leak detection is off.
+    pub fn suspected_leaks(&self, idle: Duration) -> Vec<SubscriptionInfo> {
+        let since = match self.settings().leak_detection_since {
+            Some(since) => since,
+            None => return Vec::new(),
+        };
+        let now = Instant::now();
+        self.subscription_infos().into_iter().filter(|info| {
+            let last_seen = info.last_delivered.unwrap_or_else(|| cmp::max(info.subscribed_at, since));
+            now.duration_since(last_seen) >= idle
+        }).collect()
+    }
+
+    /// Creates a new publisher subscribed to by the same handlers as this one, e.g. to build a new pipeline next to a live one.
+    ///     The handlers are shared rather than copied, so state owned by a handler (see subscribe_with_state) is shared as well.
+    ///     From then on the two publishers are independent: subscribing to or unsubscribing from one does not affect the other.
+    ///     The subscriber limit is carried over; the audit sink, subscription hooks, source, interceptors, dead event handler,
+    ///     pausing, quarantine, chaos mode, leak detection and metrics are not.
+    /// OUTPUT: EventPublisher<E>   the forked publisher.
+    pub fn fork(&self) -> EventPublisher<E> {
+        let mut fork = EventPublisher::new();
+        fork.max_subscribers = self.max_subscribers;
+        // Locked while copying, so no id can be handed out by this publisher that is missing from the copy.
+        let handlers = sync::read(&self.registry.handlers);
+        fork.next_id = AtomicU64::new(self.next_id.load(Ordering::SeqCst));
+        *sync::write(&fork.registry.handlers) = Arc::new(handlers.iter()
+            .map(|(id, subscription)| (*id, Arc::new(Entry::new(subscription.handler.fork(), subscription.priority, subscription.group.clone()))))
+            .collect());
+        drop(handlers);
+        fork
+    }
+
     /// Subscribes event handler functions to the EventPublisher.
-    /// INPUT:  handler_box: Box<Fn(&Event<E>) + 'static>>   handler_box is a box pointer to a function to handle an event of the type E. The function must
+    /// INPUT:  handler_box: Box<dyn Fn(&Event<E>) + Send + Sync + 'static>   handler_box is a box pointer to a function to handle an event of the type E. The function must
     ///     be capable of handling references to the event type set up by the publisher, rather than the raw event itself.
-    /// OUTPUT: void
-    pub fn subscribe_handler(&mut self, handler_box: Box<Fn(&Event<E>) + 'static>){
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    pub fn subscribe_handler(&self, handler_box: HandlerBox<E>) -> Result<SubscriptionId, SubscribeError> {
+        self.insert_handler(HandlerKind::Plain(Arc::from(handler_box)))
+    }
 
-        //self.handlers.push( Rc::new(handler_box) );
-        //self.handlers.sort_by(|a,b| (&**a as *const _).cmp(&(&**b as *const _))) 
-        let p_handler = &*handler_box as *const _;
-        
-        self.handlers.insert(p_handler as usize, Rc::new(handler_box));
+    /// Subscribes event handler functions with a priority. Handlers are called in order of descending priority, and in the order
+    ///     they subscribed when priorities are equal. The other subscribe functions use priority 0.
+    /// INPUT:  handler_box: Box<dyn Fn(&Event<E>) + Send + Sync + 'static>   function to handle events of the type E, as for subscribe_handler.
+    ///         priority: i32   handlers with a higher priority are called first.
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    pub fn subscribe_with_priority(&self, handler_box: HandlerBox<E>, priority: i32) -> Result<SubscriptionId, SubscribeError> {
+        self.insert_handler_with_priority(HandlerKind::Plain(Arc::from(handler_box)), priority)
+    }
+
+    /// Subscribes event handler functions as part of a named group, e.g. the handlers of one subsystem or plugin, so they can be
+    ///     unsubscribed all at once with unsubscribe_group.
+    /// INPUT:  group: &str   name of the group.
+    ///         handler: Fn(&Event<E>) + Send + Sync + 'static   handler is called with a reference to every published event.
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    pub fn subscribe_named<F>(&self, group: &str, handler: F) -> Result<SubscriptionId, SubscribeError> where F: Fn(&Event<E>) + Send + Sync + 'static {
+        self.insert_entry(HandlerKind::Plain(Arc::new(handler)), 0, Some(String::from(group)))
+    }
+
+    /// Subscribes a shared event handler, such as one of your own types implementing EventHandler. The publisher keeps a clone
+    ///     of the Arc, so the same handler may be subscribed to several publishers.
+    /// INPUT:  handler: Arc<dyn EventHandler<E>>   handler is called with a reference to every published event.
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    pub fn subscribe(&self, handler: Arc<dyn EventHandler<E>>) -> Result<SubscriptionId, SubscribeError> {
+        self.insert_handler(HandlerKind::Shared(handler))
+    }
+
+    /// Subscribes an event handler for as long as the returned guard is alive. Dropping the Subscription unsubscribes the handler.
+    /// INPUT:  handler_box: Box<dyn Fn(&Event<E>) + Send + Sync + 'static>   function to handle events of the type E, as for subscribe_handler.
+    /// OUTPUT: Result<Subscription<E>, SubscribeError>   guard of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    pub fn subscribe_scoped(&self, handler_box: HandlerBox<E>) -> Result<Subscription<E>, SubscribeError> {
+        let id = self.subscribe_handler(handler_box)?;
+        Ok(Subscription { id, registry: Arc::downgrade(&self.registry) })
+    }
+
+    /// Subscribes a set of handlers at once, e.g. everything a module listens to, for as long as the returned guard is alive.
+    ///     The handlers are added in one step, so a concurrent publish sees either all of them or none, and either all of
+    ///     them fit under the subscriber limit or none is subscribed.
+    /// INPUT:  handler_boxes: Vec<Box<dyn Fn(&Event<E>) + Send + Sync + 'static>>   functions to handle events, as for subscribe_handler.
+    /// OUTPUT: Result<CompositeSubscription<E>, SubscribeError>   guard of the new subscriptions, or Err(SubscribeError::Full) if they don't all fit under the subscriber limit.
+    pub fn subscribe_all(&self, handler_boxes: Vec<HandlerBox<E>>) -> Result<CompositeSubscription<E>, SubscribeError> {
+        let ids = self.insert_entries(handler_boxes.into_iter().map(|handler| (HandlerKind::Plain(Arc::from(handler)), 0, None)).collect())?;
+        Ok(CompositeSubscription { ids, registry: Arc::downgrade(&self.registry) })
+    }
+
+    /// Subscribes a handler that decides after each event whether it wants to stay subscribed. Returning
+    ///     ControlFlow::Break(Unsubscribe) removes the handler once the current event has been delivered to all handlers.
+    /// INPUT:  handler: Fn(&Event<E>) -> ControlFlow<Unsubscribe> + Send + Sync + 'static   handler is called with a reference to every published event.
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    pub fn subscribe_until<F>(&self, handler: F) -> Result<SubscriptionId, SubscribeError> where F: Fn(&Event<E>) -> ControlFlow<Unsubscribe> + Send + Sync + 'static {
+        self.insert_handler(HandlerKind::Until(Arc::new(handler)))
     }
-    
+
+    /// Subscribes a handler for a single event. The handler is called with the next published event and is removed afterwards;
+    ///     if several threads publish at once, only one of them calls it.
+    /// INPUT:  handler: FnOnce(&Event<E>) + Send + 'static   handler is called with a reference to the next published event.
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    pub fn subscribe_once<F>(&self, handler: F) -> Result<SubscriptionId, SubscribeError> where F: FnOnce(&Event<E>) + Send + 'static {
+        let handler = Mutex::new(Some(handler));
+        self.subscribe_until(move |event: &Event<E>| {
+            let handler = sync::lock(&handler).take();
+            if let Some(handler) = handler {
+                handler(event);
+            }
+            ControlFlow::Break(Unsubscribe)
+        })
+    }
+
+    /// Subscribes a method of an object without keeping the object alive. On each event the Weak is upgraded and the handler is
+    ///     called with the object; once the object has been dropped the subscription removes itself on the next publish.
+    /// INPUT:  subscriber: Weak<T>   object the handler belongs to.
+    ///         handler: Fn(&T, &Event<E>) + Send + Sync + 'static   handler is called with the object and a reference to every published event.
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    pub fn subscribe_weak<T, F>(&self, subscriber: Weak<T>, handler: F) -> Result<SubscriptionId, SubscribeError> where T: Send + Sync + 'static, F: Fn(&T, &Event<E>) + Send + Sync + 'static {
+        self.subscribe_until(move |event: &Event<E>| {
+            match subscriber.upgrade() {
+                Some(subscriber) => {
+                    handler(&subscriber, event);
+                    ControlFlow::Continue(())
+                },
+                None => ControlFlow::Break(Unsubscribe),
+            }
+        })
+    }
+
+    /// Subscribes a handler that may stop an event from reaching the handlers after it, by calling stop_propagation on the
+    ///     EventContext it is handed. Only the handlers called after it are skipped; see the EventPublisher docs for the call order.
+    /// INPUT:  handler: Fn(&mut EventContext<E>) + Send + Sync + 'static   handler is called with the context of every published event.
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    pub fn subscribe_cancellable<F>(&self, handler: F) -> Result<SubscriptionId, SubscribeError> where F: Fn(&mut EventContext<E>) + Send + Sync + 'static {
+        self.insert_handler(HandlerKind::Context(Arc::new(handler)))
+    }
+
+    /// Subscribes a handler that may fail. The errors it returns are collected by publish_event_fallible; the other publish
+    ///     functions call it like any other handler and discard them.
+    /// INPUT:  handler: Fn(&Event<E>) -> Result<(), BoxError> + Send + Sync + 'static   handler is called with a reference to every published event.
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    pub fn subscribe_fallible<F>(&self, handler: F) -> Result<SubscriptionId, SubscribeError> where F: Fn(&Event<E>) -> Result<(), BoxError> + Send + Sync + 'static {
+        self.insert_handler(HandlerKind::Fallible(Arc::new(handler)))
+    }
+
+    /// Subscribes a handler that may fail, retrying it as the policy says when it returns an error, e.g. for handlers pushing
+    ///     events to a flaky downstream service. The retries run on the publishing thread, which waits for the backoff in between;
+    ///     on wasm32, where that thread can't wait, the attempts follow each other without any backoff.
+    ///     Only the error of the last attempt is passed on, as for subscribe_fallible.
+    /// INPUT:  policy: RetryPolicy   number of attempts and backoff between them.
+    ///         handler: Fn(&Event<E>) -> Result<(), BoxError> + Send + Sync + 'static   handler is called with a reference to every published event.
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    pub fn subscribe_fallible_with_retry<F>(&self, policy: RetryPolicy, handler: F) -> Result<SubscriptionId, SubscribeError> where F: Fn(&Event<E>) -> Result<(), BoxError> + Send + Sync + 'static {
+        self.subscribe_fallible(move |event: &Event<E>| {
+            let mut retry = 0;
+            loop {
+                match handler(event) {
+                    Err(_) if retry + 1 < policy.attempts() => {
+                        retry += 1;
+                        time::sleep(policy.delay(retry));
+                    },
+                    result => return result,
+                }
+            }
+        })
+    }
+
+    /// Subscribes a handler to a publisher of encoded events, e.g. frames received from another process, decoding each of them
+    ///     with codec before calling handler. Events that fail to decode are skipped; publish_event_fallible returns the
+    ///     CodecError for them.
+    /// INPUT:  codec: Codec + 'static   codec the payloads were encoded with, e.g. JsonCodec.
+    ///         handler: Fn(&Event<T>) + Send + Sync + 'static   handler is called with a reference to every decoded event.
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    #[cfg(feature = "serde")]
+    pub fn subscribe_deserializing<T, C, F>(&self, codec: C, handler: F) -> Result<SubscriptionId, SubscribeError>
+        where E: AsRef<[u8]>, T: SerializableEvent, C: Codec + 'static, F: Fn(&Event<T>) + Send + Sync + 'static {
+        self.subscribe_fallible(move |event: &Event<E>| {
+            let event = match *event {
+                Event::Args(ref bytes) => codec.decode(bytes.as_ref())?,
+                Event::Missing => Event::Missing,
+            };
+            handler(&event);
+            Ok(())
+        })
+    }
+
+    /// Subscribes a handler to a publisher of encoded events like subscribe_deserializing, for events encoded with a Schema:
+    ///     each subscription reads them with its own schema, so a handler expecting the current payload type still gets events
+    ///     encoded with older versions, through the schema's upgrade hook. Events that fail to decode or upgrade are skipped;
+    ///     publish_event_fallible returns the CodecError for them.
+    /// INPUT:  codec: Codec + 'static   codec the payloads were encoded with, e.g. JsonCodec.
+    ///         schema: Schema<T>   current version of T, and how to upgrade older ones.
+    ///         handler: Fn(&Event<T>) + Send + Sync + 'static   handler is called with a reference to every decoded event.
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    #[cfg(feature = "serde")]
+    pub fn subscribe_versioned<T, C, F>(&self, codec: C, schema: Schema<T>, handler: F) -> Result<SubscriptionId, SubscribeError>
+        where E: AsRef<[u8]>, T: SerializableEvent + 'static, C: Codec + 'static, F: Fn(&Event<T>) + Send + Sync + 'static {
+        self.subscribe_fallible(move |event: &Event<E>| {
+            let event = match *event {
+                Event::Args(ref bytes) => schema.decode(&codec, bytes.as_ref())?,
+                Event::Missing => Event::Missing,
+            };
+            handler(&event);
+            Ok(())
+        })
+    }
+
+    /// Subscribes a RemotePublisher, sending every event published here to the process it is connected to. Events that could
+    ///     not be sent are skipped; publish_event_fallible returns the io::Error for them.
+    /// INPUT:  remote: RemotePublisher<E, C>
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    #[cfg(all(feature = "serde", not(target_arch = "wasm32")))]
+    pub fn forward_remote<C>(&self, remote: RemotePublisher<E, C>) -> Result<SubscriptionId, SubscribeError>
+        where E: SerializableEvent + 'static, C: Codec + 'static {
+        self.subscribe_fallible(move |event: &Event<E>| remote.publish_event(event).map_err(BoxError::from))
+    }
+
+    /// Subscribes a handler answering events with a reply, for query-style events such as "who can handle this?". The replies
+    ///     are collected by publish_and_collect::<R>; the other publish functions call it like any other handler and discard them.
+    /// INPUT:  handler: Fn(&Event<E>) -> R + Send + Sync + 'static   handler is called with a reference to every published event.
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    pub fn subscribe_responder<R, F>(&self, handler: F) -> Result<SubscriptionId, SubscribeError> where R: 'static, F: Fn(&Event<E>) -> R + Send + Sync + 'static {
+        self.insert_handler(HandlerKind::Responder(Arc::new(move |event: &Event<E>| Box::new(handler(event)) as Box<dyn Any>)))
+    }
+
+    /// Subscribes a handler that only cares about the payload of the event. Event::Missing is skipped.
+    /// INPUT:  handler: Fn(&E) + Send + Sync + 'static   handler is called with a reference to the args of every Event::Args published.
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    pub fn subscribe_args<F>(&self, handler: F) -> Result<SubscriptionId, SubscribeError> where F: Fn(&E) + Send + Sync + 'static {
+        self.subscribe_handler(Box::new(move |event: &Event<E>| {
+            if let Event::Args(ref args) = *event {
+                handler(args);
+            }
+        }))
+    }
+
+    /// Subscribes a handler that is only called for events matching a predicate. The predicate is checked when the publish
+    ///     starts, and the handler doesn't count as a subscriber of events it rejects, see set_dead_event_handler.
+    /// INPUT:  predicate: Fn(&Event<E>) -> bool + Send + Sync + 'static   predicate is called with a reference to every published event.
+    ///         handler: Fn(&Event<E>) + Send + Sync + 'static   handler is called with a reference to every event the predicate returns true for.
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    pub fn subscribe_filtered<P, F>(&self, predicate: P, handler: F) -> Result<SubscriptionId, SubscribeError> where P: Fn(&Event<E>) -> bool + Send + Sync + 'static, F: Fn(&Event<E>) + Send + Sync + 'static {
+        self.insert_handler(HandlerKind::Filtered(Arc::new(predicate), Arc::new(handler)))
+    }
+
+    /// Subscribes a handler that is only called for one variant of an enum event type, e.g.
+    ///     subscribe_variant::<my_event::Clicked, _>(handler) with the markers generated by #[derive(EventKind)]. Like
+    ///     subscribe_filtered, the handler doesn't count as a subscriber of the other variants. Event::Missing is skipped.
+    /// INPUT:  handler: Fn(&E) + Send + Sync + 'static   handler is called with the payload of every event of the variant V.
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    pub fn subscribe_variant<V, F>(&self, handler: F) -> Result<SubscriptionId, SubscribeError> where E: EventKind, V: EventVariant<E>, F: Fn(&E) + Send + Sync + 'static {
+        self.subscribe_filtered(|event: &Event<E>| match *event {
+            Event::Args(ref args) => V::matches(args),
+            Event::Missing => false,
+        }, move |event: &Event<E>| {
+            if let Event::Args(ref args) = *event {
+                handler(args);
+            }
+        })
+    }
+
+    /// Subscribes a handler that is called at most once per interval, for high-frequency events such as mouse moves. An event
+    ///     is passed on if the handler wasn't called during the interval before it, and dropped otherwise.
+    /// INPUT:  interval: Duration   minimum time between two calls of the handler.
+    ///         handler: Fn(&Event<E>) + Send + Sync + 'static   handler is called with a reference to the events passed on.
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    pub fn subscribe_throttled<F>(&self, interval: Duration, handler: F) -> Result<SubscriptionId, SubscribeError> where F: Fn(&Event<E>) + Send + Sync + 'static {
+        let last_call: Mutex<Option<Instant>> = Mutex::new(None);
+        self.subscribe_handler(Box::new(move |event: &Event<E>| {
+            let now = Instant::now();
+            let due = {
+                let mut last_call = sync::lock(&last_call);
+                match *last_call {
+                    Some(last_call) if now.duration_since(last_call) < interval => false,
+                    _ => {
+                        *last_call = Some(now);
+                        true
+                    },
+                }
+            };
+            if due {
+                handler(event);
+            }
+        }))
+    }
+
+    /// Subscribes a handler that is only called once a burst of events has settled, for notifications such as file-watcher
+    ///     events. The handler is called with the last event of the burst, delay after it was published, on a timer thread
+    ///     of the subscription rather than the publishing one, which ends once the handler is unsubscribed. Panics there are
+    ///     caught, so later bursts are still delivered, but not handed to the panic hook. A burst pending when the handler is
+    ///     unsubscribed is still delivered. If the timer thread can't be started, the event is delivered straight away.
+    /// INPUT:  delay: Duration   time without events after which the burst counts as settled.
+    ///         handler: Fn(&Event<E>) + Send + Sync + 'static   handler is called with a reference to the last event of every burst.
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn subscribe_debounced<F>(&self, delay: Duration, handler: F) -> Result<SubscriptionId, SubscribeError> where E: Clone + Send + 'static, F: Fn(&Event<E>) + Send + Sync + 'static {
+        self.subscribe_handler(Box::new(debounce::debounce(delay, handler)))
+    }
+
+    /// Subscribes a handler together with a piece of state owned by the publisher. The state is handed to the handler mutably on
+    ///     every event, so handlers can keep counters, buffers etc. without wrapping them in a Mutex themselves. The state is locked
+    ///     for the duration of each call, so concurrent publishes take turns running this handler. If the handler publishes to
+    ///     this publisher, it is not called again with the nested event, as it is still busy with the outer one.
+    /// INPUT:  state: S    initial value of the state, moved into the subscription.
+    ///         handler: Fn(&mut S, &Event<E>) + Send + Sync + 'static   handler is called with the state and a reference to every published event.
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    pub fn subscribe_with_state<S, F>(&self, state: S, handler: F) -> Result<SubscriptionId, SubscribeError> where S: Send + 'static, F: Fn(&mut S, &Event<E>) + Send + Sync + 'static {
+        let state = Exclusive::new(state);
+        self.subscribe_handler(Box::new(move |event: &Event<E>| {
+            state.with(|state| handler(state, event));
+        }))
+    }
+
+    /// Subscribes an FnMut handler. The handler is kept in a Mutex that is locked for the duration of each call, so concurrent
+    ///     publishes take turns running it. If the handler publishes to this publisher, it is not called again with the nested
+    ///     event, as it is still busy with the outer one.
+    /// INPUT:  handler: FnMut(&Event<E>) + Send + 'static   handler is called with a reference to every published event.
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    pub fn subscribe_handler_mut<F>(&self, handler: F) -> Result<SubscriptionId, SubscribeError> where F: FnMut(&Event<E>) + Send + 'static {
+        let handler = Exclusive::new(handler);
+        self.subscribe_handler(Box::new(move |event: &Event<E>| {
+            handler.with(|handler| handler(event));
+        }))
+    }
+
+    /// Subscribes a handler that sees events in the order they were published, by sequence number, even when several threads
+    ///     publish at once or publish_event_multithreaded, publish_event_parallel and publish_event_async run concurrently.
+    ///     An event reaching the handler ahead of an earlier one that is still being published is kept, and the handler is
+    ///     called with it once every earlier publish reaching an ordered handler has finished, on the thread of whichever publish
+    ///     finishes last. Calls never overlap, and an event published from inside the handler is delivered after the handler
+    ///     returned. A handler that never returns holds up the events published after the one it is handling. Only publishes
+    ///     reaching an ordered handler are kept track of, so publishing costs nothing extra while none is subscribed.
+    /// INPUT:  handler: Fn(&Event<E>) + Send + Sync + 'static   handler is called with a reference to every published event.
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    pub fn subscribe_ordered<F>(&self, handler: F) -> Result<SubscriptionId, SubscribeError> where E: Clone + Send + 'static, F: Fn(&Event<E>) + Send + Sync + 'static {
+        self.insert_handler(HandlerKind::Ordered(Arc::new(OrderedQueue::new(Box::new(handler)))))
+    }
+
+    /// Subscribes a handler that is called where run_on says rather than on the publishing thread, e.g. on a thread of its own
+    ///     for a handler that blocks, or through an executor posting to a UI thread. Except for RunOn::Inline, every event is
+    ///     cloned into the task, publishing returns without waiting for the handler, and the handler's panics are not passed
+    ///     to the panic hook: a panic on the pool or a dedicated thread is caught there, one in an Executor is up to it.
+    /// INPUT:  run_on: RunOn   where the handler is called.
+    ///         handler: Fn(&Event<E>) + Send + Sync + 'static   handler is called with a reference to every published event.
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    pub fn subscribe_on<F>(&self, run_on: RunOn, handler: F) -> Result<SubscriptionId, SubscribeError> where E: Clone + Send + 'static, F: Fn(&Event<E>) + Send + Sync + 'static {
+        let submit = match executor::submitter(run_on) {
+            Some(submit) => submit,
+            None => return self.subscribe_handler(Box::new(handler)),
+        };
+        let handler = Arc::new(handler);
+        self.subscribe_handler(Box::new(move |event: &Event<E>| {
+            let handler = handler.clone();
+            let event = event.clone();
+            submit(Box::new(move || handler(&event)));
+        }))
+    }
+
+    /// Subscribes a handler that takes shared ownership of published payloads. The handler is only called by publish_owned,
+    ///     with its own clone of the Arc the payload was moved into, so it may hold on to the payload after the publish returns.
+    /// INPUT:  handler: Fn(Arc<E>) + Send + Sync + 'static   handler is called with the payload of every event published with publish_owned.
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    pub fn subscribe_owned<F>(&self, handler: F) -> Result<SubscriptionId, SubscribeError> where F: Fn(Arc<E>) + Send + Sync + 'static {
+        self.insert_handler(HandlerKind::Owned(Arc::new(handler)))
+    }
+
+    /// Subscribes a handler that receives every published event in an EventEnvelope, together with the sequence number,
+    ///     publish time and source the publisher stamped on it.
+    /// INPUT:  handler: Fn(&EventEnvelope<E>) + Send + Sync + 'static   handler is called with the envelope of every published event.
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    pub fn subscribe_envelope<F>(&self, handler: F) -> Result<SubscriptionId, SubscribeError> where F: Fn(&EventEnvelope<E>) + Send + Sync + 'static {
+        self.insert_handler(HandlerKind::Envelope(Arc::new(handler)))
+    }
+
+    /// Subscribes an async handler. The handler is called with a reference to the event and returns a future, which
+    ///     publish_event_async awaits; the blocking publish functions skip async handlers. The future can't borrow the event,
+    ///     so clone what it needs from it.
+    /// INPUT:  handler: Fn(&Event<E>) -> Future<Output = ()> + Send + Sync + 'static   handler is called with a reference to every event published with publish_event_async.
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    #[cfg(feature = "async")]
+    pub fn subscribe_async<F, T>(&self, handler: F) -> Result<SubscriptionId, SubscribeError> where F: Fn(&Event<E>) -> T + Send + Sync + 'static, T: Future<Output = ()> + Send + 'static {
+        self.insert_handler(HandlerKind::Async(Arc::new(move |event: &Event<E>| Box::pin(handler(event)) as BoxFuture)))
+    }
+
+    /// Subscribes a stream of the args of the published events, for consuming them with stream combinators or
+    ///     while let Some(args) = stream.next().await. Event::Missing is skipped. Dropping the stream unsubscribes it.
+    /// OUTPUT: Result<EventStream<E>, SubscribeError>   the stream, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    #[cfg(feature = "async")]
+    pub fn subscribe_stream(&self) -> Result<EventStream<E>, SubscribeError> where E: Clone + Send + 'static {
+        let (sender, shared) = stream::channel();
+        let id = self.subscribe_args(move |args: &E| sender.send(args.clone()))?;
+        Ok(EventStream::new(shared, Subscription { id, registry: Arc::downgrade(&self.registry) }))
+    }
+
+    /// Blocks until an event whose args match the predicate is published, e.g. for a test or for startup code waiting for a
+    ///     component to report it is ready. Only events published after wait_for is called are seen, through a subscription
+    ///     that is removed again before returning; it only counts as a subscriber of the events it matches. The event has to
+    ///     be published from another thread, as the calling thread is blocked.
+    /// INPUT:  timeout: Duration   how long to wait at most.
+    ///         predicate: Fn(&E) -> bool + Send + Sync + 'static   predicate is called with the args of every published event.
+    /// OUTPUT: Result<Option<E>, SubscribeError>   the args of the first matching event, None if none was published within the timeout, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn wait_for<P>(&self, timeout: Duration, predicate: P) -> Result<Option<E>, SubscribeError> where E: Clone + Send + 'static, P: Fn(&E) -> bool + Send + Sync + 'static {
+        let deadline = Instant::now() + timeout;
+        let shared = Arc::new((Mutex::new(None), Condvar::new()));
+        let matched = shared.clone();
+        let id = self.subscribe_filtered(move |event: &Event<E>| match *event {
+            Event::Args(ref args) => predicate(args),
+            Event::Missing => false,
+        }, move |event: &Event<E>| {
+            if let Event::Args(ref args) = *event {
+                let (ref slot, ref condvar) = *matched;
+                let mut slot = sync::lock(slot);
+                if slot.is_none() {
+                    *slot = Some(args.clone());
+                    condvar.notify_all();
+                }
+            }
+        })?;
+        let (ref slot, ref condvar) = *shared;
+        let mut found = sync::lock(slot);
+        while found.is_none() {
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+            found = sync::wait_timeout(condvar, found, deadline - now);
+        }
+        let found = found.take();
+        self.unsubscribe(id);
+        Ok(found)
+    }
+
+    /// Async counterpart of wait_for: a future completing with the args of the first event matching the predicate published
+    ///     after wait_for_async is called, or with None if the publisher is dropped first. Has no timeout of its own; wrap it in
+    ///     the timeout of the runtime, dropping the future unsubscribes it.
+    /// INPUT:  predicate: Fn(&E) -> bool + Send + Sync + 'static   predicate is called with the args of every published event.
+    /// OUTPUT: Result<WaitFor<E>, SubscribeError>   the future, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    #[cfg(feature = "async")]
+    pub fn wait_for_async<P>(&self, predicate: P) -> Result<WaitFor<E>, SubscribeError> where E: Clone + Send + 'static, P: Fn(&E) -> bool + Send + Sync + 'static {
+        let (sender, shared) = stream::channel();
+        let id = self.subscribe_filtered(move |event: &Event<E>| match *event {
+            Event::Args(ref args) => predicate(args),
+            Event::Missing => false,
+        }, move |event: &Event<E>| {
+            if let Event::Args(ref args) = *event {
+                sender.send(args.clone());
+            }
+        })?;
+        Ok(WaitFor::new(EventStream::new(shared, Subscription { id, registry: Arc::downgrade(&self.registry) })))
+    }
+
+    /// Subscribes a channel, so the args of every published event are cloned and sent into it. Event::Missing is skipped.
+    ///     Once the receiving end is dropped the subscription removes itself.
+    /// INPUT:  sender: Sender<E>   sending end of the channel.
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    pub fn subscribe_channel(&self, sender: Sender<E>) -> Result<SubscriptionId, SubscribeError> where E: Clone + Send + 'static {
+        self.subscribe_until(move |event: &Event<E>| {
+            match *event {
+                Event::Args(ref args) => match sender.send(args.clone()) {
+                    Ok(()) => ControlFlow::Continue(()),
+                    Err(_) => ControlFlow::Break(Unsubscribe),
+                },
+                Event::Missing => ControlFlow::Continue(()),
+            }
+        })
+    }
+
+    /// Subscribes a batching sink, which is fed a clone of the args of every Event::Args published. Event::Missing is skipped.
+    ///     Keep a clone of the Arc to poll or flush the sink.
+    /// INPUT:  sink: Arc<BatchingSink<E>>   sink collecting the payloads into batches for its downstream handler.
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    pub fn subscribe_batching(&self, sink: Arc<BatchingSink<E>>) -> Result<SubscriptionId, SubscribeError> where E: Clone + Send + 'static {
+        self.subscribe_args(move |args: &E| sink.push(args.clone()))
+    }
+
+    /// Subscribes an event history, which keeps a clone of the last events published for replaying them later.
+    ///     Keep a clone of the Arc to replay from it.
+    /// INPUT:  history: Arc<EventHistory<E>>   history recording the published events, Event::Missing included.
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    pub fn subscribe_history(&self, history: Arc<EventHistory<E>>) -> Result<SubscriptionId, SubscribeError> where E: Clone + Send + 'static {
+        self.subscribe_envelope(move |envelope: &EventEnvelope<E>| history.record(envelope))
+    }
+
+    /// Creates a publisher republishing the events of this one, transformed, for building pipelines such as raw input to
+    ///     domain events to UI events. The payload of every Event::Args is passed through transform; Event::Missing is passed on
+    ///     as it is. This publisher only keeps a Weak to the new one: once it is dropped, the forwarding handler removes itself.
+    /// INPUT:  transform: Fn(&E) -> T + Send + Sync + 'static   maps the payload of an event to the payload of the new one.
+    /// OUTPUT: Result<Arc<EventPublisher<T>>, SubscribeError>   the new publisher, or Err(SubscribeError::Full) if this publisher is at its subscriber limit.
+    pub fn map<T, F>(&self, transform: F) -> Result<Arc<EventPublisher<T>>, SubscribeError> where T: 'static, F: Fn(&E) -> T + Send + Sync + 'static {
+        let mapped = Arc::new(EventPublisher::new());
+        let target = Arc::downgrade(&mapped);
+        self.subscribe_until(move |event: &Event<E>| {
+            match target.upgrade() {
+                Some(target) => {
+                    target.publish_event(&match *event {
+                        Event::Args(ref args) => Event::Args(transform(args)),
+                        Event::Missing => Event::Missing,
+                    });
+                    ControlFlow::Continue(())
+                },
+                None => ControlFlow::Break(Unsubscribe),
+            }
+        })?;
+        Ok(mapped)
+    }
+
+    /// Creates a publisher republishing the events of this one that match a predicate. Like map, this publisher only keeps a
+    ///     Weak to the new one.
+    /// INPUT:  predicate: Fn(&Event<E>) -> bool + Send + Sync + 'static   predicate is called with a reference to every published event.
+    /// OUTPUT: Result<Arc<EventPublisher<E>>, SubscribeError>   the new publisher, or Err(SubscribeError::Full) if this publisher is at its subscriber limit.
+    pub fn filter<P>(&self, predicate: P) -> Result<Arc<EventPublisher<E>>, SubscribeError> where E: 'static, P: Fn(&Event<E>) -> bool + Send + Sync + 'static {
+        let filtered = Arc::new(EventPublisher::new());
+        let target = Arc::downgrade(&filtered);
+        self.subscribe_until(move |event: &Event<E>| {
+            match target.upgrade() {
+                Some(target) => {
+                    if predicate(event) {
+                        target.publish_event(event);
+                    }
+                    ControlFlow::Continue(())
+                },
+                None => ControlFlow::Break(Unsubscribe),
+            }
+        })?;
+        Ok(filtered)
+    }
+
+    /// Republishes every event published on this publisher on another one, e.g. to connect a module-local publisher to an
+    ///     application-wide one. Loops are detected: an event forwarded back to a publisher it already passed through is not
+    ///     published there again, so publishers may forward to each other; they then keep each other alive until one of the
+    ///     forwards is unsubscribed.
+    /// INPUT:  other: Arc<EventPublisher<E>>   publisher the events are republished on.
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the forwarding subscription, for unsubscribe, or Err(SubscribeError::Full) if this publisher is at its subscriber limit.
+    pub fn forward_to(&self, other: Arc<EventPublisher<E>>) -> Result<SubscriptionId, SubscribeError> where E: 'static {
+        let source = self.identity();
+        self.subscribe_handler(Box::new(move |event: &Event<E>| {
+            forward::forward(source, other.identity(), || other.publish_event(event));
+        }))
+    }
+
     /// Unsubscribes an event handler from the publisher.
-    /// INPUT:  handler_box: Box<Fn(&Event<E>) + 'static>    handler_box is a box pointer to a function to handle an event of the type E.
-    /// OUTPUT: bool    output is a bool of whether or not the function was found in the list of subscribed event handlers and subsequently removed.
-    pub fn unsubscribe_handler(&mut self, handler_box: Box<Fn(&Event<E>) + 'static>) -> bool {
-        let p_handler = &*handler_box as *const _;
-        match self.handlers.remove(&(p_handler as usize)){
-            Some(_) => true,
+    /// INPUT:  id: SubscriptionId    id returned when the handler was subscribed.
+    /// OUTPUT: bool    output is a bool of whether or not the subscription was found in the list of subscribed event handlers and subsequently removed.
+    pub fn unsubscribe(&self, id: SubscriptionId) -> bool {
+        self.registry.remove(id)
+    }
+        
+    /// Unsubscribes every handler subscribed to a group with subscribe_named.
+    /// INPUT:  group: &str   name of the group.
+    /// OUTPUT: usize   number of subscriptions removed.
+    pub fn unsubscribe_group(&self, group: &str) -> usize {
+        self.retain_subscriptions(|info| info.group.as_deref() != Some(group))
+    }
+
+    /// Removes every subscription for which keep returns false, e.g. everything subscribed longer ago than some cut-off.
+    /// INPUT:  keep: FnMut(&SubscriptionInfo) -> bool   called once per subscription, in subscription order.
+    /// OUTPUT: usize   number of subscriptions removed.
+    pub fn retain_subscriptions<F>(&self, mut keep: F) -> usize where F: FnMut(&SubscriptionInfo) -> bool {
+        let removed: Vec<SubscriptionId> = self.subscription_infos().into_iter()
+            .filter(|info| !keep(info))
+            .map(|info| info.id)
+            .collect();
+        let count = removed.len();
+        self.remove_handlers(removed);
+        count
+    }
+
+    /// Number of subscriptions, of any kind. Lets publishers skip building expensive events nobody listens to.
+    pub fn subscriber_count(&self) -> usize {
+        self.registry.load().len()
+    }
+
+    /// Subscriber limit the publisher was constructed with, see with_max_subscribers. Lets callers rejected with
+    ///     SubscribeError::Full report the limit, or check how much room is left before subscribing.
+    /// OUTPUT: Option<usize>   the limit, or None if the publisher accepts any number of subscriptions.
+    pub fn max_subscribers(&self) -> Option<usize> {
+        self.max_subscribers
+    }
+
+    /// Ends the quarantine of a handler, see set_quarantine_threshold, so it is called again from the next publish on.
+    /// INPUT:  id: SubscriptionId
+    /// OUTPUT: bool   whether the handler was quarantined.
+    pub fn release_quarantine(&self, id: SubscriptionId) -> bool {
+        match self.registry.load().iter().find(|&&(subscribed, _)| subscribed == id) {
+            Some((_, handler)) => {
+                handler.failures.store(0, Ordering::SeqCst);
+                handler.quarantined.swap(false, Ordering::SeqCst)
+            },
             None => false,
         }
     }
-        
-    // TODO: Implement this concurrently
+
+    /// Subscriptions currently quarantined, in dispatch order.
+    pub fn quarantined(&self) -> Vec<SubscriptionId> {
+        self.registry.load().iter()
+            .filter(|(_, handler)| handler.quarantined.load(Ordering::SeqCst))
+            .map(|&(id, _)| id)
+            .collect()
+    }
+
+    /// Whether nothing is subscribed to the publisher.
+    pub fn is_empty(&self) -> bool {
+        self.registry.load().is_empty()
+    }
+
+    /// Lists the current subscriptions, e.g. for showing the wiring of an application in a debug UI.
+    /// OUTPUT: Vec<SubscriptionInfo>   subscriptions in subscription order.
+    pub fn subscriptions(&self) -> Vec<SubscriptionInfo> {
+        self.subscription_infos()
+    }
+
     /// Publishes events, pushing the &Event<E> to all handler functions stored by the event publisher.
     /// INPUT: event: &Event<E>     Reference to the Event<E> being pushed to all handling functions.
     pub fn publish_event(&self, event: &Event<E>){
-        for (_, handler) in self.handlers.iter(){
-            handler(event);
+        if self.hold_if_paused(event) {
+            return;
+        }
+        self.intercept(event, &|settings, event| {
+            let handlers = self.snapshot(settings, event, |handler| handler.is_synchronous());
+            let envelope = self.envelope(settings, event, handlers.iter());
+            self.publish_envelope(settings, handlers, &envelope);
+        });
+    }
+
+    /// Decodes an event encoded with codec, e.g. received from another process or read back from storage, and publishes it
+    ///     with publish_event.
+    /// INPUT:  codec: &Codec   codec the event was encoded with, e.g. &JsonCodec.
+    ///         bytes: &[u8]   the encoded Event<E>, as returned by codec.encode(&event).
+    /// OUTPUT: Result<(), CodecError>   Err if bytes could not be decoded, in which case nothing is published.
+    #[cfg(feature = "serde")]
+    pub fn publish_serialized<C>(&self, codec: &C, bytes: &[u8]) -> Result<(), CodecError> where E: SerializableEvent, C: Codec {
+        let event: Event<E> = codec.decode(bytes)?;
+        self.publish_event(&event);
+        Ok(())
+    }
+
+    /// Decodes an event encoded with a Schema, upgrading it if it was encoded with an older version, and publishes it with
+    ///     publish_event.
+    /// INPUT:  codec: &Codec   codec the payload was encoded with, e.g. &JsonCodec.
+    ///         schema: &Schema<E>   current version of E, and how to upgrade older ones.
+    ///         bytes: &[u8]   the versioned encoding, as returned by schema.encode(codec, &event).
+    /// OUTPUT: Result<(), CodecError>   Err if bytes could not be decoded or upgraded, in which case nothing is published.
+    #[cfg(feature = "serde")]
+    pub fn publish_versioned<C>(&self, codec: &C, schema: &Schema<E>, bytes: &[u8]) -> Result<(), CodecError> where E: SerializableEvent, C: Codec {
+        let event = schema.decode(codec, bytes)?;
+        self.publish_event(&event);
+        Ok(())
+    }
+
+    /// Publishes a batch of events, one after the other as publish_event does, but takes the snapshot of the handlers once for
+    ///     the whole batch, which saves the per-event overhead when publishing many small events. Handlers subscribed while the
+    ///     batch is published first see the next batch; handlers unsubscribing themselves miss the rest of it.
+    /// INPUT: events: IntoIterator<Item = &Event<E>>   events to publish, e.g. a slice or an iterator over references.
+    pub fn publish_events<'a, I>(&self, events: I) where I: IntoIterator<Item = &'a Event<E>>, E: 'a {
+        let handlers = RefCell::new(self.registry.load());
+        for event in events {
+            if self.hold_if_paused(event) {
+                continue;
+            }
+            self.intercept(event, &|settings, event| {
+                let recipients = self.select(settings, &handlers.borrow(), event, |handler| handler.is_synchronous());
+                let envelope = self.envelope(settings, event, recipients.iter());
+                let stopped = AtomicBool::new(false);
+                let finished = self.dispatch(settings, recipients, &stopped, |_, handler| handler.call(&envelope, &stopped));
+                if !finished.is_empty() {
+                    Arc::make_mut(&mut *handlers.borrow_mut()).retain(|(id, _)| !finished.contains(id));
+                }
+                self.remove_handlers(finished);
+            });
+        }
+    }
+
+    fn hold_if_paused(&self, event: &Event<E>) -> bool {
+        match *sync::read(&self.paused) {
+            Some(ref paused) => {
+                paused.hold(event);
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Publishes an event and keeps it as the sticky event of the publisher: every handler subscribed afterwards is called with
+    ///     it straight away, from within the subscribe call, so state-like events (current user, current configuration) reach
+    ///     late subscribers too. Replaces any previous sticky event. Handlers subscribed with subscribe_owned or subscribe_async don't get the replay.
+    ///     A handler subscribing while the event is being published may see it twice; envelope handlers can tell by the sequence.
+    /// INPUT: event: Event<E>
+    pub fn publish_sticky(&self, event: Event<E>) where E: Send + Sync + 'static {
+        self.publish_sticky_until(event, None);
+    }
+
+    /// Publishes a sticky event like publish_sticky that is only replayed to handlers subscribing within ttl, so a late
+    ///     subscriber gets no event rather than a stale one, e.g. a "current position" from minutes ago.
+    /// INPUT:  event: Event<E>
+    ///         ttl: Duration   how long the event is replayed for.
+    pub fn publish_sticky_with_ttl(&self, event: Event<E>, ttl: Duration) where E: Send + Sync + 'static {
+        self.publish_sticky_until(event, Some(Instant::now() + ttl));
+    }
+
+    fn publish_sticky_until(&self, event: Event<E>, expires_at: Option<Instant>) where E: Send + Sync + 'static {
+        let settings = self.settings();
+        #[cfg(feature = "tracing")]
+        let _span = trace_publish(&settings);
+        // Always in flight, as the ordered handlers it reaches are only known once it is kept.
+        let sticky = Arc::new(StickyEvent { sequence: self.next_sequence(true), published_at: SystemTime::now(), expires_at, event });
+        // Kept before publishing, so a handler subscribing meanwhile can't miss it.
+        *sync::write(&self.sticky) = Some(sticky.clone());
+        let handlers = self.snapshot(&settings, &sticky.event, |handler| handler.is_synchronous());
+        self.publish_envelope(&settings, handlers, &sticky.envelope(settings.source.as_deref()));
+        self.finish(&settings, sticky.sequence);
+    }
+
+    /// Pauses publish_event, e.g. while an application is starting up and its handlers aren't all subscribed yet. Until resume
+    ///     is called, events published with publish_event are kept for resume or discarded, as mode says. The other publish
+    ///     functions deliver as usual. Does nothing if the publisher is already paused.
+    /// INPUT:  mode: WhilePaused   what to do with the events published while paused.
+    /// OUTPUT: void
+    pub fn pause(&self, mode: WhilePaused) where E: Clone + Send + 'static {
+        let mut paused = sync::write(&self.paused);
+        if paused.is_none() {
+            *paused = Some(Box::new(PauseBuffer::new(mode)));
+        }
+    }
+
+    /// Resumes publishing after pause and publishes the events kept meanwhile, in the order they were published. Events
+    ///     published by other threads while these are being published may overtake them.
+    /// OUTPUT: usize   number of kept events published; 0 if the publisher wasn't paused.
+    pub fn resume(&self) -> usize {
+        let paused = sync::write(&self.paused).take();
+        let events = match paused {
+            Some(paused) => paused.release(),
+            None => return 0,
+        };
+        let published = events.len();
+        for event in events {
+            self.publish_event(&event);
         }
+        published
+    }
+
+    /// Whether the publisher is paused.
+    pub fn is_paused(&self) -> bool {
+        sync::read(&self.paused).is_some()
+    }
+
+    /// Forgets the sticky event, so handlers subscribed from now on are not called straight away.
+    /// OUTPUT: void
+    pub fn clear_sticky(&self) {
+        *sync::write(&self.sticky) = None;
+    }
+
+    fn publish_envelope(&self, settings: &Settings<E>, handlers: Recipients<(SubscriptionId, Handler<E>)>, envelope: &EventEnvelope<E>) {
+        let stopped = AtomicBool::new(false);
+        let finished = self.dispatch(settings, handlers, &stopped, |_, handler| handler.call(envelope, &stopped));
+        self.remove_handlers(finished);
+    }
+
+    /// Publishes an event like publish_event and collects the errors returned by handlers subscribed with subscribe_fallible.
+    /// INPUT:  event: &Event<E>
+    ///         policy: ErrorPolicy   whether to keep delivering the event after a handler failed.
+    /// OUTPUT: Result<(), Vec<HandlerError>>   Ok if no handler failed, otherwise the errors in the order the handlers were called.
+    pub fn publish_event_fallible(&self, event: &Event<E>, policy: ErrorPolicy) -> Result<(), Vec<HandlerError>> {
+        let errors = RefCell::new(Vec::new());
+        self.intercept(event, &|settings, event| {
+            let handlers = self.snapshot(settings, event, |handler| handler.is_synchronous());
+            let envelope = self.envelope(settings, event, handlers.iter());
+            let stopped = AtomicBool::new(false);
+            let finished = self.dispatch(settings, handlers, &stopped, |id, handler| {
+                match handler.call_fallible(&envelope, &stopped) {
+                    Ok(flow) => flow,
+                    Err(error) => {
+                        self.record_failure_of(settings, id);
+                        errors.borrow_mut().push(HandlerError { subscription: id, error });
+                        if policy == ErrorPolicy::StopAtFirst {
+                            stopped.store(true, Ordering::SeqCst);
+                        }
+                        ControlFlow::Continue(())
+                    },
+                }
+            });
+            self.remove_handlers(finished);
+        });
+
+        let errors = errors.into_inner();
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+
+    /// Publishes an event like publish_event and collects the replies of the handlers subscribed with subscribe_responder
+    ///     whose reply type is R. Replies of responders of another type are discarded.
+    /// INPUT:  event: &Event<E>
+    /// OUTPUT: Vec<R>   replies in the order the handlers were called.
+    pub fn publish_and_collect<R>(&self, event: &Event<E>) -> Vec<R> where R: 'static {
+        let replies = RefCell::new(Vec::new());
+        self.intercept(event, &|settings, event| {
+            let handlers = self.snapshot(settings, event, |handler| handler.is_synchronous());
+            let envelope = self.envelope(settings, event, handlers.iter());
+            let stopped = AtomicBool::new(false);
+            let finished = self.dispatch(settings, handlers, &stopped, |_, handler| {
+                match handler.call_responder(event) {
+                    Some(reply) => {
+                        if let Ok(reply) = reply.downcast::<R>() {
+                            replies.borrow_mut().push(*reply);
+                        }
+                        ControlFlow::Continue(())
+                    },
+                    None => handler.call(&envelope, &stopped),
+                }
+            });
+            self.remove_handlers(finished);
+        });
+        replies.into_inner()
+    }
+
+    /// Publishes an event to async handlers as well as to the others. Handlers that aren't async run straight away, as with
+    ///     publish_event; the returned future completes once the futures returned by all async handlers have completed,
+    ///     which run concurrently.
+    /// INPUT:  event: &Event<E>
+    /// OUTPUT: PublishFuture   future awaiting the async handlers.
+    #[cfg(feature = "async")]
+    pub fn publish_event_async(&self, event: &Event<E>) -> PublishFuture {
+        let futures = RefCell::new(Vec::new());
+        self.intercept(event, &|settings, event| {
+            let handlers = self.snapshot(settings, event, |handler| !handler.is_owned());
+            let envelope = self.envelope(settings, event, handlers.iter());
+            let stopped = AtomicBool::new(false);
+            let finished = self.dispatch(settings, handlers, &stopped, |_, handler| {
+                match handler.call_async(event) {
+                    Some(future) => {
+                        futures.borrow_mut().push(future);
+                        ControlFlow::Continue(())
+                    },
+                    None => handler.call(&envelope, &stopped),
+                }
+            });
+            self.remove_handlers(finished);
+        });
+        PublishFuture::new(futures.into_inner())
+    }
+
+    /// Publishes an event like publish_event_async, but polls at most max_in_flight of the async handlers' futures at once, so
+    ///     hundreds of async subscribers calling the same downstream service don't all hit it at the same time. The futures are
+    ///     polled in the order the handlers were called; each one completing lets the next one start. The handler functions
+    ///     themselves are all called straight away, so they should leave the work to the futures they return.
+    /// INPUT:  event: &Event<E>
+    ///         max_in_flight: usize   number of futures polled at once. A limit of 0 is treated as 1.
+    /// OUTPUT: PublishFuture   future awaiting the async handlers.
+    #[cfg(feature = "async")]
+    pub fn publish_event_concurrent(&self, event: &Event<E>, max_in_flight: usize) -> PublishFuture {
+        self.publish_event_async(event).with_max_in_flight(cmp::max(max_in_flight, 1))
+    }
+
+    /// Publishes an event like publish_event_async and hands the returned future to the browser's event loop, for web targets
+    ///     where nothing else would poll it. Returns once the handlers that aren't async have run.
+    /// INPUT:  event: &Event<E>
+    #[cfg(all(feature = "async", target_arch = "wasm32", target_os = "unknown"))]
+    pub fn publish_event_spawn_local(&self, event: &Event<E>) {
+        wasm_bindgen_futures::spawn_local(self.publish_event_async(event));
     }
-}
\ No newline at end of file
+
+    /// Publishes an event to all handlers at once, each on its own thread, and returns once every handler has finished.
+    ///     The handler snapshot and the event are shared with the threads rather than copied, so the payload has to be Sync.
+    ///     Handlers subscribed with subscribe_owned are skipped, as with publish_event. Panics are handed to the panic hook on
+    ///     the handler's thread. As the handlers run at the same time, EventContext::stop_propagation has no effect on them.
+    ///     See publish_event_with_timeout for handlers that may take too long.
+    /// INPUT: event: &Event<E>
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn publish_event_multithreaded(&self, event: &Event<E>) where E: Sync {
+        self.intercept(event, &|settings, event| {
+            let handlers = self.snapshot(settings, event, |handler| handler.is_synchronous());
+            let envelope = self.envelope(settings, event, handlers.iter());
+            let stopped = AtomicBool::new(false);
+            let finished = self.dispatch_scoped(settings, handlers, |_, handler| handler.call(&envelope, &stopped));
+            self.remove_handlers(finished);
+        });
+    }
+
+    /// Publishes an event to all handlers at once, each on its own thread, like publish_event_multithreaded, but waits no
+    ///     longer than the timeout set with set_handler_timeout. Handlers still running by then are reported to the timeout
+    ///     hook and left to finish on their threads, so a stuck handler can't stall the publishing thread; without a timeout
+    ///     it waits for every handler. The threads may outlive the call, so they get a copy of the event and a clone of the Arc.
+    /// INPUT: event: &Event<E>
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn publish_event_with_timeout(self: &Arc<Self>, event: &Event<E>) where E: Clone + Send + Sync + 'static {
+        self.intercept(event, &|settings, event| {
+            let mut handlers = self.snapshot(settings, event, |handler| handler.is_synchronous()).into_vec();
+            if let Some(ref chaos) = settings.chaos {
+                sync::lock(chaos).shuffle(&mut handlers);
+            }
+            let tracked = handlers.iter().any(|(_, handler)| handler.handler.is_ordered());
+            let publish = Arc::new(DetachedPublish {
+                publisher: self.clone(),
+                settings: settings.clone(),
+                sequence: self.next_sequence(tracked),
+                tracked,
+                published_at: SystemTime::now(),
+                event: event.clone(),
+            });
+            let (done, done_ids) = mpsc::channel();
+            for &(id, ref handler) in &handlers {
+                let publish = publish.clone();
+                let handler = handler.clone();
+                let done = done.clone();
+                thread::spawn(move || {
+                    let envelope = publish.envelope();
+                    let stopped = AtomicBool::new(false);
+                    let flow = publish.publisher.deliver_isolated(&publish.settings, id, &handler, &|_, handler| handler.call(&envelope, &stopped));
+                    if flow.is_break() {
+                        publish.publisher.registry.remove(id);
+                    }
+                    let _ = done.send(id);
+                });
+            }
+            drop(done);
+            match settings.handler_timeout {
+                Some(policy) => self.watch_timeouts(settings, &policy, handlers, &done_ids),
+                None => done_ids.iter().for_each(drop),
+            }
+        });
+    }
+
+    /// Starts a thread publishing events on this publisher, so publishing through the returned Dispatcher only queues the
+    ///     event and the handlers run on the dispatcher thread, one event at a time in the order they were queued.
+    ///     Keep a clone of the Arc to subscribe and unsubscribe meanwhile.
+    /// OUTPUT: Dispatcher<E>   handle for publishing to the dispatcher thread and shutting it down.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn spawn_dispatcher(self: Arc<Self>) -> Dispatcher<E> where E: Send + 'static {
+        Dispatcher::spawn(self, EventQueue::unbounded())
+    }
+
+    /// Same as spawn_dispatcher, with a queue holding at most capacity events.
+    /// INPUT:  capacity: usize   maximum number of queued events. A capacity of 0 is treated as 1.
+    ///         backpressure: Backpressure   what Dispatcher::publish_event does while the queue is full.
+    /// OUTPUT: Dispatcher<E>   handle for publishing to the dispatcher thread and shutting it down.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn spawn_dispatcher_with_capacity(self: Arc<Self>, capacity: usize, backpressure: Backpressure) -> Dispatcher<E> where E: Send + 'static {
+        Dispatcher::spawn(self, EventQueue::bounded(capacity, backpressure))
+    }
+
+    /// Listens for RemotePublishers on an endpoint, publishing the events they send on this publisher, see RemoteSubscriber.
+    ///     Keep a clone of the Arc to subscribe, unsubscribe and publish meanwhile.
+    /// INPUT:  endpoint: &Endpoint   where to listen, e.g. Endpoint::Tcp("127.0.0.1:0".parse().unwrap()).
+    ///         codec: Codec + 'static   codec the events are encoded with; the RemotePublishers have to use the same.
+    /// OUTPUT: io::Result<RemoteSubscriber>   handle for shutting the listener down, or Err if the endpoint could not be bound.
+    #[cfg(all(feature = "serde", not(target_arch = "wasm32")))]
+    pub fn listen_remote<C>(self: Arc<Self>, endpoint: &Endpoint, codec: C) -> io::Result<RemoteSubscriber>
+        where E: SerializableEvent + Send + Sync + 'static, C: Codec + 'static {
+        RemoteSubscriber::bind(self, endpoint, codec, None)
+    }
+
+    /// Same as listen_remote, for RemotePublishers sending events tagged with a schema version, see
+    ///     RemotePublisher::set_schema_version. Events encoded with an older version are upgraded with the schema before they
+    ///     are published, so the sending processes can be updated one by one.
+    /// INPUT:  endpoint: &Endpoint   where to listen.
+    ///         codec: Codec + 'static   codec the events are encoded with; the RemotePublishers have to use the same.
+    ///         schema: Schema<E>   current version of E, and how to upgrade older ones.
+    /// OUTPUT: io::Result<RemoteSubscriber>   handle for shutting the listener down, or Err if the endpoint could not be bound.
+    #[cfg(all(feature = "serde", not(target_arch = "wasm32")))]
+    pub fn listen_remote_versioned<C>(self: Arc<Self>, endpoint: &Endpoint, codec: C, schema: Schema<E>) -> io::Result<RemoteSubscriber>
+        where E: SerializableEvent + Send + Sync + 'static, C: Codec + 'static {
+        RemoteSubscriber::bind(self, endpoint, codec, Some(schema))
+    }
+
+    /// Starts a timer thread publishing events on this publisher at scheduled times, see Scheduler.
+    ///     Keep a clone of the Arc to subscribe, unsubscribe and publish meanwhile.
+    /// OUTPUT: Scheduler<E>   handle for scheduling events and shutting the timer thread down.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn spawn_scheduler(self: Arc<Self>) -> Scheduler<E> where E: Send + 'static {
+        Scheduler::spawn(self)
+    }
+
+    /// Publishes an event to all handlers on the rayon thread pool, for many CPU-heavy handlers that shouldn't each get a
+    ///     thread of their own. Returns once every handler has finished. Otherwise behaves like publish_event_multithreaded.
+    /// INPUT: event: &Event<E>
+    #[cfg(feature = "rayon")]
+    pub fn publish_event_parallel(&self, event: &Event<E>) where E: Sync {
+        self.intercept(event, &|settings, event| {
+            let handlers = self.snapshot(settings, event, |handler| handler.is_synchronous());
+            let envelope = self.envelope(settings, event, handlers.iter());
+            let stopped = AtomicBool::new(false);
+            let finished = self.dispatch_parallel(settings, handlers, |_, handler| handler.call(&envelope, &stopped));
+            self.remove_handlers(finished);
+        });
+    }
+
+    /// Publishes an owned payload. The payload is moved into an Arc once, and handlers subscribed with subscribe_owned each get
+    ///     a clone of it, so they can keep it (or send it to another thread) after the publish returns. All other handlers are
+    ///     called first with a reference to the event, as with publish_event; owned handlers run afterwards.
+    ///     Unlike publish_event, the event skips the interceptors and is delivered while the publisher is paused, as both
+    ///     would need to borrow or copy the payload before it is moved. The dead event handler is still told if no handler
+    ///     receives it.
+    /// INPUT: args: E     payload of the published Event::Args.
+    pub fn publish_owned(&self, args: E) {
+        let settings = self.settings();
+        #[cfg(feature = "tracing")]
+        let _span = trace_publish(&settings);
+        let event = Event::Args(args);
+        let (owned, borrowed): (Vec<_>, Vec<_>) = self.snapshot(&settings, &event, |handler| handler.is_synchronous() || handler.is_owned()).into_vec().into_iter().partition(|(_, handler)| handler.handler.is_owned());
+
+        let stopped = AtomicBool::new(false);
+        let mut finished = {
+            let envelope = self.envelope(&settings, &event, &borrowed);
+            self.dispatch(&settings, Recipients::Selected(borrowed), &stopped, |_, handler| handler.call(&envelope, &stopped))
+        };
+        if let Event::Args(args) = event {
+            let args = Arc::new(args);
+            finished.extend(self.dispatch(&settings, Recipients::Selected(owned), &stopped, |_, handler| handler.call_owned(&args)));
+        }
+        self.remove_handlers(finished);
+    }
+
+    /// Sequence number of the most recently published event, or 0 if nothing has been published yet.
+    pub fn last_sequence(&self) -> u64 {
+        self.sequence.load(Ordering::SeqCst)
+    }
+
+    // Stable for the lifetime of the publisher, as the registry never moves.
+    fn identity(&self) -> usize {
+        Arc::as_ptr(&self.registry) as usize
+    }
+
+    // A tracked publish is in flight until finish is called with the sequence number. Only publishes reaching ordered
+    //     handlers are tracked, so the others don't take the lock of the in-flight set.
+    fn next_sequence(&self, tracked: bool) -> u64 {
+        if tracked {
+            self.in_flight.begin(&self.sequence)
+        } else {
+            self.sequence.fetch_add(1, Ordering::SeqCst) + 1
+        }
+    }
+
+    // Delivers the events ordered handlers kept back until this publish finished.
+    fn finish(&self, settings: &Settings<E>, sequence: u64) {
+        for (id, handler) in self.in_flight.finish(sequence) {
+            self.drain_ordered(settings, id, &handler);
+        }
+    }
+
+    fn drain_ordered(&self, settings: &Settings<E>, id: SubscriptionId, handler: &Handler<E>) {
+        let ordered = match handler.handler {
+            HandlerKind::Ordered(ref ordered) => ordered,
+            _ => return,
+        };
+        ordered.drain(&|sequence| self.in_flight.ready_or_wait(sequence, || (id, handler.clone())), &|outcome| {
+            match outcome {
+                Ok(()) => handler.failures.store(0, Ordering::SeqCst),
+                Err(payload) => {
+                    if let Some(ref hook) = settings.panic_hook {
+                        hook(&HandlerPanic::new(id, payload));
+                    }
+                    self.record_failure(settings, id, handler);
+                },
+            }
+        });
+    }
+
+    fn record_failure(&self, settings: &Settings<E>, id: SubscriptionId, handler: &Entry<E>) {
+        let failures = handler.failures.fetch_add(1, Ordering::SeqCst) + 1;
+        let threshold_reached = settings.quarantine_threshold.is_some_and(|threshold| failures >= threshold);
+        if threshold_reached && !handler.quarantined.swap(true, Ordering::SeqCst) {
+            if let Some(ref hook) = settings.quarantine_hook {
+                hook(id, failures);
+            }
+        }
+    }
+
+    // For failures noticed where only the id is at hand. Does nothing if the handler was unsubscribed meanwhile.
+    fn record_failure_of(&self, settings: &Settings<E>, id: SubscriptionId) {
+        if let Some((_, handler)) = self.registry.load().iter().find(|&&(subscribed, _)| subscribed == id) {
+            self.record_failure(settings, id, handler);
+        }
+    }
+
+    // Loads the settings for a publish and runs the interceptors in the order they were added, the last of them handing the
+    //     event to deliver.
+    fn intercept(&self, event: &Event<E>, deliver: &Deliver<'_, E>) {
+        let settings = self.settings();
+        #[cfg(feature = "tracing")]
+        let _span = trace_publish(&settings);
+        intercept_from(&settings, 0, event, &|event: &Event<E>| deliver(&settings, event));
+    }
+
+    fn settings(&self) -> Arc<Settings<E>> {
+        sync::read(&self.settings).clone()
+    }
+
+    // Changes a copy of the settings if a publish still holds the current ones.
+    fn configure<F>(&self, change: F) where F: FnOnce(&mut Settings<E>) {
+        change(Arc::make_mut(&mut *sync::write(&self.settings)));
+    }
+
+    // Taken once the recipients are known: an ordered handler only waits for the publishes that reach it, and those are
+    //     tracked from before it is called.
+    fn envelope<'a, 'h, I>(&'a self, settings: &'a Settings<E>, event: &'a Event<E>, recipients: I) -> Publishing<'a, E>
+        where I: IntoIterator<Item = &'h (SubscriptionId, Handler<E>)>, E: 'h {
+        let tracked = recipients.into_iter().any(|(_, handler)| handler.handler.is_ordered());
+        let envelope = EventEnvelope { sequence: self.next_sequence(tracked), published_at: SystemTime::now(), source: settings.source.as_deref(), event };
+        Publishing { publisher: self, settings, tracked, envelope }
+    }
+
+    // Work on a snapshot so handlers run without the lock held and may subscribe, unsubscribe or publish themselves.
+    //     The list is already in dispatch order.
+    fn snapshot<P>(&self, settings: &Settings<E>, event: &Event<E>, predicate: P) -> Recipients<(SubscriptionId, Handler<E>)> where P: Fn(&HandlerKind<E>) -> bool {
+        self.select(settings, &self.registry.load(), event, predicate)
+    }
+
+    // Picks the handlers an event is delivered to. Records the publish in the audit trail, and hands the event to the dead
+    //     event handler if no handler accepts it.
+    fn select<P>(&self, settings: &Settings<E>, handlers: &HandlerList<E>, event: &Event<E>, predicate: P) -> Recipients<(SubscriptionId, Handler<E>)> where P: Fn(&HandlerKind<E>) -> bool {
+        let handlers = Recipients::select(handlers, |(_, handler)| {
+            !handler.quarantined.load(Ordering::SeqCst) && predicate(&handler.handler) && handler.handler.accepts(event)
+        });
+        self.audit(AuditOperation::Publish { handlers: handlers.len() });
+        if let Some(ref metrics) = settings.metrics {
+            metrics.record_publish();
+        }
+        if handlers.is_empty() {
+            if let Some(ref dead_event_handler) = settings.dead_event_handler {
+                dead_event_handler(event);
+            }
+        }
+        handlers
+    }
+
+    // Sorted by id, i.e. in subscription order rather than dispatch order.
+    fn subscription_infos(&self) -> Vec<SubscriptionInfo> {
+        let mut infos: Vec<SubscriptionInfo> = self.registry.load().iter().map(|(id, subscription)| subscription.info(*id)).collect();
+        infos.sort_by_key(|info| info.id);
+        infos
+    }
+
+    // Returns the ids of the handlers that asked to be unsubscribed. Stops early once a handler stopped the propagation.
+    fn dispatch<F>(&self, settings: &Settings<E>, mut handlers: Recipients<(SubscriptionId, Handler<E>)>, stopped: &AtomicBool, call: F) -> Vec<SubscriptionId> where F: Fn(SubscriptionId, &HandlerKind<E>) -> ControlFlow<Unsubscribe> {
+        if let Some(ref chaos) = settings.chaos {
+            sync::lock(chaos).shuffle(handlers.make_mut());
+        }
+        let mut finished = Vec::new();
+        for &(id, ref handler) in handlers.iter() {
+            if stopped.load(Ordering::SeqCst) {
+                break;
+            }
+            if self.deliver_isolated(settings, id, handler, &call).is_break() {
+                finished.push(id);
+            }
+        }
+        finished
+    }
+
+    // Same as dispatch, but every handler runs on a scoped thread. The scope joins all of them before returning.
+    fn dispatch_scoped<F>(&self, settings: &Settings<E>, handlers: Recipients<(SubscriptionId, Handler<E>)>, call: F) -> Vec<SubscriptionId> where F: Fn(SubscriptionId, &HandlerKind<E>) -> ControlFlow<Unsubscribe> + Sync {
+        let mut handlers = handlers.into_vec();
+        if let Some(ref chaos) = settings.chaos {
+            sync::lock(chaos).shuffle(&mut handlers);
+        }
+        let call = &call;
+        thread::scope(|scope| {
+            let running: Vec<_> = handlers.into_iter()
+                .map(|(id, handler)| (id, scope.spawn(move || self.deliver_isolated(settings, id, &handler, call))))
+                .collect();
+            let mut finished = Vec::new();
+            for (id, thread) in running {
+                // deliver_isolated catches handler panics, so joining only fails if the publisher itself panicked.
+                match thread.join() {
+                    Ok(flow) => if flow.is_break() { finished.push(id) },
+                    Err(payload) => panic::resume_unwind(payload),
+                }
+            }
+            finished
+        })
+    }
+
+    // Waits until the handlers report on done_ids or the timeout passed, and reports the ones still running.
+    fn watch_timeouts(&self, settings: &Settings<E>, policy: &TimeoutPolicy, handlers: Vec<(SubscriptionId, Handler<E>)>, done_ids: &mpsc::Receiver<SubscriptionId>) {
+        let deadline = Instant::now() + policy.timeout;
+        let mut running: BTreeMap<SubscriptionId, Handler<E>> = handlers.into_iter().collect();
+        while !running.is_empty() {
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+            match done_ids.recv_timeout(deadline - now) {
+                Ok(id) => { running.remove(&id); },
+                Err(_) => break,
+            }
+        }
+        for (id, handler) in running {
+            let violations = handler.timeouts.fetch_add(1, Ordering::SeqCst) + 1;
+            let unsubscribed = match policy.unsubscribe_after {
+                Some(limit) if violations >= limit => self.registry.remove(id),
+                _ => false,
+            };
+            if let Some(ref hook) = settings.timeout_hook {
+                hook(&HandlerTimeout { subscription: id, timeout: policy.timeout, violations, unsubscribed });
+            }
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    fn dispatch_parallel<F>(&self, settings: &Settings<E>, mut handlers: Recipients<(SubscriptionId, Handler<E>)>, call: F) -> Vec<SubscriptionId> where F: Fn(SubscriptionId, &HandlerKind<E>) -> ControlFlow<Unsubscribe> + Sync {
+        use rayon::prelude::*;
+
+        if let Some(ref chaos) = settings.chaos {
+            sync::lock(chaos).shuffle(handlers.make_mut());
+        }
+        handlers.par_iter()
+            .filter(|&&(id, ref handler)| self.deliver_isolated(settings, id, handler, &call).is_break())
+            .map(|&(id, _)| id)
+            .collect()
+    }
+
+    fn remove_handlers(&self, ids: Vec<SubscriptionId>) {
+        for id in ids {
+            self.registry.remove(id);
+        }
+    }
+
+    // Handlers run without any of the publisher's locks held, so a panicking handler can't leave the publisher inconsistent.
+    fn deliver_isolated<F>(&self, settings: &Settings<E>, id: SubscriptionId, handler: &Handler<E>, call: &F) -> ControlFlow<Unsubscribe> where F: Fn(SubscriptionId, &HandlerKind<E>) -> ControlFlow<Unsubscribe> {
+        let failures = handler.failures.load(Ordering::SeqCst);
+        let flow = match panic::catch_unwind(AssertUnwindSafe(|| self.deliver(settings, id, handler, call))) {
+            Ok(flow) => {
+                // Unless the call recorded a failure of its own, such as an error returned to publish_event_fallible. Ordered
+                //     handlers only keep the event here, drain_ordered keeps their count.
+                if !handler.handler.is_ordered() {
+                    let _ = handler.failures.compare_exchange(failures, 0, Ordering::SeqCst, Ordering::SeqCst);
+                }
+                flow
+            },
+            Err(payload) => {
+                if let Some(ref hook) = settings.panic_hook {
+                    hook(&HandlerPanic::new(id, payload));
+                }
+                self.record_failure(settings, id, handler);
+                ControlFlow::Continue(())
+            },
+        };
+        if handler.handler.is_ordered() {
+            self.drain_ordered(settings, id, handler);
+        }
+        flow
+    }
+
+    fn deliver<F>(&self, settings: &Settings<E>, id: SubscriptionId, handler: &Handler<E>, call: &F) -> ControlFlow<Unsubscribe> where F: Fn(SubscriptionId, &HandlerKind<E>) -> ControlFlow<Unsubscribe> {
+        let chaos = match settings.chaos {
+            Some(ref chaos) => chaos,
+            None => return self.invoke(settings, id, handler, call),
+        };
+
+        if sync::lock(chaos).should_drop() {
+            return ControlFlow::Continue(());
+        }
+        let delay = sync::lock(chaos).delay();
+        if let Some(delay) = delay {
+            time::sleep(delay);
+        }
+        let flow = self.invoke(settings, id, handler, call);
+        if flow.is_continue() && sync::lock(chaos).should_duplicate() {
+            return self.invoke(settings, id, handler, call);
+        }
+        flow
+    }
+
+    fn invoke<F>(&self, settings: &Settings<E>, id: SubscriptionId, handler: &Handler<E>, call: &F) -> ControlFlow<Unsubscribe> where F: Fn(SubscriptionId, &HandlerKind<E>) -> ControlFlow<Unsubscribe> {
+        if settings.leak_detection_since.is_some() {
+            *sync::lock(&handler.last_delivered) = Some(Instant::now());
+        }
+        #[cfg(feature = "tracing")]
+        tracing::trace!(subscription = id.0, group = handler.group.as_deref(), "calling handler");
+        let metrics = match settings.metrics {
+            Some(ref metrics) => metrics,
+            None => return call(id, &handler.handler),
+        };
+        let started = Instant::now();
+        let flow = call(id, &handler.handler);
+        metrics.record_call(&mut sync::lock(&handler.stats), started.elapsed());
+        flow
+    }
+
+    fn insert_handler(&self, handler: HandlerKind<E>) -> Result<SubscriptionId, SubscribeError> {
+        self.insert_handler_with_priority(handler, 0)
+    }
+
+    fn insert_handler_with_priority(&self, handler: HandlerKind<E>, priority: i32) -> Result<SubscriptionId, SubscribeError> {
+        self.insert_entry(handler, priority, None)
+    }
+
+    fn insert_entry(&self, handler: HandlerKind<E>, priority: i32, group: Option<String>) -> Result<SubscriptionId, SubscribeError> {
+        let ids = self.insert_entries(vec![(handler, priority, group)])?;
+        Ok(ids[0])
+    }
+
+    // Adds all of the handlers in one change of the list, or none of them if they don't all fit.
+    fn insert_entries(&self, entries: Vec<(HandlerKind<E>, i32, Option<String>)>) -> Result<Vec<SubscriptionId>, SubscribeError> {
+        let inserted = self.registry.update(|handlers| {
+            match self.max_subscribers {
+                Some(max_subscribers) if handlers.len() + entries.len() > max_subscribers => None,
+                _ => {
+                    let initial_count = handlers.len();
+                    let inserted: Vec<_> = entries.into_iter().map(|(handler, priority, group)| {
+                        // Taken under the lock so ids keep increasing in the order handlers are added to the list.
+                        let id = SubscriptionId(self.next_id.fetch_add(1, Ordering::SeqCst) + 1);
+                        let entry = Arc::new(Entry::new(handler, priority, group));
+                        // After every handler of the same or a higher priority, so ties stay in subscription order.
+                        let position = handlers.iter().position(|(_, other)| other.priority < priority).unwrap_or(handlers.len());
+                        handlers.insert(position, (id, entry.clone()));
+                        (id, entry)
+                    }).collect();
+                    Some((inserted, initial_count))
+                },
+            }
+        });
+
+        match inserted {
+            Some((inserted, initial_count)) => {
+                let settings = self.settings();
+                let mut ids = Vec::with_capacity(inserted.len());
+                for (index, (id, entry)) in inserted.into_iter().enumerate() {
+                    self.audit(AuditOperation::Subscribe { subscription: id });
+                    if let Some(ref hook) = *sync::read(&self.registry.subscribe_hook) {
+                        hook(id, initial_count + index + 1);
+                    }
+                    self.replay_sticky(&settings, id, entry);
+                    ids.push(id);
+                }
+                Ok(ids)
+            },
+            None => {
+                self.audit(AuditOperation::SubscribeRejected);
+                Err(SubscribeError::Full)
+            },
+        }
+    }
+
+    fn replay_sticky(&self, settings: &Settings<E>, id: SubscriptionId, entry: Handler<E>) {
+        let sticky = sync::read(&self.sticky).clone();
+        if let Some(sticky) = sticky {
+            let sticky: &StickyEvent<E> = (*sticky).borrow();
+            let expired = sticky.expires_at.is_some_and(|expires_at| Instant::now() >= expires_at);
+            if !expired && entry.handler.is_synchronous() && entry.handler.accepts(&sticky.event) {
+                let envelope = sticky.envelope(settings.source.as_deref());
+                let stopped = AtomicBool::new(false);
+                let finished = self.dispatch(settings, Recipients::Selected(vec![(id, entry)]), &stopped, |_, handler| handler.call(&envelope, &stopped));
+                self.remove_handlers(finished);
+            }
+        }
+    }
+
+    fn audit(&self, operation: AuditOperation) {
+        self.registry.audit(operation);
+    }
+}
+
+impl<E> Default for EventPublisher<E> {
+    fn default() -> EventPublisher<E> {
+        EventPublisher::new()
+    }
+}
+
+fn intercept_from<E>(settings: &Settings<E>, index: usize, event: &Event<E>, deliver: &dyn Fn(&Event<E>)) {
+    match settings.interceptors.get(index) {
+        Some(interceptor) => interceptor(event, &|event: &Event<E>| intercept_from(settings, index + 1, event, deliver)),
+        None => deliver(event),
+    }
+}
+
+// Span around a publish, covering the handler calls made on the publishing thread.
+#[cfg(feature = "tracing")]
+fn trace_publish<E>(settings: &Settings<E>) -> tracing::span::EnteredSpan {
+    tracing::debug_span!("publish", source = settings.source.as_deref()).entered()
+}
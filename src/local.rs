@@ -0,0 +1,270 @@
+use std::cell::{Cell, RefCell};
+use std::ops::ControlFlow;
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
+
+use {BoxError, ErrorPolicy, Event, HandlerError, HandlerPanic, SubscribeError, SubscriptionId, Unsubscribe};
+
+/// Boxed event handler function, as accepted by LocalEventPublisher::subscribe_handler.
+pub type LocalHandlerBox<E> = Box<dyn Fn(&Event<E>) + 'static>;
+type LocalUntilHandlerBox<E> = Box<dyn Fn(&Event<E>) -> ControlFlow<Unsubscribe> + 'static>;
+type LocalFilterBox<E> = Box<dyn Fn(&Event<E>) -> bool + 'static>;
+type LocalFallibleHandlerBox<E> = Box<dyn Fn(&Event<E>) -> Result<(), BoxError> + 'static>;
+type LocalPanicHook = dyn Fn(&HandlerPanic) + 'static;
+type LocalPanicHookBox = Box<LocalPanicHook>;
+
+enum LocalHandler<E> {
+    Plain(LocalHandlerBox<E>),
+    Filtered(LocalFilterBox<E>, LocalHandlerBox<E>),
+    Until(LocalUntilHandlerBox<E>),
+    Fallible(LocalFallibleHandlerBox<E>),
+}
+
+impl<E> LocalHandler<E> {
+    fn call(&self, event: &Event<E>) -> Result<ControlFlow<Unsubscribe>, BoxError> {
+        match *self {
+            LocalHandler::Plain(ref handler) => handler(event),
+            LocalHandler::Filtered(ref predicate, ref handler) => if predicate(event) { handler(event) },
+            LocalHandler::Until(ref handler) => return Ok(handler(event)),
+            LocalHandler::Fallible(ref handler) => return handler(event).map(ControlFlow::Continue),
+        }
+        Ok(ControlFlow::Continue(()))
+    }
+}
+
+struct LocalEntry<E> {
+    handler: LocalHandler<E>,
+    priority: i32,
+}
+
+// Copy-on-write like the handler list of EventPublisher, so a publish can hold on to the list it started with.
+type LocalHandlerList<E> = Rc<Vec<(SubscriptionId, Rc<LocalEntry<E>>)>>;
+
+/// Single-threaded counterpart of EventPublisher, for GUI and other main-thread-only code: neither the payloads nor the
+/// handlers have to be Send or Sync, so handlers can capture Rc and RefCell state. LocalEventPublisher is neither Send nor
+/// Sync. It has the following subset of the functions of EventPublisher, which behave the same way, so code using only
+/// these can switch between the two: with_max_subscribers, set_panic_hook, subscribe_handler, subscribe_with_priority,
+/// subscribe_args, subscribe_filtered, subscribe_until, subscribe_once, subscribe_handler_mut, subscribe_fallible,
+/// unsubscribe, subscriber_count, is_empty, publish_event, publish_events and publish_event_fallible. As with
+/// EventPublisher, handlers are called in order of descending priority, each publish delivers to the handlers subscribed
+/// when it started, and a panicking handler doesn't keep the event from the others. Everything else, such as envelopes,
+/// interceptors, sticky events, pausing, async handlers and the functions using other threads, is left to EventPublisher.
+pub struct LocalEventPublisher<E> {
+    handlers: RefCell<LocalHandlerList<E>>,
+    next_id: Cell<u64>,
+    max_subscribers: Option<usize>,
+    // Cloned out before it is called, so the hook may replace itself.
+    panic_hook: RefCell<Option<Rc<LocalPanicHook>>>,
+}
+
+impl<E> LocalEventPublisher<E> {
+    /// Local event publisher constructor.
+    pub fn new() -> LocalEventPublisher<E> {
+        LocalEventPublisher {
+            handlers: RefCell::new(Rc::new(Vec::new())),
+            next_id: Cell::new(0),
+            max_subscribers: None,
+            panic_hook: RefCell::new(None),
+        }
+    }
+
+    /// Local event publisher constructor limiting the number of subscriptions, see EventPublisher::with_max_subscribers.
+    /// INPUT:  max_subscribers: usize   maximum number of handlers subscribed at the same time.
+    pub fn with_max_subscribers(max_subscribers: usize) -> LocalEventPublisher<E> {
+        LocalEventPublisher { max_subscribers: Some(max_subscribers), ..LocalEventPublisher::new() }
+    }
+
+    /// Sets a hook called with the panics of handlers, see EventPublisher::set_panic_hook.
+    /// INPUT:  hook: Box<dyn Fn(&HandlerPanic) + 'static>
+    pub fn set_panic_hook(&self, hook: LocalPanicHookBox) {
+        *self.panic_hook.borrow_mut() = Some(Rc::from(hook));
+    }
+
+    /// Subscribes event handler functions to the LocalEventPublisher.
+    /// INPUT:  handler_box: Box<dyn Fn(&Event<E>) + 'static>   function to handle events of the type E.
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    pub fn subscribe_handler(&self, handler_box: LocalHandlerBox<E>) -> Result<SubscriptionId, SubscribeError> {
+        self.insert_handler(LocalHandler::Plain(handler_box), 0)
+    }
+
+    /// Subscribes event handler functions with a priority, see EventPublisher::subscribe_with_priority.
+    /// INPUT:  handler_box: Box<dyn Fn(&Event<E>) + 'static>   function to handle events of the type E.
+    ///         priority: i32   handlers with a higher priority are called first.
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    pub fn subscribe_with_priority(&self, handler_box: LocalHandlerBox<E>, priority: i32) -> Result<SubscriptionId, SubscribeError> {
+        self.insert_handler(LocalHandler::Plain(handler_box), priority)
+    }
+
+    /// Subscribes a handler that is only called with the args of events, skipping Event::Missing.
+    /// INPUT:  handler: Fn(&E) + 'static   handler is called with a reference to the args of every published event.
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    pub fn subscribe_args<F>(&self, handler: F) -> Result<SubscriptionId, SubscribeError> where F: Fn(&E) + 'static {
+        self.subscribe_handler(Box::new(move |event: &Event<E>| {
+            if let Event::Args(ref args) = *event {
+                handler(args);
+            }
+        }))
+    }
+
+    /// Subscribes a handler that is only called for events matching a predicate.
+    /// INPUT:  predicate: Fn(&Event<E>) -> bool + 'static   predicate is called with a reference to every published event.
+    ///         handler: Fn(&Event<E>) + 'static   handler is called with a reference to the events the predicate accepts.
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    pub fn subscribe_filtered<P, F>(&self, predicate: P, handler: F) -> Result<SubscriptionId, SubscribeError> where P: Fn(&Event<E>) -> bool + 'static, F: Fn(&Event<E>) + 'static {
+        self.insert_handler(LocalHandler::Filtered(Box::new(predicate), Box::new(handler)), 0)
+    }
+
+    /// Subscribes a handler that decides after each event whether it wants to stay subscribed, see EventPublisher::subscribe_until.
+    /// INPUT:  handler: Fn(&Event<E>) -> ControlFlow<Unsubscribe> + 'static   handler is called with a reference to every published event.
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    pub fn subscribe_until<F>(&self, handler: F) -> Result<SubscriptionId, SubscribeError> where F: Fn(&Event<E>) -> ControlFlow<Unsubscribe> + 'static {
+        self.insert_handler(LocalHandler::Until(Box::new(handler)), 0)
+    }
+
+    /// Subscribes a handler for a single event. The handler is called with the next published event and is removed afterwards.
+    /// INPUT:  handler: FnOnce(&Event<E>) + 'static   handler is called with a reference to the next published event.
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    pub fn subscribe_once<F>(&self, handler: F) -> Result<SubscriptionId, SubscribeError> where F: FnOnce(&Event<E>) + 'static {
+        let handler = RefCell::new(Some(handler));
+        self.subscribe_until(move |event: &Event<E>| {
+            let handler = handler.borrow_mut().take();
+            if let Some(handler) = handler {
+                handler(event);
+            }
+            ControlFlow::Break(Unsubscribe)
+        })
+    }
+
+    /// Subscribes a handler that needs mutable access to its own state. If the handler publishes to this publisher, it is
+    ///     not called again with the nested event, as it is still busy with the outer one.
+    /// INPUT:  handler: FnMut(&Event<E>) + 'static   handler is called with a reference to every published event.
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    pub fn subscribe_handler_mut<F>(&self, handler: F) -> Result<SubscriptionId, SubscribeError> where F: FnMut(&Event<E>) + 'static {
+        let handler = RefCell::new(handler);
+        self.subscribe_handler(Box::new(move |event: &Event<E>| {
+            if let Ok(mut handler) = handler.try_borrow_mut() {
+                (*handler)(event);
+            }
+        }))
+    }
+
+    /// Subscribes a handler that may fail. The errors it returns are collected by publish_event_fallible; publish_event
+    ///     discards them.
+    /// INPUT:  handler: Fn(&Event<E>) -> Result<(), BoxError> + 'static   handler is called with a reference to every published event.
+    /// OUTPUT: Result<SubscriptionId, SubscribeError>   id of the new subscription, or Err(SubscribeError::Full) if the publisher is at its subscriber limit.
+    pub fn subscribe_fallible<F>(&self, handler: F) -> Result<SubscriptionId, SubscribeError> where F: Fn(&Event<E>) -> Result<(), BoxError> + 'static {
+        self.insert_handler(LocalHandler::Fallible(Box::new(handler)), 0)
+    }
+
+    /// Unsubscribes a handler, see EventPublisher::unsubscribe.
+    /// INPUT:  id: SubscriptionId   id returned when the handler was subscribed.
+    /// OUTPUT: bool   whether a handler was removed.
+    pub fn unsubscribe(&self, id: SubscriptionId) -> bool {
+        let mut handlers = self.handlers.borrow_mut();
+        match handlers.iter().position(|&(subscribed, _)| subscribed == id) {
+            Some(position) => {
+                Rc::make_mut(&mut *handlers).remove(position);
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Number of handlers currently subscribed.
+    pub fn subscriber_count(&self) -> usize {
+        self.handlers.borrow().len()
+    }
+
+    /// Whether no handler is subscribed.
+    pub fn is_empty(&self) -> bool {
+        self.subscriber_count() == 0
+    }
+
+    /// Publishes events, pushing the &Event<E> to all handler functions stored by the event publisher.
+    /// INPUT: event: &Event<E>     Reference to the Event<E> being pushed to all handling functions.
+    pub fn publish_event(&self, event: &Event<E>) {
+        let handlers = self.handlers.borrow().clone();
+        let finished = self.dispatch(&handlers, &[], event, |_, _| ControlFlow::Continue(()));
+        self.remove_handlers(finished);
+    }
+
+    /// Publishes a batch of events, taking the snapshot of the handlers once for the whole batch, see EventPublisher::publish_events.
+    /// INPUT: events: IntoIterator<Item = &Event<E>>   events to publish, e.g. a slice or an iterator over references.
+    pub fn publish_events<'a, I>(&self, events: I) where I: IntoIterator<Item = &'a Event<E>>, E: 'a {
+        let handlers = self.handlers.borrow().clone();
+        let mut finished = Vec::new();
+        for event in events {
+            let mut done = self.dispatch(&handlers, &finished, event, |_, _| ControlFlow::Continue(()));
+            finished.append(&mut done);
+        }
+        self.remove_handlers(finished);
+    }
+
+    /// Publishes an event like publish_event and collects the errors returned by handlers subscribed with subscribe_fallible.
+    /// INPUT:  event: &Event<E>
+    ///         policy: ErrorPolicy   whether to keep delivering the event after a handler failed.
+    /// OUTPUT: Result<(), Vec<HandlerError>>   Ok if no handler failed, otherwise the errors in the order the handlers were called.
+    pub fn publish_event_fallible(&self, event: &Event<E>, policy: ErrorPolicy) -> Result<(), Vec<HandlerError>> {
+        let handlers = self.handlers.borrow().clone();
+        let mut errors = Vec::new();
+        let finished = self.dispatch(&handlers, &[], event, |subscription, error| {
+            errors.push(HandlerError { subscription, error });
+            match policy {
+                ErrorPolicy::CollectAll => ControlFlow::Continue(()),
+                ErrorPolicy::StopAtFirst => ControlFlow::Break(()),
+            }
+        });
+        self.remove_handlers(finished);
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+
+    // Calls the handlers that aren't in skip. Returns the handlers that asked to be unsubscribed.
+    fn dispatch<F>(&self, handlers: &LocalHandlerList<E>, skip: &[SubscriptionId], event: &Event<E>, mut failed: F) -> Vec<SubscriptionId>
+        where F: FnMut(SubscriptionId, BoxError) -> ControlFlow<()> {
+        let mut finished = Vec::new();
+        for &(id, ref entry) in handlers.iter().filter(|&&(id, _)| !skip.contains(&id)) {
+            match panic::catch_unwind(AssertUnwindSafe(|| entry.handler.call(event))) {
+                Ok(Ok(ControlFlow::Continue(()))) => {},
+                Ok(Ok(ControlFlow::Break(Unsubscribe))) => finished.push(id),
+                Ok(Err(error)) => {
+                    if failed(id, error).is_break() {
+                        break;
+                    }
+                },
+                Err(payload) => {
+                    let hook = self.panic_hook.borrow().clone();
+                    if let Some(hook) = hook {
+                        hook(&HandlerPanic::new(id, payload));
+                    }
+                },
+            }
+        }
+        finished
+    }
+
+    fn remove_handlers(&self, ids: Vec<SubscriptionId>) {
+        for id in ids {
+            self.unsubscribe(id);
+        }
+    }
+
+    fn insert_handler(&self, handler: LocalHandler<E>, priority: i32) -> Result<SubscriptionId, SubscribeError> {
+        let mut handlers = self.handlers.borrow_mut();
+        if let Some(max_subscribers) = self.max_subscribers {
+            if handlers.len() >= max_subscribers {
+                return Err(SubscribeError::Full);
+            }
+        }
+        let id = SubscriptionId(self.next_id.get() + 1);
+        self.next_id.set(id.0);
+        let position = handlers.iter().position(|(_, entry)| entry.priority < priority).unwrap_or(handlers.len());
+        Rc::make_mut(&mut *handlers).insert(position, (id, Rc::new(LocalEntry { handler, priority })));
+        Ok(id)
+    }
+}
+
+impl<E> Default for LocalEventPublisher<E> {
+    fn default() -> LocalEventPublisher<E> {
+        LocalEventPublisher::new()
+    }
+}
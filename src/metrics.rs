@@ -0,0 +1,80 @@
+use std::cmp;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use SubscriptionId;
+
+/// Metrics of an EventPublisher, as returned by EventPublisher::metrics. Only what happened while metrics were on is counted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublisherMetrics {
+    /// Events delivered to the handlers, including the ones nobody was subscribed to.
+    pub events_published: u64,
+    /// Handler calls, including the ones of handlers unsubscribed since.
+    pub handlers_invoked: u64,
+    /// Metrics of the current subscriptions, in subscription order.
+    pub handlers: Vec<HandlerMetrics>,
+}
+
+impl PublisherMetrics {
+    /// Handler with the slowest single call, the likely cause of stutters.
+    pub fn slowest_handler(&self) -> Option<&HandlerMetrics> {
+        self.handlers.iter().filter(|handler| handler.calls > 0).max_by_key(|handler| handler.slowest_call)
+    }
+}
+
+/// Metrics of one subscription.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandlerMetrics {
+    pub subscription: SubscriptionId,
+    /// Group the handler was subscribed to with subscribe_named, if any.
+    pub group: Option<String>,
+    pub calls: u64,
+    /// Time spent in the handler, over all calls.
+    pub total_time: Duration,
+    pub slowest_call: Duration,
+}
+
+// Counters of a publisher while metrics are on.
+pub(crate) struct Metrics {
+    events_published: AtomicU64,
+    handlers_invoked: AtomicU64,
+}
+
+impl Metrics {
+    pub(crate) fn new() -> Metrics {
+        Metrics { events_published: AtomicU64::new(0), handlers_invoked: AtomicU64::new(0) }
+    }
+
+    pub(crate) fn record_publish(&self) {
+        self.events_published.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_call(&self, stats: &mut CallStats, elapsed: Duration) {
+        self.handlers_invoked.fetch_add(1, Ordering::Relaxed);
+        stats.calls += 1;
+        stats.total_time += elapsed;
+        stats.slowest_call = cmp::max(stats.slowest_call, elapsed);
+    }
+
+    pub(crate) fn report(&self, handlers: Vec<HandlerMetrics>) -> PublisherMetrics {
+        PublisherMetrics {
+            events_published: self.events_published.load(Ordering::Relaxed),
+            handlers_invoked: self.handlers_invoked.load(Ordering::Relaxed),
+            handlers,
+        }
+    }
+}
+
+// Per subscription counters, kept with the subscription.
+#[derive(Default)]
+pub(crate) struct CallStats {
+    calls: u64,
+    total_time: Duration,
+    slowest_call: Duration,
+}
+
+impl CallStats {
+    pub(crate) fn report(&self, subscription: SubscriptionId, group: Option<String>) -> HandlerMetrics {
+        HandlerMetrics { subscription, group, calls: self.calls, total_time: self.total_time, slowest_call: self.slowest_call }
+    }
+}
@@ -0,0 +1,117 @@
+use std::any::Any;
+use std::collections::{BTreeMap, BTreeSet};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use sync;
+use {Event, HandlerBox};
+
+// Kept behind a trait object, like the sticky event, so only subscribe_ordered needs E: Clone + Send, not every publisher.
+pub(crate) trait OrderedHandler<E>: Send + Sync {
+    // Keeps a copy of the event until it is its turn.
+    fn push(&self, sequence: u64, event: &Event<E>);
+    // Calls the handler with the kept events in sequence order, as long as ready says no earlier publish is in flight.
+    //     Every call is reported, with the payload if the handler panicked.
+    fn drain(&self, ready: &dyn Fn(u64) -> bool, report: &dyn Fn(Result<(), Box<dyn Any + Send>>));
+    // Same handler with a queue of its own, for a forked publisher whose sequence numbers are unrelated to these.
+    fn fork(&self) -> Arc<dyn OrderedHandler<E>>;
+}
+
+pub(crate) struct OrderedQueue<E> {
+    handler: Arc<HandlerBox<E>>,
+    state: Mutex<QueueState<E>>,
+}
+
+struct QueueState<E> {
+    pending: BTreeMap<u64, Event<E>>,
+    // Whether a thread is calling the handler, which then goes on with the events kept meanwhile.
+    delivering: bool,
+}
+
+impl<E> OrderedQueue<E> {
+    pub(crate) fn new(handler: HandlerBox<E>) -> OrderedQueue<E> {
+        OrderedQueue::sharing(Arc::new(handler))
+    }
+
+    fn sharing(handler: Arc<HandlerBox<E>>) -> OrderedQueue<E> {
+        OrderedQueue { handler, state: Mutex::new(QueueState { pending: BTreeMap::new(), delivering: false }) }
+    }
+}
+
+impl<E> OrderedHandler<E> for OrderedQueue<E> where E: Clone + Send + 'static {
+    fn push(&self, sequence: u64, event: &Event<E>) {
+        sync::lock(&self.state).pending.insert(sequence, event.clone());
+    }
+
+    fn drain(&self, ready: &dyn Fn(u64) -> bool, report: &dyn Fn(Result<(), Box<dyn Any + Send>>)) {
+        let mut state = sync::lock(&self.state);
+        if state.delivering {
+            return;
+        }
+        loop {
+            let sequence = match state.pending.keys().next() {
+                Some(&sequence) => sequence,
+                None => return,
+            };
+            if !ready(sequence) {
+                return;
+            }
+            let event = state.pending.remove(&sequence).expect("sequence was just looked up");
+            state.delivering = true;
+            drop(state);
+            report(panic::catch_unwind(AssertUnwindSafe(|| (self.handler)(&event))));
+            state = sync::lock(&self.state);
+            state.delivering = false;
+        }
+    }
+
+    fn fork(&self) -> Arc<dyn OrderedHandler<E>> {
+        Arc::new(OrderedQueue::sharing(self.handler.clone()))
+    }
+}
+
+// Sequence numbers of the publishes that haven't finished yet, so ordered handlers can tell whether an earlier event may
+//     still reach them. W identifies an ordered handler waiting for an earlier publish to finish.
+pub(crate) struct InFlight<W> {
+    state: Mutex<InFlightState<W>>,
+}
+
+struct InFlightState<W> {
+    sequences: BTreeSet<u64>,
+    waiting: Vec<W>,
+}
+
+impl<W> InFlight<W> {
+    pub(crate) fn new() -> InFlight<W> {
+        InFlight { state: Mutex::new(InFlightState { sequences: BTreeSet::new(), waiting: Vec::new() }) }
+    }
+
+    // Hands out the next sequence number under the lock, so a publish is in flight from the moment it has its number.
+    pub(crate) fn begin(&self, sequence: &AtomicU64) -> u64 {
+        let mut state = sync::lock(&self.state);
+        let next = sequence.fetch_add(1, Ordering::SeqCst) + 1;
+        state.sequences.insert(next);
+        next
+    }
+
+    // Returns the handlers that were waiting, to be drained again.
+    pub(crate) fn finish(&self, sequence: u64) -> Vec<W> {
+        let mut state = sync::lock(&self.state);
+        state.sequences.remove(&sequence);
+        state.waiting.drain(..).collect()
+    }
+
+    // Whether the event with the given sequence number may be delivered. If not, the handler is kept until a publish finishes;
+    //     checked and kept under the same lock, so the publish it waits for can't finish in between.
+    pub(crate) fn ready_or_wait<F>(&self, sequence: u64, waiting: F) -> bool where F: FnOnce() -> W {
+        let mut state = sync::lock(&self.state);
+        match state.sequences.iter().next() {
+            Some(&earliest) if earliest < sequence => {
+                state.waiting.push(waiting());
+                false
+            },
+            _ => true,
+        }
+    }
+}
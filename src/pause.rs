@@ -0,0 +1,50 @@
+use std::collections::VecDeque;
+
+use queue::{Backpressure, EventQueue};
+use Event;
+
+/// What EventPublisher::publish_event does with the events published while the publisher is paused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WhilePaused {
+    /// Keep up to the given number of events for resume, discarding the oldest ones beyond that. A limit of 0 is treated as 1.
+    Buffer(usize),
+    /// Discard them.
+    Discard,
+}
+
+// Kept behind a trait object, like the sticky event, so only pausing needs E: Clone + Send, not every publisher.
+pub(crate) trait Paused<E> {
+    fn hold(&self, event: &Event<E>);
+    fn release(&self) -> VecDeque<Event<E>>;
+}
+
+pub(crate) struct PauseBuffer<E> {
+    // None when the events are discarded.
+    queue: Option<EventQueue<E>>,
+}
+
+impl<E> PauseBuffer<E> {
+    pub(crate) fn new(mode: WhilePaused) -> PauseBuffer<E> {
+        let queue = match mode {
+            WhilePaused::Buffer(limit) => Some(EventQueue::bounded(limit, Backpressure::DropOldest)),
+            WhilePaused::Discard => None,
+        };
+        PauseBuffer { queue }
+    }
+}
+
+impl<E> Paused<E> for PauseBuffer<E> where E: Clone {
+    fn hold(&self, event: &Event<E>) {
+        if let Some(ref queue) = self.queue {
+            // DropOldest never rejects.
+            let _ = queue.push(event.clone());
+        }
+    }
+
+    fn release(&self) -> VecDeque<Event<E>> {
+        match self.queue {
+            Some(ref queue) => queue.take_all(),
+            None => VecDeque::new(),
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Read};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use frame::{read_frame, write_frame};
+use sync;
+use {Codec, CodecError, Event, EventPublisher, Schema, SerializableEvent};
+
+/// Publisher writing every event it publishes to an append-only log file before handing it to its handlers, as a
+/// foundation for event sourcing: on startup, subscribe the handlers and call replay to rebuild their state from the log.
+/// Events are stored as length-prefixed frames encoded with a Codec. Handlers are subscribed on the underlying
+/// EventPublisher, see publisher; events published on that directly are not logged. A log opened with open_versioned tags
+/// every event with a schema version, so it can still be replayed after the payload type changed.
+pub struct PersistentPublisher<E, C> {
+    publisher: EventPublisher<E>,
+    codec: C,
+    schema: Option<Schema<E>>,
+    path: PathBuf,
+    log: Mutex<File>,
+}
+
+impl<E, C> PersistentPublisher<E, C> where E: SerializableEvent, C: Codec {
+    /// Persistent publisher constructor, opening the log at path or creating it if it doesn't exist. A frame left
+    ///     incomplete by a crash while it was written is cut off the end of the log.
+    /// INPUT:  path: AsRef<Path>   log file.
+    ///         codec: Codec   codec events are encoded with; a log must always be opened with the same codec.
+    /// OUTPUT: io::Result<PersistentPublisher<E, C>>   Err if the log could not be opened.
+    pub fn open<P>(path: P, codec: C) -> io::Result<PersistentPublisher<E, C>> where P: AsRef<Path> {
+        let path = path.as_ref().to_path_buf();
+        let log = OpenOptions::new().read(true).append(true).create(true).open(&path)?;
+        let complete = complete_len(&log)?;
+        if complete < log.metadata()?.len() {
+            log.set_len(complete)?;
+        }
+        Ok(PersistentPublisher { publisher: EventPublisher::new(), codec, schema: None, path, log: Mutex::new(log) })
+    }
+
+    /// Persistent publisher constructor like open, for a log whose events are tagged with a schema version. Events logged
+    ///     with an older version are upgraded with the schema when they are replayed or compacted; the log keeps them as they
+    ///     were written. A log must always be opened the same way, either versioned or not.
+    /// INPUT:  path: AsRef<Path>   log file.
+    ///         codec: Codec   codec events are encoded with; a log must always be opened with the same codec.
+    ///         schema: Schema<E>   current version of E, which new events are logged with, and how to upgrade older ones.
+    /// OUTPUT: io::Result<PersistentPublisher<E, C>>   Err if the log could not be opened.
+    pub fn open_versioned<P>(path: P, codec: C, schema: Schema<E>) -> io::Result<PersistentPublisher<E, C>> where P: AsRef<Path> {
+        let mut publisher = PersistentPublisher::open(path, codec)?;
+        publisher.schema = Some(schema);
+        Ok(publisher)
+    }
+
+    /// Publisher the events are dispatched on, for subscribing handlers.
+    pub fn publisher(&self) -> &EventPublisher<E> {
+        &self.publisher
+    }
+
+    /// Appends an event to the log, then publishes it with publish_event. The event is handed to the operating system
+    ///     before the handlers see it; call sync to make sure it has reached the disk. The log is not locked while the handlers
+    ///     run, so they may publish themselves; events published by several threads at once may therefore reach the handlers
+    ///     in another order than they were logged in. replay always follows the order of the log.
+    /// INPUT:  event: &Event<E>
+    /// OUTPUT: io::Result<()>   Err if the event could not be encoded or written, in which case it is not published.
+    pub fn publish_event(&self, event: &Event<E>) -> io::Result<()> {
+        let frame = match self.schema {
+            Some(ref schema) => schema.encode(&self.codec, event),
+            None => self.codec.encode(event),
+        }.map_err(invalid_data)?;
+        write_frame(&mut *sync::lock(&self.log), &frame)?;
+        self.publisher.publish_event(event);
+        Ok(())
+    }
+
+    /// Publishes every event in the log to the handlers, in the order they were logged, without logging them again.
+    ///     Events published while the log is replayed are appended after it and not replayed.
+    /// OUTPUT: io::Result<usize>   number of events replayed, or Err if the log could not be read or decoded.
+    pub fn replay(&self) -> io::Result<usize> {
+        let len = sync::lock(&self.log).metadata()?.len();
+        let mut reader = BufReader::new(File::open(&self.path)?.take(len));
+        let mut replayed = 0;
+        while let Some(frame) = read_frame(&mut reader)? {
+            let event = self.decode(&frame)?;
+            self.publisher.publish_event(&event);
+            replayed += 1;
+        }
+        Ok(replayed)
+    }
+
+    /// Rewrites the log with only the events retain returns true for, e.g. to drop events superseded by later ones. The new
+    ///     log is written next to the old one and then moved over it, so a crash leaves one of them intact. Publishing
+    ///     waits until compaction has finished.
+    /// INPUT:  retain: FnMut(&Event<E>) -> bool   called with every logged event, in order. Must not publish to this publisher.
+    /// OUTPUT: io::Result<usize>   number of events kept, or Err if the log could not be read, decoded or rewritten.
+    pub fn compact<F>(&self, mut retain: F) -> io::Result<usize> where F: FnMut(&Event<E>) -> bool {
+        let mut log = sync::lock(&self.log);
+        let len = log.metadata()?.len();
+        let mut reader = BufReader::new(File::open(&self.path)?.take(len));
+        let mut compacted_path = self.path.clone().into_os_string();
+        compacted_path.push(".compact");
+        let compacted_path = PathBuf::from(compacted_path);
+        let mut writer = BufWriter::new(File::create(&compacted_path)?);
+        let mut kept = 0;
+        while let Some(frame) = read_frame(&mut reader)? {
+            let event = self.decode(&frame)?;
+            if retain(&event) {
+                write_frame(&mut writer, &frame)?;
+                kept += 1;
+            }
+        }
+        let compacted = writer.into_inner().map_err(io::IntoInnerError::into_error)?;
+        compacted.sync_all()?;
+        fs::rename(&compacted_path, &self.path)?;
+        *log = OpenOptions::new().read(true).append(true).open(&self.path)?;
+        Ok(kept)
+    }
+
+    fn decode(&self, frame: &[u8]) -> io::Result<Event<E>> {
+        match self.schema {
+            Some(ref schema) => schema.decode(&self.codec, frame),
+            None => self.codec.decode(frame),
+        }.map_err(invalid_data)
+    }
+
+    /// Flushes the log to the disk, so the events published so far survive a crash of the machine.
+    /// OUTPUT: io::Result<()>
+    pub fn sync(&self) -> io::Result<()> {
+        sync::lock(&self.log).sync_data()
+    }
+}
+
+fn invalid_data(error: CodecError) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+// Length of the complete frames at the start of the log.
+fn complete_len(log: &File) -> io::Result<u64> {
+    let mut reader = BufReader::new(log);
+    let mut complete = 0;
+    loop {
+        match read_frame(&mut reader) {
+            Ok(Some(frame)) => complete += 4 + frame.len() as u64,
+            Ok(None) => return Ok(complete),
+            Err(ref error) if error.kind() == io::ErrorKind::UnexpectedEof => return Ok(complete),
+            Err(error) => return Err(error),
+        }
+    }
+}
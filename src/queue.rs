@@ -0,0 +1,259 @@
+use std::cmp;
+use std::collections::VecDeque;
+use std::mem;
+use std::sync::{Condvar, Mutex};
+use std::time::Duration;
+
+use error::PublishError;
+use sync;
+use time::Instant;
+use {Event, EventPublisher, Shutdown};
+
+/// What a bounded event queue does with an event published while it is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backpressure {
+    /// Wait until there is room. The queue has to be drained by another thread, otherwise the publisher waits forever.
+    Block,
+    /// Discard the event being published.
+    DropNewest,
+    /// Discard the oldest queued event to make room. With events of several priorities queued, the oldest of the lowest
+    ///     priority is discarded. An event of a lower priority than every queued one is discarded itself instead, as with
+    ///     DropNewest, so a low priority event never displaces a more important one.
+    DropOldest,
+    /// Refuse the event with PublishError::Full, which hands it back.
+    Reject,
+}
+
+// Queue behind QueuedPublisher and Dispatcher.
+pub(crate) struct EventQueue<E> {
+    state: Mutex<QueueState<E>>,
+    changed: Condvar,
+    capacity: Option<usize>,
+    backpressure: Backpressure,
+}
+
+struct Queued<E> {
+    priority: u8,
+    // Events past this time are dropped instead of being handed out.
+    expires_at: Option<Instant>,
+    event: Event<E>,
+}
+
+impl<E> Queued<E> {
+    fn is_expired(&self, now: Instant) -> bool {
+        self.expires_at.is_some_and(|expires_at| now >= expires_at)
+    }
+}
+
+struct QueueState<E> {
+    // Highest priority first, in the order they were pushed within a priority.
+    events: VecDeque<Queued<E>>,
+    closed: bool,
+    // Events discarded by close, and expired ones dropped instead of being handed out.
+    discarded: usize,
+}
+
+impl<E> EventQueue<E> {
+    pub(crate) fn unbounded() -> EventQueue<E> {
+        EventQueue::new(None, Backpressure::Block)
+    }
+
+    pub(crate) fn bounded(capacity: usize, backpressure: Backpressure) -> EventQueue<E> {
+        EventQueue::new(Some(cmp::max(capacity, 1)), backpressure)
+    }
+
+    fn new(capacity: Option<usize>, backpressure: Backpressure) -> EventQueue<E> {
+        EventQueue {
+            state: Mutex::new(QueueState { events: VecDeque::new(), closed: false, discarded: 0 }),
+            changed: Condvar::new(),
+            capacity,
+            backpressure,
+        }
+    }
+
+    pub(crate) fn push(&self, event: Event<E>) -> Result<(), PublishError<E>> {
+        self.push_with_priority(event, 0)
+    }
+
+    pub(crate) fn push_with_priority(&self, event: Event<E>, priority: u8) -> Result<(), PublishError<E>> {
+        self.push_queued(Queued { priority, expires_at: None, event })
+    }
+
+    pub(crate) fn push_with_ttl(&self, event: Event<E>, ttl: Duration) -> Result<(), PublishError<E>> {
+        self.push_queued(Queued { priority: 0, expires_at: Some(Instant::now() + ttl), event })
+    }
+
+    fn push_queued(&self, queued: Queued<E>) -> Result<(), PublishError<E>> {
+        let mut state = sync::lock(&self.state);
+        if let Some(capacity) = self.capacity {
+            while state.events.len() >= capacity && !state.closed {
+                match self.backpressure {
+                    Backpressure::Block => state = sync::wait(&self.changed, state),
+                    Backpressure::DropNewest => return Ok(()),
+                    Backpressure::DropOldest => {
+                        let lowest = state.events.back().map_or(queued.priority, |queued| queued.priority);
+                        if queued.priority < lowest {
+                            return Ok(());
+                        }
+                        let oldest = state.events.iter().position(|other| other.priority == lowest);
+                        if let Some(oldest) = oldest {
+                            state.events.remove(oldest);
+                        }
+                    },
+                    Backpressure::Reject => return Err(PublishError::Full(queued.event)),
+                }
+            }
+        }
+        if state.closed {
+            return Err(PublishError::Closed(queued.event));
+        }
+        // After every event of the same or a higher priority, so events of one priority stay in the order they were pushed.
+        let position = state.events.iter().rposition(|other| other.priority >= queued.priority).map_or(0, |last| last + 1);
+        state.events.insert(position, queued);
+        self.changed.notify_all();
+        Ok(())
+    }
+
+    // Waits for the next event. None once the queue is closed and drained.
+    pub(crate) fn pop_blocking(&self) -> Option<Event<E>> {
+        let mut state = sync::lock(&self.state);
+        loop {
+            if let Some(queued) = state.events.pop_front() {
+                self.changed.notify_all();
+                if queued.is_expired(Instant::now()) {
+                    state.discarded += 1;
+                    continue;
+                }
+                return Some(queued.event);
+            }
+            if state.closed {
+                return None;
+            }
+            state = sync::wait(&self.changed, state);
+        }
+    }
+
+    pub(crate) fn take_all(&self) -> VecDeque<Event<E>> {
+        let mut state = sync::lock(&self.state);
+        let events = mem::take(&mut state.events);
+        self.changed.notify_all();
+        let now = Instant::now();
+        let (expired, events): (VecDeque<_>, VecDeque<_>) = events.into_iter().partition(|queued| queued.is_expired(now));
+        state.discarded += expired.len();
+        events.into_iter().map(|queued| queued.event).collect()
+    }
+
+    // Refuses events pushed from now on. Returns the number of events discarded so far, including by earlier calls.
+    pub(crate) fn close(&self, shutdown: Shutdown) -> usize {
+        let mut state = sync::lock(&self.state);
+        state.closed = true;
+        if shutdown == Shutdown::Discard {
+            state.discarded += state.events.len();
+            state.events.clear();
+        }
+        self.changed.notify_all();
+        state.discarded
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        sync::lock(&self.state).events.len()
+    }
+
+    pub(crate) fn discarded(&self) -> usize {
+        sync::lock(&self.state).discarded
+    }
+}
+
+/// Publisher that defers dispatch: publish_event only queues the event, and handlers run when dispatch_pending is called, on
+/// the thread calling it. Lets game loops and UI code decide exactly when handlers run. Events published with
+/// publish_with_priority are dispatched ahead of the ones of a lower priority.
+/// Handlers are subscribed on the underlying EventPublisher, see publisher.
+pub struct QueuedPublisher<E> {
+    publisher: EventPublisher<E>,
+    pending: EventQueue<E>,
+}
+
+impl<E> QueuedPublisher<E> {
+    /// Queued publisher constructor.
+    pub fn new() -> QueuedPublisher<E> {
+        QueuedPublisher::from_publisher(EventPublisher::new())
+    }
+
+    /// Queued publisher constructor dispatching to an already configured publisher.
+    /// INPUT:  publisher: EventPublisher<E>   publisher the queued events are published on.
+    pub fn from_publisher(publisher: EventPublisher<E>) -> QueuedPublisher<E> {
+        QueuedPublisher { publisher, pending: EventQueue::unbounded() }
+    }
+
+    /// Queued publisher constructor for a queue holding at most capacity events.
+    /// INPUT:  publisher: EventPublisher<E>   publisher the queued events are published on.
+    ///         capacity: usize   maximum number of queued events. A capacity of 0 is treated as 1.
+    ///         backpressure: Backpressure   what publish_event does while the queue is full.
+    pub fn with_capacity(publisher: EventPublisher<E>, capacity: usize, backpressure: Backpressure) -> QueuedPublisher<E> {
+        QueuedPublisher { publisher, pending: EventQueue::bounded(capacity, backpressure) }
+    }
+
+    /// Publisher the queued events are dispatched on, for subscribing handlers.
+    pub fn publisher(&self) -> &EventPublisher<E> {
+        &self.publisher
+    }
+
+    /// Queues an event for the next dispatch_pending.
+    /// INPUT:  event: Event<E>
+    /// OUTPUT: Result<(), PublishError<E>>   Err(PublishError::Full) with the event if the queue is full and its backpressure is Reject.
+    pub fn publish_event(&self, event: Event<E>) -> Result<(), PublishError<E>> {
+        self.pending.push(event)
+    }
+
+    /// Queues an event for the next dispatch_pending, ahead of the queued events of a lower priority, e.g. so quitting or
+    ///     damage is handled before cosmetic events queued earlier. Events of the same priority keep the order they were
+    ///     queued in; publish_event queues with priority 0.
+    /// INPUT:  event: Event<E>
+    ///         priority: u8   events with a higher priority are dispatched first.
+    /// OUTPUT: Result<(), PublishError<E>>   Err(PublishError::Full) with the event if the queue is full and its backpressure is Reject.
+    pub fn publish_with_priority(&self, event: Event<E>, priority: u8) -> Result<(), PublishError<E>> {
+        self.pending.push_with_priority(event, priority)
+    }
+
+    /// Queues an event for the next dispatch_pending that is dropped instead if it hasn't been dispatched within ttl, e.g. a
+    ///     "current position" that is worse than no event once it is stale. Queued with priority 0.
+    /// INPUT:  event: Event<E>
+    ///         ttl: Duration   how long the event stays deliverable.
+    /// OUTPUT: Result<(), PublishError<E>>   Err(PublishError::Full) with the event if the queue is full and its backpressure is Reject.
+    pub fn publish_with_ttl(&self, event: Event<E>, ttl: Duration) -> Result<(), PublishError<E>> {
+        self.pending.push_with_ttl(event, ttl)
+    }
+
+    /// Publishes the queued events, highest priority first and in the order they were queued within a priority, dropping the
+    ///     ones whose time-to-live has passed. Events queued by handlers meanwhile are left for the next
+    ///     call, so a handler that keeps publishing cannot hold up the caller forever.
+    /// OUTPUT: usize   number of events dispatched, not counting dropped ones.
+    pub fn dispatch_pending(&self) -> usize {
+        let pending = self.pending.take_all();
+        let dispatched = pending.len();
+        for event in pending {
+            self.publisher.publish_event(&event);
+        }
+        dispatched
+    }
+
+    /// Number of events dropped by dispatch_pending because their time-to-live had passed.
+    pub fn discarded(&self) -> usize {
+        self.pending.discarded()
+    }
+
+    /// Number of events waiting for dispatch_pending, including expired ones not dropped yet.
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<E> Default for QueuedPublisher<E> {
+    fn default() -> QueuedPublisher<E> {
+        QueuedPublisher::new()
+    }
+}
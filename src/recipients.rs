@@ -0,0 +1,52 @@
+use std::ops::Deref;
+use std::sync::Arc;
+
+// Handlers a publish is delivered to. Shares the snapshot of the handler list as long as every handler in it takes the
+//     event, which is the common case, so publishing to handlers that don't filter doesn't allocate a list of its own.
+pub(crate) enum Recipients<T> {
+    All(Arc<Vec<T>>),
+    Selected(Vec<T>),
+}
+
+impl<T> Recipients<T> where T: Clone {
+    // keep is called once per handler, in order.
+    pub(crate) fn select<P>(handlers: &Arc<Vec<T>>, mut keep: P) -> Recipients<T> where P: FnMut(&T) -> bool {
+        match handlers.iter().position(|handler| !keep(handler)) {
+            None => Recipients::All(handlers.clone()),
+            Some(rejected) => {
+                let mut selected = handlers[..rejected].to_vec();
+                selected.extend(handlers[rejected + 1..].iter().filter(|&handler| keep(handler)).cloned());
+                Recipients::Selected(selected)
+            },
+        }
+    }
+
+    pub(crate) fn into_vec(self) -> Vec<T> {
+        match self {
+            Recipients::All(handlers) => Arc::try_unwrap(handlers).unwrap_or_else(|handlers| handlers.to_vec()),
+            Recipients::Selected(handlers) => handlers,
+        }
+    }
+
+    // Copies a shared snapshot first, for reordering the handlers.
+    pub(crate) fn make_mut(&mut self) -> &mut [T] {
+        if let Recipients::All(ref handlers) = *self {
+            *self = Recipients::Selected(handlers.to_vec());
+        }
+        match *self {
+            Recipients::Selected(ref mut handlers) => handlers,
+            Recipients::All(_) => unreachable!("shared snapshot was just copied"),
+        }
+    }
+}
+
+impl<T> Deref for Recipients<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        match *self {
+            Recipients::All(ref handlers) => handlers,
+            Recipients::Selected(ref handlers) => handlers,
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+
+use sync;
+use time::SystemTime;
+use {Event, EventPublisher};
+
+/// Event captured by a RecordingPublisher, with the time it was published at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventRecord<E> {
+    pub published_at: SystemTime,
+    pub event: Event<E>,
+}
+
+/// Test double capturing every event published on its publisher, so a test can check what the code under test publishes
+/// without subscribing handlers of its own. Hand publisher to the code under test, then inspect records or use
+/// assert_published. Events are captured by an interceptor added to the publisher, so capturing doesn't count as a
+/// subscription and the events are still delivered to any handlers subscribed; like other interceptors, it doesn't see
+/// publish_owned and publish_sticky.
+pub struct RecordingPublisher<E> {
+    publisher: EventPublisher<E>,
+    records: Arc<Mutex<Vec<EventRecord<E>>>>,
+}
+
+impl<E> RecordingPublisher<E> where E: Clone + Send + 'static {
+    /// Recording publisher constructor.
+    pub fn new() -> RecordingPublisher<E> {
+        RecordingPublisher::from_publisher(EventPublisher::new())
+    }
+
+    /// Recording publisher constructor capturing the events of an already configured publisher. Interceptors added to it
+    ///     before run first, so the events are captured as they left them.
+    /// INPUT:  publisher: EventPublisher<E>   publisher whose events are captured.
+    pub fn from_publisher(publisher: EventPublisher<E>) -> RecordingPublisher<E> {
+        let records = Arc::new(Mutex::new(Vec::new()));
+        let recorder = records.clone();
+        publisher.add_interceptor(move |event: &Event<E>, next: &dyn Fn(&Event<E>)| {
+            sync::lock(&recorder).push(EventRecord { published_at: SystemTime::now(), event: event.clone() });
+            next(event);
+        });
+        RecordingPublisher { publisher, records }
+    }
+
+    /// Publisher whose events are captured, for handing to the code under test.
+    pub fn publisher(&self) -> &EventPublisher<E> {
+        &self.publisher
+    }
+
+    /// Events captured so far, oldest first.
+    /// OUTPUT: Vec<EventRecord<E>>
+    pub fn records(&self) -> Vec<EventRecord<E>> {
+        sync::lock(&self.records).clone()
+    }
+
+    /// Events captured so far without their timestamps, oldest first.
+    /// OUTPUT: Vec<Event<E>>
+    pub fn events(&self) -> Vec<Event<E>> {
+        sync::lock(&self.records).iter().map(|record| record.event.clone()).collect()
+    }
+
+    /// Number of events captured so far.
+    pub fn len(&self) -> usize {
+        sync::lock(&self.records).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Forgets the events captured so far, e.g. between the steps of a test.
+    /// OUTPUT: void
+    pub fn clear(&self) {
+        sync::lock(&self.records).clear();
+    }
+
+    /// Publishes the captured events again, oldest first, on another publisher, e.g. to feed what one component published
+    ///     to another component under test.
+    /// INPUT:  publisher: &EventPublisher<E>   publisher the events are replayed on.
+    /// OUTPUT: void
+    pub fn replay_to(&self, publisher: &EventPublisher<E>) {
+        for record in self.records() {
+            publisher.publish_event(&record.event);
+        }
+    }
+
+    /// Panics unless an event matching the predicate was captured.
+    /// INPUT:  predicate: Fn(&Event<E>) -> bool
+    /// OUTPUT: void
+    pub fn assert_published<P>(&self, predicate: P) where E: Debug, P: Fn(&Event<E>) -> bool {
+        let events = self.events();
+        if !events.iter().any(predicate) {
+            panic!("no matching event was published, published events: {:?}", events);
+        }
+    }
+
+    /// Panics if an event matching the predicate was captured.
+    /// INPUT:  predicate: Fn(&Event<E>) -> bool
+    /// OUTPUT: void
+    pub fn assert_not_published<P>(&self, predicate: P) where E: Debug, P: Fn(&Event<E>) -> bool {
+        if let Some(event) = self.events().iter().find(|&event| predicate(event)) {
+            panic!("unexpected event was published: {:?}", event);
+        }
+    }
+}
+
+impl<E> Default for RecordingPublisher<E> where E: Clone + Send + 'static {
+    fn default() -> RecordingPublisher<E> {
+        RecordingPublisher::new()
+    }
+}
@@ -0,0 +1,345 @@
+use std::collections::BTreeMap;
+use std::io::{self, Read, Write};
+use std::marker::PhantomData;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, Shutdown, SocketAddr, TcpListener, TcpStream};
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+#[cfg(unix)]
+use std::path::PathBuf;
+use std::sync::{mpsc, Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use frame::{read_frame, write_frame};
+use schema;
+use sync;
+use {Backoff, Codec, Event, EventPublisher, RetryPolicy, Schema, SerializableEvent};
+
+// How long the listener waits after accept failed for a reason that doesn't go away by itself straight away.
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(50);
+// How long shutdown waits for the listener thread to end after waking it up.
+const STOP_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Address a RemoteSubscriber listens on and a RemotePublisher connects to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Endpoint {
+    /// TCP socket. Port 0 lets RemoteSubscriber pick a free port, see RemoteSubscriber::endpoint.
+    Tcp(SocketAddr),
+    /// Unix domain socket at the given path.
+    #[cfg(unix)]
+    Unix(PathBuf),
+}
+
+enum Connection {
+    Tcp(TcpStream),
+    #[cfg(unix)]
+    Unix(UnixStream),
+}
+
+impl Connection {
+    fn connect(endpoint: &Endpoint) -> io::Result<Connection> {
+        match *endpoint {
+            Endpoint::Tcp(addr) => {
+                let stream = TcpStream::connect(addr)?;
+                stream.set_nodelay(true)?;
+                Ok(Connection::Tcp(stream))
+            },
+            #[cfg(unix)]
+            Endpoint::Unix(ref path) => UnixStream::connect(path).map(Connection::Unix),
+        }
+    }
+
+    fn try_clone(&self) -> io::Result<Connection> {
+        match *self {
+            Connection::Tcp(ref stream) => stream.try_clone().map(Connection::Tcp),
+            #[cfg(unix)]
+            Connection::Unix(ref stream) => stream.try_clone().map(Connection::Unix),
+        }
+    }
+
+    fn shutdown(&self) {
+        // The peer may have closed the connection already.
+        let _ = match *self {
+            Connection::Tcp(ref stream) => stream.shutdown(Shutdown::Both),
+            #[cfg(unix)]
+            Connection::Unix(ref stream) => stream.shutdown(Shutdown::Both),
+        };
+    }
+}
+
+impl Read for Connection {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match *self {
+            Connection::Tcp(ref mut stream) => stream.read(buf),
+            #[cfg(unix)]
+            Connection::Unix(ref mut stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for Connection {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match *self {
+            Connection::Tcp(ref mut stream) => stream.write(buf),
+            #[cfg(unix)]
+            Connection::Unix(ref mut stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match *self {
+            Connection::Tcp(ref mut stream) => stream.flush(),
+            #[cfg(unix)]
+            Connection::Unix(ref mut stream) => stream.flush(),
+        }
+    }
+}
+
+enum Listener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(UnixListener),
+}
+
+impl Listener {
+    fn bind(endpoint: &Endpoint) -> io::Result<(Listener, Endpoint)> {
+        match *endpoint {
+            Endpoint::Tcp(addr) => {
+                let listener = TcpListener::bind(addr)?;
+                let local = Endpoint::Tcp(listener.local_addr()?);
+                Ok((Listener::Tcp(listener), local))
+            },
+            #[cfg(unix)]
+            Endpoint::Unix(ref path) => UnixListener::bind(path).map(|listener| (Listener::Unix(listener), endpoint.clone())),
+        }
+    }
+
+    fn accept(&self) -> io::Result<Connection> {
+        match *self {
+            Listener::Tcp(ref listener) => {
+                let (stream, _) = listener.accept()?;
+                stream.set_nodelay(true)?;
+                Ok(Connection::Tcp(stream))
+            },
+            #[cfg(unix)]
+            Listener::Unix(ref listener) => listener.accept().map(|(stream, _)| Connection::Unix(stream)),
+        }
+    }
+}
+
+/// Sending end of a cross-process event bus, writing events as length-prefixed frames encoded with a Codec to the
+/// RemoteSubscriber listening on an endpoint. Forward a publisher's events with EventPublisher::forward_remote.
+/// A connection that fails is re-established on the next publish, following the reconnect policy. Events written shortly
+/// before the connection dropped may be lost; events are not acknowledged by the other end.
+pub struct RemotePublisher<E, C> {
+    endpoint: Endpoint,
+    codec: C,
+    reconnect: RetryPolicy,
+    schema_version: Option<u32>,
+    connection: Mutex<Option<Connection>>,
+    marker: PhantomData<fn(&E)>,
+}
+
+impl<E, C> RemotePublisher<E, C> where E: SerializableEvent, C: Codec {
+    /// Remote publisher constructor, connecting to the endpoint.
+    /// INPUT:  endpoint: Endpoint   where the RemoteSubscriber listens.
+    ///         codec: Codec   codec events are encoded with; the RemoteSubscriber has to use the same.
+    /// OUTPUT: io::Result<RemotePublisher<E, C>>   Err if the endpoint could not be connected to.
+    pub fn connect(endpoint: Endpoint, codec: C) -> io::Result<RemotePublisher<E, C>> {
+        let connection = Connection::connect(&endpoint)?;
+        Ok(RemotePublisher {
+            endpoint,
+            codec,
+            reconnect: RetryPolicy { max_attempts: 5, backoff: Backoff::Exponential { initial: Duration::from_millis(50), max: Duration::from_secs(2) } },
+            schema_version: None,
+            connection: Mutex::new(Some(connection)),
+            marker: PhantomData,
+        })
+    }
+
+    /// Sets how often and how fast a publish tries to reconnect after the connection failed. Defaults to 5 attempts with
+    ///     an exponential backoff from 50ms up to 2s.
+    /// INPUT:  policy: RetryPolicy
+    pub fn set_reconnect_policy(&mut self, policy: RetryPolicy) {
+        self.reconnect = policy;
+    }
+
+    /// Tags the events sent from now on with a schema version, see Schema. The other end has to be listening with
+    ///     EventPublisher::listen_remote_versioned.
+    /// INPUT:  version: u32   current version of the payload type E.
+    pub fn set_schema_version(&mut self, version: u32) {
+        self.schema_version = Some(version);
+    }
+
+    /// Sends an event to the other end, reconnecting first if the connection has failed.
+    /// INPUT:  event: &Event<E>
+    /// OUTPUT: io::Result<()>   Err if the event could not be encoded, or could not be written within the reconnect policy.
+    pub fn publish_event(&self, event: &Event<E>) -> io::Result<()> {
+        let frame = match self.schema_version {
+            Some(version) => schema::encode_versioned(&self.codec, version, event),
+            None => self.codec.encode(event),
+        }.map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        let mut retry = 0;
+        loop {
+            {
+                let mut connection = sync::lock(&self.connection);
+                let result = match connection.take() {
+                    Some(connection) => Ok(connection),
+                    None => Connection::connect(&self.endpoint),
+                }.and_then(|mut stream| {
+                    write_frame(&mut stream, &frame)?;
+                    Ok(stream)
+                });
+                match result {
+                    Ok(stream) => {
+                        *connection = Some(stream);
+                        return Ok(());
+                    },
+                    Err(error) if retry + 1 >= self.reconnect.attempts() => return Err(error),
+                    Err(_) => {},
+                }
+            }
+            // The connection is unlocked while waiting, so the other publishing threads aren't held up by the backoff.
+            retry += 1;
+            thread::sleep(self.reconnect.delay(retry));
+        }
+    }
+
+    /// Whether the publisher currently holds a connection. A connection that failed is dropped and re-established by the
+    ///     next publish.
+    pub fn is_connected(&self) -> bool {
+        sync::lock(&self.connection).is_some()
+    }
+}
+
+/// Receiving end of a cross-process event bus, started with EventPublisher::listen_remote. Accepts connections from
+/// RemotePublishers on a background thread, decodes the frames they send and publishes the events on the publisher, one
+/// thread per connection. Publishers that reconnect are accepted like new ones; frames that fail to decode are skipped.
+/// Dropping the subscriber shuts it down like shutdown does.
+pub struct RemoteSubscriber {
+    endpoint: Endpoint,
+    closed: Arc<AtomicBool>,
+    // Clones of the open connections, for closing them on shutdown. Keyed by the order they were accepted in.
+    connections: Arc<Mutex<BTreeMap<u64, Connection>>>,
+    thread: Option<JoinHandle<()>>,
+    // Disconnected once the listener thread has ended, which holds the sender.
+    stopped: Mutex<mpsc::Receiver<()>>,
+}
+
+impl RemoteSubscriber {
+    pub(crate) fn bind<E, C>(publisher: Arc<EventPublisher<E>>, endpoint: &Endpoint, codec: C, schema: Option<Schema<E>>) -> io::Result<RemoteSubscriber>
+        where E: SerializableEvent + Send + Sync + 'static, C: Codec + 'static {
+        let (listener, endpoint) = Listener::bind(endpoint)?;
+        let closed = Arc::new(AtomicBool::new(false));
+        let connections = Arc::new(Mutex::new(BTreeMap::new()));
+        let accepting = closed.clone();
+        let accepted = connections.clone();
+        let codec = Arc::new(codec);
+        let schema = Arc::new(schema);
+        let (stopping, stopped) = mpsc::channel::<()>();
+        let thread = thread::Builder::new()
+            .name(String::from("event-remote-listener"))
+            .spawn(move || {
+                let _stopping = stopping;
+                let mut next_connection = 0;
+                loop {
+                    let accepted_connection = listener.accept();
+                    if accepting.load(Ordering::SeqCst) {
+                        return;
+                    }
+                    let connection = match accepted_connection {
+                        Ok(connection) => connection,
+                        // A connection given up on by its peer before it was accepted; the next one may be fine.
+                        Err(ref error) if error.kind() == io::ErrorKind::ConnectionAborted || error.kind() == io::ErrorKind::Interrupted => continue,
+                        // Such as running out of file descriptors: wait for some to be released rather than spinning.
+                        Err(_) => {
+                            thread::sleep(ACCEPT_BACKOFF);
+                            continue;
+                        },
+                    };
+                    let id = next_connection;
+                    next_connection += 1;
+                    // Without a clone it couldn't be closed on shutdown, so it is dropped, and its publisher reconnects.
+                    match connection.try_clone() {
+                        Ok(clone) => { sync::lock(&accepted).insert(id, clone); },
+                        Err(_) => continue,
+                    }
+                    let publisher = publisher.clone();
+                    let codec = codec.clone();
+                    let schema = schema.clone();
+                    let open = accepted.clone();
+                    // A connection that fails to start is dropped, and its publisher reconnects.
+                    let _ = thread::Builder::new()
+                        .name(String::from("event-remote-connection"))
+                        .spawn(move || {
+                            receive(connection, &publisher, &*codec, (*schema).as_ref());
+                            sync::lock(&open).remove(&id);
+                        });
+                }
+            })?;
+        Ok(RemoteSubscriber { endpoint, closed, connections, thread: Some(thread), stopped: Mutex::new(stopped) })
+    }
+
+    /// Endpoint the subscriber listens on, with the port picked if it was bound to port 0.
+    pub fn endpoint(&self) -> &Endpoint {
+        &self.endpoint
+    }
+
+    /// Stops accepting connections and closes the open ones. Waits for the listener thread to finish, for up to a second
+    ///     if it can't be woken up; events already being published complete.
+    pub fn shutdown(mut self) {
+        self.stop();
+    }
+
+    fn stop(&mut self) {
+        if let Some(thread) = self.thread.take() {
+            self.closed.store(true, Ordering::SeqCst);
+            // Wakes the listener thread up from accept. If that fails, the thread stops by itself on the next connection,
+            //     which is only waited for up to STOP_TIMEOUT; after that it is left to end on its own.
+            let _ = Connection::connect(&wake_endpoint(&self.endpoint));
+            if let Err(mpsc::RecvTimeoutError::Disconnected) = sync::lock(&self.stopped).recv_timeout(STOP_TIMEOUT) {
+                let _ = thread.join();
+            }
+            for connection in sync::lock(&self.connections).values() {
+                connection.shutdown();
+            }
+            #[cfg(unix)]
+            {
+                if let Endpoint::Unix(ref path) = self.endpoint {
+                    let _ = ::std::fs::remove_file(path);
+                }
+            }
+        }
+    }
+}
+
+// A listener bound to an unspecified address such as 0.0.0.0 can't be connected to at that address; loopback reaches it.
+fn wake_endpoint(endpoint: &Endpoint) -> Endpoint {
+    match *endpoint {
+        Endpoint::Tcp(addr) if addr.ip().is_unspecified() => {
+            let loopback = match addr {
+                SocketAddr::V4(_) => IpAddr::V4(Ipv4Addr::LOCALHOST),
+                SocketAddr::V6(_) => IpAddr::V6(Ipv6Addr::LOCALHOST),
+            };
+            Endpoint::Tcp(SocketAddr::new(loopback, addr.port()))
+        },
+        _ => endpoint.clone(),
+    }
+}
+
+impl Drop for RemoteSubscriber {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn receive<E, C>(mut connection: Connection, publisher: &EventPublisher<E>, codec: &C, schema: Option<&Schema<E>>) where E: SerializableEvent, C: Codec {
+    while let Ok(Some(frame)) = read_frame(&mut connection) {
+        // Frames that fail to decode are skipped; the framing keeps the stream in step.
+        let _ = match schema {
+            Some(schema) => publisher.publish_versioned(codec, schema, &frame),
+            None => publisher.publish_serialized(codec, &frame),
+        };
+    }
+}
@@ -0,0 +1,36 @@
+use std::cmp;
+use std::time::Duration;
+
+/// Retry policy of a handler subscribed with EventPublisher::subscribe_fallible_with_retry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Number of calls per event, the first one included. 0 is treated as 1.
+    pub max_attempts: u32,
+    pub backoff: Backoff,
+}
+
+/// Time waited between two attempts of a RetryPolicy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backoff {
+    /// The same delay before every retry.
+    Fixed(Duration),
+    /// initial before the first retry, doubling with every further retry up to max.
+    Exponential { initial: Duration, max: Duration },
+}
+
+impl RetryPolicy {
+    pub(crate) fn attempts(&self) -> u32 {
+        cmp::max(self.max_attempts, 1)
+    }
+
+    // Delay before the given retry, counting from 1.
+    pub(crate) fn delay(&self, retry: u32) -> Duration {
+        match self.backoff {
+            Backoff::Fixed(delay) => delay,
+            Backoff::Exponential { initial, max } => {
+                let factor = 1u32.checked_shl(retry.saturating_sub(1)).unwrap_or(u32::MAX);
+                cmp::min(initial.saturating_mul(factor), max)
+            },
+        }
+    }
+}
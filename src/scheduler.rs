@@ -0,0 +1,224 @@
+use std::cmp;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Condvar, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use sync;
+use time::Instant;
+use {CancellationToken, Event, EventPublisher, Shutdown};
+
+struct Job<E> {
+    event: Event<E>,
+    period: Option<Duration>,
+    cancelled: Arc<AtomicBool>,
+}
+
+struct Schedule<E> {
+    // Keyed by due time, then by the order the jobs were scheduled in.
+    jobs: BTreeMap<(Instant, u64), Job<E>>,
+    next_job: u64,
+    // Set once the scheduler is shut down.
+    stopping: Option<Shutdown>,
+    discarded: usize,
+}
+
+struct Shared<E> {
+    schedule: Mutex<Schedule<E>>,
+    changed: Condvar,
+}
+
+/// Handle of an event scheduled on a Scheduler, for cancelling it.
+#[derive(Debug, Clone)]
+pub struct ScheduleHandle {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl ScheduleHandle {
+    /// Cancels the scheduled event. A publish already in progress completes; no further ones happen.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether cancel has been called.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+/// Timer thread started with EventPublisher::spawn_scheduler, publishing events on the publisher at scheduled times.
+/// Events due at the same time are published in the order they were scheduled. Dropping the scheduler shuts it down like
+/// shutdown does.
+pub struct Scheduler<E> {
+    shared: Arc<Shared<E>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl<E> Scheduler<E> where E: Send + 'static {
+    pub(crate) fn spawn(publisher: Arc<EventPublisher<E>>) -> Scheduler<E> {
+        let shared = Arc::new(Shared {
+            schedule: Mutex::new(Schedule { jobs: BTreeMap::new(), next_job: 0, stopping: None, discarded: 0 }),
+            changed: Condvar::new(),
+        });
+        let timer = shared.clone();
+        let thread = thread::Builder::new()
+            .name(String::from("event-scheduler"))
+            .spawn(move || timer.run(&publisher))
+            .expect("failed to spawn the event scheduler thread");
+        Scheduler { shared, thread: Some(thread) }
+    }
+
+    /// Shuts the scheduler down once token is cancelled, as shutdown_with does, without waiting for the timer thread to end.
+    ///     Does so straight away if the token is already cancelled. Events scheduled afterwards are discarded, and their
+    ///     handles report being cancelled.
+    /// INPUT:  token: &CancellationToken
+    ///         shutdown: Shutdown   what to do with the scheduled events.
+    /// OUTPUT: void
+    pub fn cancel_on(&self, token: &CancellationToken, shutdown: Shutdown) {
+        let shared = Arc::downgrade(&self.shared);
+        token.on_cancel(move || {
+            if let Some(shared) = shared.upgrade() {
+                shared.close(shutdown);
+            }
+        });
+    }
+}
+
+impl<E> Scheduler<E> {
+    /// Publishes an event once delay has passed.
+    /// INPUT:  event: Event<E>
+    ///         delay: Duration
+    /// OUTPUT: ScheduleHandle   handle for cancelling the publish.
+    pub fn publish_after(&self, event: Event<E>, delay: Duration) -> ScheduleHandle {
+        self.publish_at(event, Instant::now() + delay)
+    }
+
+    /// Publishes an event at a given time, or straight away if that time has passed.
+    /// INPUT:  event: Event<E>
+    ///         at: Instant
+    /// OUTPUT: ScheduleHandle   handle for cancelling the publish.
+    pub fn publish_at(&self, event: Event<E>, at: Instant) -> ScheduleHandle {
+        self.schedule(at, Job { event, period: None, cancelled: Arc::new(AtomicBool::new(false)) })
+    }
+
+    /// Publishes an event every period, the first time once period has passed, until the handle is cancelled. A publish
+    ///     running late delays the following ones rather than making them catch up.
+    /// INPUT:  event: Event<E>
+    ///         period: Duration
+    /// OUTPUT: ScheduleHandle   handle for cancelling the publishes.
+    pub fn publish_every(&self, event: Event<E>, period: Duration) -> ScheduleHandle {
+        self.schedule(Instant::now() + period, Job { event, period: Some(period), cancelled: Arc::new(AtomicBool::new(false)) })
+    }
+
+    /// Number of scheduled events, counting each repeating one once. Cancelled ones are counted until they were due.
+    pub fn pending(&self) -> usize {
+        sync::lock(&self.shared.schedule).jobs.len()
+    }
+
+    /// Stops the scheduler, discarding the events not published yet, and waits for a publish in progress to complete.
+    /// OUTPUT: void
+    pub fn shutdown(mut self) {
+        self.stop(Shutdown::Discard);
+    }
+
+    /// Stops the scheduler and waits for the timer thread to end. With Shutdown::Drain, that is once the one-off events
+    ///     still scheduled have been published at their times; repeating events are discarded either way.
+    /// INPUT:  shutdown: Shutdown
+    /// OUTPUT: usize   number of events discarded, including the ones discarded when a token passed to cancel_on was
+    ///     cancelled. Cancelled events are not counted.
+    pub fn shutdown_with(mut self, shutdown: Shutdown) -> usize {
+        self.stop(shutdown)
+    }
+
+    fn schedule(&self, at: Instant, job: Job<E>) -> ScheduleHandle {
+        let handle = ScheduleHandle { cancelled: job.cancelled.clone() };
+        let mut schedule = sync::lock(&self.shared.schedule);
+        if schedule.stopping.is_some() {
+            handle.cancel();
+            schedule.discarded += 1;
+            return handle;
+        }
+        let id = schedule.next_job;
+        schedule.next_job += 1;
+        schedule.jobs.insert((at, id), job);
+        self.shared.changed.notify_all();
+        handle
+    }
+
+    fn stop(&mut self, shutdown: Shutdown) -> usize {
+        self.shared.close(shutdown);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        sync::lock(&self.shared.schedule).discarded
+    }
+}
+
+impl<E> Drop for Scheduler<E> {
+    fn drop(&mut self) {
+        self.stop(Shutdown::Discard);
+    }
+}
+
+impl<E> Shared<E> {
+    // Discards the jobs that won't be published, and cancelled ones, which aren't counted.
+    fn close(&self, shutdown: Shutdown) {
+        let mut schedule = sync::lock(&self.schedule);
+        if schedule.stopping != Some(Shutdown::Discard) {
+            schedule.stopping = Some(shutdown);
+        }
+        let keep_one_offs = schedule.stopping == Some(Shutdown::Drain);
+        let mut discarded = 0;
+        schedule.jobs.retain(|_, job| {
+            let cancelled = job.cancelled.load(Ordering::SeqCst);
+            let keep = keep_one_offs && job.period.is_none() && !cancelled;
+            if !keep && !cancelled {
+                discarded += 1;
+            }
+            keep
+        });
+        schedule.discarded += discarded;
+        self.changed.notify_all();
+    }
+
+    fn run(&self, publisher: &EventPublisher<E>) {
+        let mut schedule = sync::lock(&self.schedule);
+        loop {
+            if schedule.stopping.is_some() && schedule.jobs.is_empty() {
+                return;
+            }
+            let due = match schedule.jobs.keys().next() {
+                Some(&(due, _)) => due,
+                None => {
+                    schedule = sync::wait(&self.changed, schedule);
+                    continue;
+                },
+            };
+            let now = Instant::now();
+            if due > now {
+                schedule = sync::wait_timeout(&self.changed, schedule, due - now);
+                continue;
+            }
+
+            let ((_, id), job) = schedule.jobs.pop_first().expect("schedule checked to be non-empty");
+            // The publish runs without the schedule locked, so handlers may schedule events themselves.
+            drop(schedule);
+            if !job.cancelled.load(Ordering::SeqCst) {
+                publisher.publish_event(&job.event);
+            }
+            schedule = sync::lock(&self.schedule);
+            if let Some(period) = job.period {
+                if job.cancelled.load(Ordering::SeqCst) {
+                    continue;
+                }
+                // Shut down while it was being published.
+                if schedule.stopping.is_some() {
+                    schedule.discarded += 1;
+                } else {
+                    schedule.jobs.insert((cmp::max(due + period, Instant::now()), id), job);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use {Codec, CodecError, Event, SerializableEvent};
+
+type UpgradeBox<E> = Box<dyn Fn(u32, &[u8]) -> Result<E, CodecError> + Send + Sync + 'static>;
+
+// Versioned encodings start with the schema version as a big-endian u32, followed by whether the event has args.
+const HEADER_LEN: usize = 5;
+const MISSING: u8 = 0;
+const ARGS: u8 = 1;
+
+/// Schema version of an event payload type, and how to read payloads encoded with older versions of it. Bump the version
+/// whenever the shape of the payload changes, and give the schema an upgrade hook decoding the payloads of the older
+/// versions into the current type, so events persisted or sent before the change can still be delivered.
+/// Versioned encodings start with the version the payload was encoded with, followed by the payload encoded with the codec;
+/// they are not interchangeable with the unversioned encoding of Codec::encode(&event).
+pub struct Schema<E> {
+    version: u32,
+    upgrade: Option<UpgradeBox<E>>,
+}
+
+impl<E> Schema<E> where E: SerializableEvent {
+    /// Schema constructor for a payload type that has never changed shape, or whose older encodings don't have to be read.
+    /// INPUT:  version: u32   current version of the payload type.
+    pub fn new(version: u32) -> Schema<E> {
+        Schema { version, upgrade: None }
+    }
+
+    /// Schema constructor with an upgrade hook for payloads encoded with older versions.
+    /// INPUT:  version: u32   current version of the payload type.
+    ///         upgrade: Fn(u32, &[u8]) -> Result<E, CodecError> + Send + Sync + 'static   called with the version a payload was
+    ///         encoded with and the payload as encoded by the codec, e.g. codec.decode::<PayloadV1>(bytes).map(PayloadV2::from)
+    ///         for version 1. Only called for versions older than the current one.
+    pub fn with_upgrade<F>(version: u32, upgrade: F) -> Schema<E> where F: Fn(u32, &[u8]) -> Result<E, CodecError> + Send + Sync + 'static {
+        Schema { version, upgrade: Some(Box::new(upgrade)) }
+    }
+
+    /// Current version of the payload type.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Encodes an event, tagged with the current version.
+    /// INPUT:  codec: &Codec   codec the payload is encoded with.
+    ///         event: &Event<E>
+    /// OUTPUT: Result<Vec<u8>, CodecError>   the versioned encoding.
+    pub fn encode<C>(&self, codec: &C, event: &Event<E>) -> Result<Vec<u8>, CodecError> where C: Codec {
+        encode_versioned(codec, self.version, event)
+    }
+
+    /// Decodes a versioned encoding, upgrading payloads encoded with an older version.
+    /// INPUT:  codec: &Codec   codec the payload was encoded with.
+    ///         bytes: &[u8]   a versioned encoding, as returned by encode.
+    /// OUTPUT: Result<Event<E>, CodecError>   Err if bytes aren't a versioned encoding, the payload could not be decoded or
+    ///         upgraded, or it was encoded with a newer version than the current one.
+    pub fn decode<C>(&self, codec: &C, bytes: &[u8]) -> Result<Event<E>, CodecError> where C: Codec {
+        if bytes.len() < HEADER_LEN {
+            return Err(CodecError::new("versioned event is too short for its header"));
+        }
+        let version = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        let payload = &bytes[HEADER_LEN..];
+        match bytes[4] {
+            MISSING => return Ok(Event::Missing),
+            ARGS => {},
+            kind => return Err(CodecError::new(format!("unknown event kind {} in versioned event", kind))),
+        }
+        if version == self.version {
+            return codec.decode(payload).map(Event::Args);
+        }
+        if version > self.version {
+            return Err(CodecError::new(format!("event has schema version {}, newer than the current version {}", version, self.version)));
+        }
+        match self.upgrade {
+            Some(ref upgrade) => upgrade(version, payload).map(Event::Args),
+            None => Err(CodecError::new(format!("no upgrade from schema version {} to {}", version, self.version))),
+        }
+    }
+}
+
+// For senders that only need to know the version they encode with.
+pub(crate) fn encode_versioned<E, C>(codec: &C, version: u32, event: &Event<E>) -> Result<Vec<u8>, CodecError> where E: SerializableEvent, C: Codec {
+    let mut bytes = version.to_be_bytes().to_vec();
+    match *event {
+        Event::Args(ref args) => {
+            bytes.push(ARGS);
+            bytes.extend(codec.encode(args)?);
+        },
+        Event::Missing => bytes.push(MISSING),
+    }
+    Ok(bytes)
+}
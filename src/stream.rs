@@ -0,0 +1,104 @@
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+use futures_core::Stream;
+
+use sync;
+use Subscription;
+
+/// Stream of the args of the events published on a publisher, returned by EventPublisher::subscribe_stream. Payloads are
+/// queued until the stream is polled, without a limit. The stream ends once the publisher is gone, and dropping the stream
+/// unsubscribes it.
+pub struct EventStream<E> {
+    shared: Arc<Mutex<Shared<E>>>,
+    // Only held to unsubscribe on drop.
+    subscription: Subscription<E>,
+}
+
+pub(crate) struct Shared<E> {
+    pending: VecDeque<E>,
+    waker: Option<Waker>,
+    closed: bool,
+}
+
+// Moved into the subscribed handler. Dropped with the handler, which ends the stream.
+pub(crate) struct StreamSender<E> {
+    shared: Arc<Mutex<Shared<E>>>,
+}
+
+pub(crate) fn channel<E>() -> (StreamSender<E>, Arc<Mutex<Shared<E>>>) {
+    let shared = Arc::new(Mutex::new(Shared { pending: VecDeque::new(), waker: None, closed: false }));
+    (StreamSender { shared: shared.clone() }, shared)
+}
+
+impl<E> StreamSender<E> {
+    pub(crate) fn send(&self, item: E) {
+        let waker = {
+            let mut shared = sync::lock(&self.shared);
+            shared.pending.push_back(item);
+            shared.waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl<E> Drop for StreamSender<E> {
+    fn drop(&mut self) {
+        let waker = {
+            let mut shared = sync::lock(&self.shared);
+            shared.closed = true;
+            shared.waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl<E> EventStream<E> {
+    pub(crate) fn new(shared: Arc<Mutex<Shared<E>>>, subscription: Subscription<E>) -> EventStream<E> {
+        EventStream { shared, subscription }
+    }
+}
+
+impl<E> Stream for EventStream<E> {
+    type Item = E;
+
+    fn poll_next(self: Pin<&mut Self>, context: &mut Context) -> Poll<Option<E>> {
+        let mut shared = sync::lock(&self.shared);
+        if let Some(item) = shared.pending.pop_front() {
+            return Poll::Ready(Some(item));
+        }
+        if shared.closed {
+            return Poll::Ready(None);
+        }
+        shared.waker = Some(context.waker().clone());
+        Poll::Pending
+    }
+}
+
+/// Future returned by EventPublisher::wait_for_async, completing with the args of the first matching event, or with None once
+/// the publisher is gone. Dropping the future unsubscribes it.
+#[must_use = "the future only completes while it is polled"]
+pub struct WaitFor<E> {
+    stream: EventStream<E>,
+}
+
+impl<E> WaitFor<E> {
+    pub(crate) fn new(stream: EventStream<E>) -> WaitFor<E> {
+        WaitFor { stream }
+    }
+}
+
+impl<E> Future for WaitFor<E> {
+    type Output = Option<E>;
+
+    fn poll(mut self: Pin<&mut Self>, context: &mut Context) -> Poll<Option<E>> {
+        Pin::new(&mut self.stream).poll_next(context)
+    }
+}
@@ -0,0 +1,63 @@
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::thread::{self, ThreadId};
+use std::time::Duration;
+
+// Poisoned locks are used as they are rather than spreading one panic to every later call. Handlers never run while the
+// publisher holds one of its own locks, so those always guard consistent data; the state of subscribe_with_state is handed
+// to the next call as the panicking handler left it.
+
+pub(crate) fn read<'a, T>(lock: &'a RwLock<T>) -> RwLockReadGuard<'a, T> {
+    lock.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+pub(crate) fn write<'a, T>(lock: &'a RwLock<T>) -> RwLockWriteGuard<'a, T> {
+    lock.write().unwrap_or_else(PoisonError::into_inner)
+}
+
+pub(crate) fn lock<'a, T>(mutex: &'a Mutex<T>) -> MutexGuard<'a, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+pub(crate) fn wait<'a, T>(condvar: &Condvar, guard: MutexGuard<'a, T>) -> MutexGuard<'a, T> {
+    condvar.wait(guard).unwrap_or_else(PoisonError::into_inner)
+}
+
+pub(crate) fn wait_timeout<'a, T>(condvar: &Condvar, guard: MutexGuard<'a, T>, timeout: Duration) -> MutexGuard<'a, T> {
+    condvar.wait_timeout(guard, timeout).map(|(guard, _)| guard).unwrap_or_else(|poisoned| poisoned.into_inner().0)
+}
+
+// Value handed to one call at a time like a Mutex, except that a call nested in one the same thread is already running, such
+// as a handler publishing to its own publisher, is skipped instead of deadlocking on the lock that thread holds.
+pub(crate) struct Exclusive<T> {
+    value: Mutex<T>,
+    // Thread running a call, set while value is locked.
+    owner: Mutex<Option<ThreadId>>,
+}
+
+struct Owner<'a>(&'a Mutex<Option<ThreadId>>);
+
+impl<T> Exclusive<T> {
+    pub(crate) fn new(value: T) -> Exclusive<T> {
+        Exclusive { value: Mutex::new(value), owner: Mutex::new(None) }
+    }
+
+    // None if the call was skipped.
+    pub(crate) fn with<F, R>(&self, call: F) -> Option<R> where F: FnOnce(&mut T) -> R {
+        let current = thread::current().id();
+        // Only the thread holding the value sets itself as the owner, so no other thread can make this check pass.
+        if *lock(&self.owner) == Some(current) {
+            return None;
+        }
+        let mut value = lock(&self.value);
+        *lock(&self.owner) = Some(current);
+        // Dropped before value, also when call panics.
+        let _owner = Owner(&self.owner);
+        Some(call(&mut value))
+    }
+}
+
+impl<'a> Drop for Owner<'a> {
+    fn drop(&mut self) {
+        *lock(self.0) = None;
+    }
+}
@@ -0,0 +1,16 @@
+// std::time::Instant::now and SystemTime::now panic on wasm32-unknown-unknown, which has no clock of its own. web-time reads
+// the browser's clock there and is std::time itself everywhere else.
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+pub use std::time::{Instant, SystemTime};
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+pub use web_time::{Instant, SystemTime};
+
+// Blocks the current thread for duration. wasm32 can't block its only thread, std::thread::sleep panics there, so this
+// returns straight away instead.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn sleep(duration: ::std::time::Duration) {
+    ::std::thread::sleep(duration);
+}
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn sleep(_: ::std::time::Duration) {}
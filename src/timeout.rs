@@ -0,0 +1,24 @@
+use std::time::Duration;
+
+use SubscriptionId;
+
+/// How long EventPublisher::publish_event_with_timeout waits for handlers, and what happens to handlers that take longer.
+/// Set with EventPublisher::set_handler_timeout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeoutPolicy {
+    pub timeout: Duration,
+    /// Number of timeouts after which a handler is unsubscribed, so it stops tying up threads. None keeps it subscribed.
+    pub unsubscribe_after: Option<u32>,
+}
+
+/// Handler that exceeded the timeout of the publisher's TimeoutPolicy, as handed to the hook set with
+/// EventPublisher::set_timeout_hook. Reported as soon as the timeout passed, while the handler is still running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HandlerTimeout {
+    pub subscription: SubscriptionId,
+    pub timeout: Duration,
+    /// Number of timeouts of this handler so far, this one included.
+    pub violations: u32,
+    /// Whether the handler has been unsubscribed because of this timeout.
+    pub unsubscribed: bool,
+}
@@ -0,0 +1,83 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use sync;
+use {Event, SubscriptionId};
+
+type TopicHandler<E> = Arc<dyn Fn(&str, &Event<E>) + Send + Sync + 'static>;
+
+struct TopicSubscription<E> {
+    pattern: Vec<String>,
+    handler: TopicHandler<E>,
+}
+
+/// Publisher routing events by topic. Topics are strings of dot separated words, e.g. "orders.created". Handlers subscribe to a
+/// pattern of words in which "*" matches exactly one word and "#" matches any number of words, including none, so
+/// "orders.*" sees "orders.created" but not "orders.created.eu", while "orders.#" and "#" see both.
+pub struct TopicPublisher<E> {
+    subscriptions: RwLock<BTreeMap<SubscriptionId, TopicSubscription<E>>>,
+    next_id: AtomicU64,
+}
+
+impl<E> TopicPublisher<E> {
+    /// Topic publisher constructor.
+    pub fn new() -> TopicPublisher<E> {
+        TopicPublisher { subscriptions: RwLock::new(BTreeMap::new()), next_id: AtomicU64::new(0) }
+    }
+
+    /// Subscribes a handler to every topic matching a pattern.
+    /// INPUT:  pattern: &str   topic pattern, words separated by dots, with "*" and "#" as wildcards.
+    ///         handler: Fn(&str, &Event<E>) + Send + Sync + 'static   handler is called with the topic and a reference to every event published to a matching topic.
+    /// OUTPUT: SubscriptionId   id of the new subscription, for unsubscribe.
+    pub fn subscribe<F>(&self, pattern: &str, handler: F) -> SubscriptionId where F: Fn(&str, &Event<E>) + Send + Sync + 'static {
+        let mut subscriptions = sync::write(&self.subscriptions);
+        let id = SubscriptionId(self.next_id.fetch_add(1, Ordering::SeqCst) + 1);
+        subscriptions.insert(id, TopicSubscription { pattern: words(pattern).map(String::from).collect(), handler: Arc::new(handler) });
+        id
+    }
+
+    /// Unsubscribes a handler.
+    /// INPUT:  id: SubscriptionId   id returned by subscribe.
+    /// OUTPUT: bool   true if the handler was subscribed and has been removed.
+    pub fn unsubscribe(&self, id: SubscriptionId) -> bool {
+        sync::write(&self.subscriptions).remove(&id).is_some()
+    }
+
+    /// Publishes an event to a topic. Handlers whose pattern matches the topic are called in the order they subscribed.
+    ///     Wildcards in the published topic are taken literally.
+    /// INPUT:  topic: &str
+    ///         event: &Event<E>
+    pub fn publish(&self, topic: &str, event: &Event<E>) {
+        let topic_words: Vec<&str> = words(topic).collect();
+        // Handlers run without the lock held, so they may subscribe, unsubscribe or publish themselves.
+        let handlers: Vec<TopicHandler<E>> = sync::read(&self.subscriptions).values()
+            .filter(|subscription| matches(&subscription.pattern, &topic_words))
+            .map(|subscription| subscription.handler.clone())
+            .collect();
+        for handler in handlers {
+            handler(topic, event);
+        }
+    }
+}
+
+impl<E> Default for TopicPublisher<E> {
+    fn default() -> TopicPublisher<E> {
+        TopicPublisher::new()
+    }
+}
+
+fn words(topic: &str) -> impl Iterator<Item = &str> {
+    topic.split('.').filter(|word| !word.is_empty())
+}
+
+fn matches(pattern: &[String], topic: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => topic.is_empty(),
+        Some((word, rest)) => match word.as_str() {
+            "#" => (0..=topic.len()).any(|skipped| matches(rest, &topic[skipped..])),
+            "*" => !topic.is_empty() && matches(rest, &topic[1..]),
+            word => topic.first() == Some(&word) && matches(rest, &topic[1..]),
+        },
+    }
+}
@@ -0,0 +1,15 @@
+/// Enum event types whose variants can be subscribed to one by one with EventPublisher::subscribe_variant. Derive it with
+/// #[derive(EventKind)] from the derive feature, which also generates the variant markers.
+pub trait EventKind {
+    /// Name of the variant of the payload, e.g. "Clicked", for logging or routing events by kind.
+    fn variant_name(&self) -> &'static str;
+}
+
+/// Marker type standing for one variant of an EventKind enum, as generated by #[derive(EventKind)].
+pub trait EventVariant<E> where E: EventKind {
+    /// Name of the variant.
+    const NAME: &'static str;
+
+    /// Whether the payload is this variant.
+    fn matches(event: &E) -> bool;
+}
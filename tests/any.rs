@@ -0,0 +1,46 @@
+extern crate event;
+
+use std::sync::{Arc, Mutex};
+
+use event::{AnyEventPublisher, AnyPayload, Event};
+
+#[derive(Debug, PartialEq)]
+struct Loaded(&'static str);
+
+#[test]
+fn payloads_are_routed_to_the_handlers_of_their_type() {
+    let publisher = AnyEventPublisher::new();
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let loaded = log.clone();
+    publisher.subscribe_any(move |plugin: &Loaded| loaded.lock().unwrap().push(format!("loaded {}", plugin.0))).unwrap();
+    let numbers = log.clone();
+    publisher.subscribe_any(move |number: &u32| numbers.lock().unwrap().push(format!("number {}", number))).unwrap();
+    let all = log.clone();
+    publisher.subscribe_args(move |payload: &AnyPayload| all.lock().unwrap().push(format!("any {}", payload.is::<u32>()))).unwrap();
+
+    publisher.publish_any(Loaded("audio"));
+    publisher.publish_any(7u32);
+
+    assert_eq!(*log.lock().unwrap(), vec!["loaded audio", "any false", "number 7", "any true"]);
+}
+
+#[test]
+fn a_payload_of_a_type_nobody_subscribed_to_is_a_dead_event() {
+    let publisher = AnyEventPublisher::new();
+    let calls = Arc::new(Mutex::new(0));
+    let counter = calls.clone();
+    publisher.subscribe_any(move |_: &u32| *counter.lock().unwrap() += 1).unwrap();
+    let dead = Arc::new(Mutex::new(Vec::new()));
+    let dead_events = dead.clone();
+    publisher.set_dead_event_handler(Box::new(move |event: &Event<AnyPayload>| {
+        if let Event::Args(ref payload) = *event {
+            dead_events.lock().unwrap().push(payload.downcast_ref::<u64>().cloned());
+        }
+    }));
+
+    publisher.publish_any(7u64);
+    publisher.publish_any(7u32);
+
+    assert_eq!(*calls.lock().unwrap(), 1);
+    assert_eq!(*dead.lock().unwrap(), vec![Some(7)]);
+}
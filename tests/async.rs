@@ -0,0 +1,201 @@
+#![cfg(feature = "async")]
+
+extern crate event;
+extern crate futures_core;
+
+use std::future::{self, Future};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+use std::time::Duration;
+
+use event::{CancellationToken, Event, EventPublisher, EventStream};
+use futures_core::Stream;
+
+struct Unpark(Thread);
+
+impl Wake for Unpark {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+// Polls the future on the current thread, parking it until the future is woken.
+fn block_on<F>(future: F) -> F::Output where F: Future {
+    let waker = Waker::from(Arc::new(Unpark(thread::current())));
+    let mut context = Context::from_waker(&waker);
+    let mut future = Box::pin(future);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+        thread::park();
+    }
+}
+
+fn next<E>(stream: &mut EventStream<E>) -> Option<E> {
+    block_on(future::poll_fn(|context| Pin::new(&mut *stream).poll_next(context)))
+}
+
+// Runs start on its first poll and then on its second one, waking its task in between, so whoever awaits it is suspended
+// once in between.
+struct Suspend<S, F> {
+    start: Option<S>,
+    then: Option<F>,
+}
+
+fn suspend<S, F>(start: S, then: F) -> Suspend<S, F> where S: FnOnce(), F: FnOnce() {
+    Suspend { start: Some(start), then: Some(then) }
+}
+
+impl<S, F> Future for Suspend<S, F> where S: FnOnce() + Unpin, F: FnOnce() + Unpin {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, context: &mut Context) -> Poll<()> {
+        if let Some(start) = self.start.take() {
+            start();
+            context.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        if let Some(then) = self.then.take() {
+            then();
+        }
+        Poll::Ready(())
+    }
+}
+
+#[test]
+fn publish_event_async_awaits_the_async_handlers() {
+    let publisher = EventPublisher::new();
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let async_log = log.clone();
+    publisher.subscribe_async(move |event: &Event<u32>| {
+        let (log, event) = (async_log.clone(), event.clone());
+        suspend(|| {}, move || log.lock().unwrap().push(format!("async {:?}", event)))
+    }).unwrap();
+    let sync_log = log.clone();
+    publisher.subscribe_args(move |args: &u32| sync_log.lock().unwrap().push(format!("sync {}", args))).unwrap();
+
+    let publish = publisher.publish_event_async(&Event::Args(1));
+    assert_eq!(*log.lock().unwrap(), vec!["sync 1"]);
+    block_on(publish);
+    assert_eq!(*log.lock().unwrap(), vec!["sync 1", "async Args(1)"]);
+
+    // The blocking publish functions skip async handlers.
+    publisher.publish_event(&Event::Args(2));
+    assert_eq!(log.lock().unwrap().len(), 3);
+}
+
+#[test]
+fn a_stream_yields_the_args_of_published_events() {
+    let publisher = Arc::new(EventPublisher::new());
+    let mut stream = publisher.subscribe_stream().unwrap();
+    assert_eq!(publisher.subscriber_count(), 1);
+
+    publisher.publish_event(&Event::Args(1));
+    publisher.publish_event(&Event::Missing);
+    assert_eq!(next(&mut stream), Some(1));
+
+    // Wakes the task waiting for the next item.
+    let publishing = publisher.clone();
+    let late = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(20));
+        publishing.publish_event(&Event::Args(2));
+    });
+    assert_eq!(next(&mut stream), Some(2));
+    late.join().unwrap();
+
+    drop(stream);
+    assert_eq!(publisher.subscriber_count(), 0);
+}
+
+
+// Subscribes async handlers whose futures count how many of them are in flight at once. Returns the most seen.
+fn subscribe_counting(publisher: &EventPublisher<u32>, handlers: usize) -> Arc<AtomicUsize> {
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let most = Arc::new(AtomicUsize::new(0));
+    for _ in 0..handlers {
+        let (in_flight, most) = (in_flight.clone(), most.clone());
+        publisher.subscribe_async(move |_: &Event<u32>| {
+            let (started, finished, most) = (in_flight.clone(), in_flight.clone(), most.clone());
+            suspend(
+                move || { most.fetch_max(started.fetch_add(1, Ordering::SeqCst) + 1, Ordering::SeqCst); },
+                move || { finished.fetch_sub(1, Ordering::SeqCst); },
+            )
+        }).unwrap();
+    }
+    most
+}
+
+#[test]
+fn publish_event_concurrent_limits_the_futures_in_flight() {
+    let publisher = EventPublisher::new();
+    let most = subscribe_counting(&publisher, 5);
+
+    block_on(publisher.publish_event_concurrent(&Event::Args(1), 2));
+    assert_eq!(most.load(Ordering::SeqCst), 2);
+
+    most.store(0, Ordering::SeqCst);
+    block_on(publisher.publish_event_async(&Event::Args(2)));
+    assert_eq!(most.load(Ordering::SeqCst), 5);
+}
+
+#[test]
+fn a_cancelled_publish_completes_without_waiting_for_the_handlers() {
+    let publisher = EventPublisher::new();
+    let finished = Arc::new(AtomicUsize::new(0));
+    let counter = finished.clone();
+    publisher.subscribe_async(move |_: &Event<u32>| {
+        let counter = counter.clone();
+        suspend(|| {}, move || { counter.fetch_add(1, Ordering::SeqCst); })
+    }).unwrap();
+    let token = CancellationToken::new();
+
+    let mut publish = Box::pin(publisher.publish_event_async(&Event::Args(1)).cancel_on(&token));
+    let waker = Waker::from(Arc::new(Unpark(thread::current())));
+    assert!(publish.as_mut().poll(&mut Context::from_waker(&waker)).is_pending());
+    token.cancel();
+    block_on(publish);
+
+    assert_eq!(finished.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn wait_for_async_resolves_with_the_first_matching_event_published_after_it() {
+    let publisher: Arc<EventPublisher<u32>> = Arc::new(EventPublisher::new());
+    publisher.publish_event(&Event::Args(50));
+    let waiting = publisher.wait_for_async(|args| *args >= 10).unwrap();
+    let publishing = publisher.clone();
+    let thread = thread::spawn(move || {
+        for args in 0..20 {
+            thread::sleep(Duration::from_millis(1));
+            publishing.publish_event(&Event::Args(args));
+        }
+    });
+
+    assert_eq!(block_on(waiting), Some(10));
+    thread.join().unwrap();
+    assert_eq!(publisher.subscriber_count(), 0);
+}
+
+#[test]
+fn wait_for_async_resolves_with_none_once_the_publisher_is_dropped() {
+    let publisher: EventPublisher<u32> = EventPublisher::new();
+    let waiting = publisher.wait_for_async(|_| true).unwrap();
+    drop(publisher);
+
+    assert_eq!(block_on(waiting), None);
+}
+
+#[test]
+fn dropping_the_wait_for_async_future_unsubscribes_it() {
+    let publisher: EventPublisher<u32> = EventPublisher::new();
+    let waiting = publisher.wait_for_async(|_| true).unwrap();
+    assert_eq!(publisher.subscriber_count(), 1);
+
+    drop(waiting);
+
+    assert_eq!(publisher.subscriber_count(), 0);
+}
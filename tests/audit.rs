@@ -0,0 +1,38 @@
+extern crate event;
+
+use std::ops::ControlFlow;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use event::{AuditOperation, AuditRecord, Event, EventPublisher, Unsubscribe};
+
+#[test]
+fn subscribes_unsubscribes_and_publishes_are_recorded_in_order() {
+    let publisher = EventPublisher::with_max_subscribers(2);
+    let records: Arc<Mutex<Vec<AuditRecord>>> = Arc::new(Mutex::new(Vec::new()));
+    let sink = records.clone();
+    publisher.set_audit_sink(Box::new(move |record: &AuditRecord| sink.lock().unwrap().push(record.clone())));
+    let started = SystemTime::now();
+
+    let handler = publisher.subscribe_args(|_: &u32| {}).unwrap();
+    let once = publisher.subscribe_until(|_: &Event<u32>| ControlFlow::Break(Unsubscribe)).unwrap();
+    assert!(publisher.subscribe_args(|_: &u32| {}).is_err());
+    publisher.publish_event(&Event::Args(1));
+    publisher.unsubscribe(handler);
+    publisher.unsubscribe(handler);
+    publisher.publish_event(&Event::Args(2));
+
+    let records = records.lock().unwrap();
+    let operations: Vec<AuditOperation> = records.iter().map(|record| record.operation.clone()).collect();
+    assert_eq!(operations, vec![
+        AuditOperation::Subscribe { subscription: handler },
+        AuditOperation::Subscribe { subscription: once },
+        AuditOperation::SubscribeRejected,
+        AuditOperation::Publish { handlers: 2 },
+        AuditOperation::Unsubscribe { subscription: once, removed: true },
+        AuditOperation::Unsubscribe { subscription: handler, removed: true },
+        AuditOperation::Unsubscribe { subscription: handler, removed: false },
+        AuditOperation::Publish { handlers: 0 },
+    ]);
+    assert!(records.iter().all(|record| record.time >= started && record.time <= SystemTime::now()));
+}
@@ -0,0 +1,57 @@
+extern crate event;
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use event::{BatchingSink, Event, EventPublisher};
+
+type Batches = Arc<Mutex<Vec<Vec<u32>>>>;
+
+fn sink(max_size: usize, max_delay: Duration) -> (Arc<BatchingSink<u32>>, Batches) {
+    let batches: Batches = Arc::new(Mutex::new(Vec::new()));
+    let downstream = batches.clone();
+    (Arc::new(BatchingSink::new(max_size, max_delay, move |batch| downstream.lock().unwrap().push(batch))), batches)
+}
+
+#[test]
+fn a_full_batch_is_flushed() {
+    let publisher = EventPublisher::new();
+    let (sink, batches) = sink(2, Duration::from_secs(60));
+    publisher.subscribe_batching(sink.clone()).unwrap();
+
+    for args in 1..=3 {
+        publisher.publish_event(&Event::Args(args));
+    }
+    publisher.publish_event(&Event::Missing);
+
+    assert_eq!(*batches.lock().unwrap(), vec![vec![1, 2]]);
+    assert_eq!(sink.len(), 1);
+}
+
+#[test]
+fn an_overdue_batch_is_flushed_by_poll() {
+    let (sink, batches) = sink(10, Duration::from_millis(10));
+    sink.push(1);
+    sink.poll();
+    assert!(batches.lock().unwrap().is_empty());
+
+    thread::sleep(Duration::from_millis(20));
+    sink.poll();
+
+    assert_eq!(*batches.lock().unwrap(), vec![vec![1]]);
+    assert!(sink.is_empty());
+}
+
+#[test]
+fn dropping_the_sink_flushes_what_is_pending() {
+    let (sink, batches) = sink(10, Duration::from_secs(60));
+    sink.push(1);
+    sink.push(2);
+    sink.flush();
+    sink.flush();
+    sink.push(3);
+    drop(sink);
+
+    assert_eq!(*batches.lock().unwrap(), vec![vec![1, 2], vec![3]]);
+}
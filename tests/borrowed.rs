@@ -0,0 +1,94 @@
+extern crate event;
+
+use std::sync::{Arc, Mutex};
+
+use event::{BorrowedEventPublisher, BorrowedHandlerBox, Event, SubscriptionId};
+
+type Log = Arc<Mutex<Vec<String>>>;
+
+fn logging(log: &Log, name: &'static str) -> BorrowedHandlerBox<str> {
+    let log = log.clone();
+    Box::new(move |event: &Event<&str>| {
+        if let Event::Args(word) = *event {
+            log.lock().unwrap().push(format!("{} {}", name, word));
+        }
+    })
+}
+
+#[test]
+fn handlers_are_called_by_descending_priority_then_subscription_order() {
+    let publisher = BorrowedEventPublisher::<str>::new();
+    let log: Log = Arc::new(Mutex::new(Vec::new()));
+    publisher.subscribe_with_priority(logging(&log, "low"), -1).unwrap();
+    publisher.subscribe_handler(logging(&log, "first")).unwrap();
+    publisher.subscribe_with_priority(logging(&log, "high"), 5).unwrap();
+    publisher.subscribe_handler(logging(&log, "second")).unwrap();
+
+    let line = String::from("a b");
+    for word in line.split(' ') {
+        publisher.publish_event(&Event::Args(word));
+    }
+
+    assert_eq!(*log.lock().unwrap(), vec!["high a", "first a", "second a", "low a", "high b", "first b", "second b", "low b"]);
+}
+
+#[test]
+fn a_panicking_handler_does_not_keep_the_event_from_the_others() {
+    let publisher = BorrowedEventPublisher::<str>::new();
+    let panics = Arc::new(Mutex::new(Vec::new()));
+    let hook_panics = panics.clone();
+    publisher.set_panic_hook(Box::new(move |panic| hook_panics.lock().unwrap().push((panic.subscription, panic.message.clone()))));
+    let log: Log = Arc::new(Mutex::new(Vec::new()));
+    let panicking = publisher.subscribe_handler(Box::new(|_: &Event<&str>| panic!("handler failed"))).unwrap();
+    publisher.subscribe_handler(logging(&log, "after")).unwrap();
+
+    publisher.publish_event(&Event::Args("word"));
+
+    assert_eq!(*log.lock().unwrap(), vec!["after word"]);
+    assert_eq!(*panics.lock().unwrap(), vec![(panicking, Some(String::from("handler failed")))]);
+}
+
+#[test]
+fn a_publish_delivers_to_the_handlers_subscribed_when_it_started() {
+    let publisher = Arc::new(BorrowedEventPublisher::<str>::new());
+    let log: Log = Arc::new(Mutex::new(Vec::new()));
+    let later: Arc<Mutex<Option<SubscriptionId>>> = Arc::new(Mutex::new(None));
+
+    let (inner, inner_log, inner_later) = (Arc::downgrade(&publisher), log.clone(), later.clone());
+    publisher.subscribe_handler(Box::new(move |event: &Event<&str>| {
+        let publisher = inner.upgrade().unwrap();
+        inner_log.lock().unwrap().push(format!("changing {:?}", event));
+        let later = inner_later.lock().unwrap().take();
+        if let Some(later) = later {
+            publisher.unsubscribe(later);
+            publisher.subscribe_handler(logging(&inner_log, "added")).unwrap();
+        }
+    })).unwrap();
+    *later.lock().unwrap() = Some(publisher.subscribe_handler(logging(&log, "removed")).unwrap());
+
+    publisher.publish_event(&Event::Args("one"));
+    publisher.publish_event(&Event::Args("two"));
+
+    assert_eq!(*log.lock().unwrap(), vec!["changing Args(\"one\")", "removed one", "changing Args(\"two\")", "added two"]);
+}
+
+#[test]
+fn events_borrow_stack_data_that_is_dropped_after_the_publish() {
+    let publisher = BorrowedEventPublisher::<str>::new();
+    let log: Log = Arc::new(Mutex::new(Vec::new()));
+    publisher.subscribe_handler(logging(&log, "word")).unwrap();
+    let lengths = Arc::new(Mutex::new(Vec::new()));
+    let counted = lengths.clone();
+    publisher.subscribe_args(move |word: &str| counted.lock().unwrap().push(word.len())).unwrap();
+
+    for number in 1..4 {
+        // Lives on the stack of this iteration only, so handlers can only copy out of it.
+        let line = format!("{} {}", "x".repeat(number), number);
+        let (first, second) = line.split_at(number);
+        publisher.publish_event(&Event::Args(first));
+        publisher.publish_event(&Event::Args(second.trim_start()));
+    }
+
+    assert_eq!(*log.lock().unwrap(), vec!["word x", "word 1", "word xx", "word 2", "word xxx", "word 3"]);
+    assert_eq!(*lengths.lock().unwrap(), vec![1, 1, 2, 1, 3, 1]);
+}
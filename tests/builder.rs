@@ -0,0 +1,103 @@
+extern crate event;
+
+use std::sync::{Arc, Mutex};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use event::{AuditOperation, AuditRecord, Backpressure, ChaosConfig, Event, EventHistory, EventPublisher, PublishError, TimeoutPolicy};
+
+#[test]
+fn every_option_is_applied_to_the_built_publisher() {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let (panic_log, audit_log) = (log.clone(), log.clone());
+    let history = Arc::new(EventHistory::new(10));
+    let publisher = EventPublisher::builder()
+        .max_subscribers(4)
+        .panic_hook(move |panic| panic_log.lock().unwrap().push(format!("panic {:?}", panic.message)))
+        .source("builder")
+        .interceptor(|event: &Event<u32>, next: &dyn Fn(&Event<u32>)| {
+            if *event != Event::Args(0) {
+                next(event);
+            }
+        })
+        .audit_sink(Box::new(move |record: &AuditRecord| {
+            if let AuditOperation::Publish { handlers } = record.operation {
+                audit_log.lock().unwrap().push(format!("audit {}", handlers));
+            }
+        }))
+        .metrics()
+        .leak_detection()
+        .history(history.clone())
+        .build();
+
+    assert_eq!(publisher.max_subscribers(), Some(4));
+    let sources = log.clone();
+    publisher.subscribe_envelope(move |envelope| sources.lock().unwrap().push(format!("source {:?}", envelope.source))).unwrap();
+    publisher.subscribe_args(|args: &u32| if *args == 2 { panic!("two") }).unwrap();
+    publisher.publish_event(&Event::Args(0));
+    publisher.publish_event(&Event::Args(2));
+    publisher.subscribe_filtered(|_| false, |_| {}).unwrap();
+    assert!(publisher.subscribe_args(|_| {}).is_err());
+
+    assert_eq!(*log.lock().unwrap(), vec!["audit 3", "source Some(\"builder\")", "panic Some(\"two\")"]);
+    assert_eq!(history.len(), 1);
+    assert_eq!(publisher.metrics().unwrap().events_published, 1);
+    assert_eq!(publisher.suspected_leaks(Duration::from_millis(0)).len(), 4);
+}
+
+#[test]
+fn the_dead_event_handler_and_chaos_are_applied_to_the_built_publisher() {
+    let dead = Arc::new(Mutex::new(Vec::new()));
+    let dead_events = dead.clone();
+    let publisher = EventPublisher::builder()
+        .dead_event_handler(move |event: &Event<u32>| dead_events.lock().unwrap().push(event.clone()))
+        .chaos(ChaosConfig { drop_probability: 1.0, ..ChaosConfig::new(1) })
+        .build();
+    publisher.publish_event(&Event::Args(1));
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let recorded = received.clone();
+    publisher.subscribe_args(move |args| recorded.lock().unwrap().push(*args)).unwrap();
+
+    publisher.publish_event(&Event::Args(2));
+
+    assert_eq!(*dead.lock().unwrap(), vec![Event::Args(1)]);
+    assert!(received.lock().unwrap().is_empty());
+}
+
+#[test]
+fn the_handler_timeout_and_its_hook_are_applied_to_the_built_publisher() {
+    let timeouts = Arc::new(Mutex::new(Vec::new()));
+    let reported = timeouts.clone();
+    let publisher = Arc::new(EventPublisher::builder()
+        .handler_timeout(TimeoutPolicy { timeout: Duration::from_millis(10), unsubscribe_after: Some(1) })
+        .timeout_hook(move |timeout| reported.lock().unwrap().push(*timeout))
+        .build());
+    let slow = publisher.subscribe_args(|_: &u32| thread::sleep(Duration::from_millis(100))).unwrap();
+
+    publisher.publish_event_with_timeout(&Event::Args(1));
+
+    let timeouts = timeouts.lock().unwrap();
+    assert_eq!(timeouts.len(), 1);
+    assert_eq!(timeouts[0].subscription, slow);
+    assert!(timeouts[0].unsubscribed);
+}
+
+#[test]
+fn the_queue_capacity_bounds_the_queued_and_threaded_publishers() {
+    let queued = EventPublisher::builder().queue_capacity(1, Backpressure::Reject).build_queued();
+    queued.publish_event(Event::Args(1u32)).unwrap();
+    match queued.publish_event(Event::Args(2)) {
+        Err(PublishError::Full(event)) => assert_eq!(event, Event::Args(2)),
+        other => panic!("expected the queue to be full, got {:?}", other),
+    }
+
+    let (sender, receiver) = mpsc::channel();
+    let (publisher, dispatcher) = EventPublisher::builder().queue_capacity(4, Backpressure::Block).build_threaded();
+    publisher.subscribe_channel(sender).unwrap();
+    for args in 0..10u32 {
+        dispatcher.publish_event(Event::Args(args)).unwrap();
+    }
+    dispatcher.shutdown();
+    assert_eq!(receiver.try_iter().collect::<Vec<u32>>(), (0..10).collect::<Vec<u32>>());
+}
@@ -0,0 +1,118 @@
+extern crate event;
+
+use std::ptr;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use event::{global_bus, Event, EventBus, WhilePaused};
+
+#[derive(Debug, Clone, PartialEq)]
+struct Clicked(u32);
+
+#[derive(Debug, Clone, PartialEq)]
+struct Closed;
+
+fn collect<T>(bus: &EventBus) -> Arc<Mutex<Vec<T>>> where T: Clone + Send + 'static {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let sink = received.clone();
+    bus.subscribe(move |event: &T| sink.lock().unwrap().push(event.clone()));
+    received
+}
+
+#[test]
+fn events_are_routed_by_type() {
+    let bus = EventBus::new();
+    let clicks = collect::<Clicked>(&bus);
+    let closes = collect::<Closed>(&bus);
+
+    bus.publish(Clicked(1));
+    bus.publish(Closed);
+    bus.publish(Clicked(2));
+    bus.publish(String::from("nobody listens"));
+
+    assert_eq!(*clicks.lock().unwrap(), vec![Clicked(1), Clicked(2)]);
+    assert_eq!(*closes.lock().unwrap(), vec![Closed]);
+}
+
+#[test]
+fn unsubscribed_handlers_no_longer_receive_events() {
+    let bus = EventBus::new();
+    let received = Arc::new(Mutex::new(0));
+    let counter = received.clone();
+    let id = bus.subscribe(move |_: &Clicked| *counter.lock().unwrap() += 1);
+
+    bus.publish(Clicked(1));
+    assert!(bus.unsubscribe::<Clicked>(id));
+    assert!(!bus.unsubscribe::<Closed>(id));
+    bus.publish(Clicked(2));
+
+    assert_eq!(*received.lock().unwrap(), 1);
+}
+
+#[test]
+fn pausing_the_publisher_of_a_type_holds_bus_events() {
+    let bus = EventBus::new();
+    let clicks = collect::<Clicked>(&bus);
+
+    bus.publisher::<Clicked>().pause(WhilePaused::Buffer(10));
+    bus.publish(Clicked(1));
+    assert!(clicks.lock().unwrap().is_empty());
+
+    assert_eq!(bus.publisher::<Clicked>().resume(), 1);
+    assert_eq!(*clicks.lock().unwrap(), vec![Clicked(1)]);
+}
+
+#[test]
+fn the_global_bus_is_shared_by_every_caller() {
+    #[derive(Debug, Clone, PartialEq)]
+    struct GlobalOnly(u32);
+
+    let received = collect::<GlobalOnly>(global_bus());
+    assert!(ptr::eq(global_bus(), global_bus()));
+
+    thread::spawn(|| global_bus().publish(GlobalOnly(1))).join().unwrap();
+
+    assert_eq!(*received.lock().unwrap(), vec![GlobalOnly(1)]);
+}
+
+#[test]
+fn diagnostics_report_every_event_type_on_the_bus() {
+    let bus = EventBus::new();
+    collect::<Clicked>(&bus);
+    collect::<Clicked>(&bus);
+    collect::<Closed>(&bus);
+    bus.publish(Clicked(1));
+    bus.publish(Clicked(2));
+    // Published on the type's publisher directly, so not counted.
+    bus.publisher::<Closed>().publish_event(&Event::Args(Closed));
+
+    let diagnostics = bus.diagnostics();
+    assert_eq!(diagnostics.len(), 2);
+    let clicked = diagnostics.iter().find(|stats| stats.type_name.ends_with("Clicked")).unwrap();
+    assert_eq!((clicked.subscribers, clicked.published), (2, 2));
+    assert!(clicked.last_published.is_some());
+    let closed = diagnostics.iter().find(|stats| stats.type_name.ends_with("Closed")).unwrap();
+    assert_eq!((closed.subscribers, closed.published), (1, 0));
+    assert_eq!(closed.average_dispatch, Duration::ZERO);
+    assert_eq!(closed.last_published, None);
+}
+
+#[test]
+fn the_dump_has_a_line_per_event_type() {
+    let bus = EventBus::new();
+    collect::<Clicked>(&bus);
+    collect::<Closed>(&bus);
+    bus.publish(Clicked(1));
+
+    let dump = bus.dump();
+    let lines: Vec<&str> = dump.lines().collect();
+
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0], "EventBus with 2 event types");
+    let clicked = lines.iter().find(|line| line.contains("Clicked")).unwrap();
+    assert!(clicked.contains("1 subscribers, 1 published"));
+    assert!(clicked.contains("last published"));
+    let closed = lines.iter().find(|line| line.contains("Closed")).unwrap();
+    assert!(closed.ends_with("1 subscribers, 0 published"));
+}
@@ -0,0 +1,67 @@
+extern crate event;
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use event::{ChaosConfig, Event, EventPublisher};
+
+// Publishes 0..100 with the given chaos configuration and returns what the handler received.
+fn deliveries(config: ChaosConfig) -> Vec<u32> {
+    let publisher = EventPublisher::new();
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let recorded = received.clone();
+    publisher.subscribe_args(move |args| recorded.lock().unwrap().push(*args)).unwrap();
+    publisher.set_chaos(Some(config));
+    for args in 0..100 {
+        publisher.publish_event(&Event::Args(args));
+    }
+    let received = received.lock().unwrap().clone();
+    received
+}
+
+#[test]
+fn the_same_seed_drops_the_same_deliveries() {
+    let config = ChaosConfig { drop_probability: 0.5, ..ChaosConfig::new(42) };
+
+    let received = deliveries(config.clone());
+
+    assert!(!received.is_empty() && received.len() < 100);
+    assert!(received.windows(2).all(|pair| pair[0] < pair[1]));
+    assert_eq!(deliveries(config.clone()), received);
+    assert_ne!(deliveries(ChaosConfig { seed: 7, ..config }), received);
+}
+
+#[test]
+fn duplicated_deliveries_reach_the_handler_twice() {
+    let received = deliveries(ChaosConfig { duplicate_probability: 1.0, ..ChaosConfig::new(1) });
+
+    let expected: Vec<u32> = (0..100).flat_map(|args| vec![args, args]).collect();
+    assert_eq!(received, expected);
+}
+
+#[test]
+fn delayed_deliveries_still_arrive_in_order() {
+    let config = ChaosConfig { delay_probability: 1.0, max_delay: Duration::from_millis(1), ..ChaosConfig::new(3) };
+    let started = Instant::now();
+
+    let received = deliveries(config);
+
+    // The delays drawn from the seed add up to well over this.
+    assert!(started.elapsed() >= Duration::from_millis(20));
+    assert_eq!(received, (0..100).collect::<Vec<u32>>());
+}
+
+#[test]
+fn switching_chaos_off_goes_back_to_reliable_delivery() {
+    let publisher = EventPublisher::new();
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let recorded = received.clone();
+    publisher.subscribe_args(move |args| recorded.lock().unwrap().push(*args)).unwrap();
+    publisher.set_chaos(Some(ChaosConfig { drop_probability: 1.0, ..ChaosConfig::new(5) }));
+    publisher.publish_event(&Event::Args(1));
+
+    publisher.set_chaos(None);
+    publisher.publish_event(&Event::Args(2));
+
+    assert_eq!(*received.lock().unwrap(), vec![2]);
+}
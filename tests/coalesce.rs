@@ -0,0 +1,38 @@
+extern crate event;
+
+use std::sync::{mpsc, Mutex};
+
+use event::{CoalescingPublisher, Event};
+
+fn delivered(coalescing: &CoalescingPublisher<(char, u32), char>) -> Vec<Event<(char, u32)>> {
+    let (sender, receiver) = mpsc::channel();
+    let sender = Mutex::new(sender);
+    let id = coalescing.publisher().subscribe_handler(Box::new(move |event: &Event<(char, u32)>| sender.lock().unwrap().send(event.clone()).unwrap())).unwrap();
+    coalescing.dispatch_pending();
+    coalescing.publisher().unsubscribe(id);
+    receiver.try_iter().collect()
+}
+
+#[test]
+fn events_of_a_key_are_merged_into_the_latest_where_the_first_was_queued() {
+    let coalescing = CoalescingPublisher::new(|&(key, _): &(char, u32)| key);
+    coalescing.publish_event(Event::Args(('a', 1)));
+    coalescing.publish_event(Event::Args(('b', 1)));
+    coalescing.publish_event(Event::Missing);
+    coalescing.publish_event(Event::Args(('a', 2)));
+    coalescing.publish_event(Event::Missing);
+    assert_eq!(coalescing.len(), 4);
+
+    assert_eq!(delivered(&coalescing), vec![Event::Args(('a', 2)), Event::Args(('b', 1)), Event::Missing, Event::Missing]);
+    assert!(coalescing.is_empty());
+}
+
+#[test]
+fn a_reducer_folds_the_events_of_a_key() {
+    let coalescing = CoalescingPublisher::with_reducer(|&(key, _): &(char, u32)| key, |(key, total), (_, count)| (key, total + count));
+    coalescing.publish_event(Event::Args(('a', 1)));
+    coalescing.publish_event(Event::Args(('b', 10)));
+    coalescing.publish_event(Event::Args(('a', 2)));
+
+    assert_eq!(delivered(&coalescing), vec![Event::Args(('a', 3)), Event::Args(('b', 10))]);
+}
@@ -0,0 +1,63 @@
+#![cfg(feature = "serde")]
+
+extern crate event;
+extern crate serde;
+
+use std::sync::{Arc, Mutex};
+
+use event::{BincodeCodec, Codec, CodecError, ErrorPolicy, Event, EventPublisher, JsonCodec};
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+struct Moved {
+    x: i32,
+    y: i32,
+}
+
+fn receiving(publisher: &EventPublisher<Moved>) -> Arc<Mutex<Vec<Event<Moved>>>> {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let recorded = received.clone();
+    publisher.subscribe_handler(Box::new(move |event: &Event<Moved>| recorded.lock().unwrap().push(event.clone()))).unwrap();
+    received
+}
+
+#[test]
+fn events_round_trip_through_bincode_and_json() {
+    let publisher = EventPublisher::new();
+    let received = receiving(&publisher);
+    let moved = Event::Args(Moved { x: 1, y: -2 });
+
+    publisher.publish_serialized(&BincodeCodec, &BincodeCodec.encode(&moved).unwrap()).unwrap();
+    publisher.publish_serialized(&JsonCodec, &JsonCodec.encode(&moved).unwrap()).unwrap();
+    publisher.publish_serialized(&BincodeCodec, &BincodeCodec.encode(&Event::Missing::<Moved>).unwrap()).unwrap();
+
+    assert_eq!(*received.lock().unwrap(), vec![moved.clone(), moved, Event::Missing]);
+}
+
+#[test]
+fn malformed_bytes_are_a_codec_error_and_are_not_published() {
+    let publisher = EventPublisher::new();
+    let received = receiving(&publisher);
+
+    let error: CodecError = publisher.publish_serialized(&BincodeCodec, &[0xff, 0xff, 0xff, 0xff]).unwrap_err();
+    assert!(error.to_string().starts_with("failed to encode or decode event"));
+    assert!(publisher.publish_serialized(&JsonCodec, b"{\"Args\":").is_err());
+
+    assert!(received.lock().unwrap().is_empty());
+}
+
+#[test]
+fn deserializing_subscribers_decode_the_frames_and_report_the_ones_that_fail() {
+    let frames: EventPublisher<Vec<u8>> = EventPublisher::new();
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let recorded = received.clone();
+    let decoding = frames.subscribe_deserializing(BincodeCodec, move |event: &Event<Moved>| recorded.lock().unwrap().push(event.clone())).unwrap();
+
+    frames.publish_event(&Event::Args(BincodeCodec.encode(&Event::Args(Moved { x: 3, y: 4 })).unwrap()));
+    let errors = frames.publish_event_fallible(&Event::Args(vec![9, 9]), ErrorPolicy::CollectAll).unwrap_err();
+    frames.publish_event(&Event::Missing);
+
+    assert_eq!(*received.lock().unwrap(), vec![Event::Args(Moved { x: 3, y: 4 }), Event::Missing]);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].subscription, decoding);
+    assert!(errors[0].error.downcast_ref::<CodecError>().is_some());
+}
@@ -0,0 +1,70 @@
+extern crate event;
+
+use std::sync::{mpsc, Mutex};
+use std::sync::mpsc::RecvTimeoutError;
+use std::time::Duration;
+
+use event::{Event, EventPublisher};
+
+#[test]
+fn a_throttled_handler_drops_events_within_its_interval() {
+    let publisher = EventPublisher::new();
+    let (sender, receiver) = mpsc::channel();
+    let sender = Mutex::new(sender);
+    publisher.subscribe_throttled(Duration::from_secs(3600), move |event: &Event<u32>| sender.lock().unwrap().send(event.clone()).unwrap()).unwrap();
+
+    for args in 1..=3 {
+        publisher.publish_event(&Event::Args(args));
+    }
+
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), vec![Event::Args(1)]);
+}
+
+#[test]
+fn a_debounced_handler_gets_the_last_event_of_a_burst() {
+    let publisher = EventPublisher::new();
+    let (sender, receiver) = mpsc::channel();
+    let sender = Mutex::new(sender);
+    publisher.subscribe_debounced(Duration::from_millis(50), move |event: &Event<u32>| sender.lock().unwrap().send(event.clone()).unwrap()).unwrap();
+
+    for args in 1..=3 {
+        publisher.publish_event(&Event::Args(args));
+    }
+
+    assert_eq!(receiver.recv_timeout(Duration::from_secs(5)).unwrap(), Event::Args(3));
+    assert!(receiver.recv_timeout(Duration::from_millis(150)).is_err());
+
+    publisher.publish_event(&Event::Args(4));
+    assert_eq!(receiver.recv_timeout(Duration::from_secs(5)).unwrap(), Event::Args(4));
+}
+
+#[test]
+fn a_panicking_debounced_handler_still_gets_later_bursts() {
+    let publisher = EventPublisher::new();
+    let (sender, receiver) = mpsc::channel();
+    let sender = Mutex::new(sender);
+    publisher.subscribe_debounced(Duration::from_millis(20), move |event: &Event<u32>| {
+        sender.lock().unwrap().send(event.clone()).unwrap();
+        panic!("handler failed");
+    }).unwrap();
+
+    publisher.publish_event(&Event::Args(1));
+    assert_eq!(receiver.recv_timeout(Duration::from_secs(5)).unwrap(), Event::Args(1));
+    publisher.publish_event(&Event::Args(2));
+    assert_eq!(receiver.recv_timeout(Duration::from_secs(5)).unwrap(), Event::Args(2));
+}
+
+#[test]
+fn a_burst_pending_when_the_handler_is_unsubscribed_is_still_delivered() {
+    let publisher = EventPublisher::new();
+    let (sender, receiver) = mpsc::channel();
+    let sender = Mutex::new(sender);
+    let id = publisher.subscribe_debounced(Duration::from_millis(50), move |event: &Event<u32>| sender.lock().unwrap().send(event.clone()).unwrap()).unwrap();
+
+    publisher.publish_event(&Event::Args(1));
+    publisher.unsubscribe(id);
+
+    assert_eq!(receiver.recv_timeout(Duration::from_secs(5)).unwrap(), Event::Args(1));
+    // The timer thread has ended and dropped the handler, and with it the sender.
+    assert_eq!(receiver.recv_timeout(Duration::from_secs(5)), Err(RecvTimeoutError::Disconnected));
+}
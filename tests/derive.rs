@@ -0,0 +1,55 @@
+#![cfg(feature = "derive")]
+
+extern crate event;
+
+use std::sync::{Arc, Mutex};
+
+use event::{Event, EventKind, EventPublisher};
+
+#[derive(Debug, Clone, PartialEq, EventKind)]
+enum UiEvent {
+    Clicked { x: i32, y: i32 },
+    KeyPressed(char),
+    Closed,
+}
+
+#[derive(Debug, Clone, PartialEq, EventKind)]
+enum HTTPEvent {
+    RequestSent,
+    IOError,
+}
+
+#[test]
+fn variant_names_are_the_variant_identifiers() {
+    assert_eq!(UiEvent::Clicked { x: 1, y: 2 }.variant_name(), "Clicked");
+    assert_eq!(UiEvent::KeyPressed('a').variant_name(), "KeyPressed");
+    assert_eq!(UiEvent::Closed.variant_name(), "Closed");
+}
+
+#[test]
+fn handlers_subscribed_to_a_variant_only_see_that_variant() {
+    let publisher = EventPublisher::new();
+    let keys = Arc::new(Mutex::new(Vec::new()));
+    let sink = keys.clone();
+    publisher.subscribe_variant::<ui_event::KeyPressed, _>(move |event: &UiEvent| sink.lock().unwrap().push(event.clone())).unwrap();
+
+    publisher.publish_event(&Event::Args(UiEvent::Clicked { x: 1, y: 2 }));
+    publisher.publish_event(&Event::Args(UiEvent::KeyPressed('q')));
+    publisher.publish_event(&Event::Missing);
+    publisher.publish_event(&Event::Args(UiEvent::Closed));
+
+    assert_eq!(*keys.lock().unwrap(), vec![UiEvent::KeyPressed('q')]);
+}
+
+#[test]
+fn a_run_of_capitals_is_one_word_in_the_marker_module_name() {
+    let publisher = EventPublisher::new();
+    let errors = Arc::new(Mutex::new(0));
+    let counter = errors.clone();
+    publisher.subscribe_variant::<http_event::IOError, _>(move |_: &HTTPEvent| *counter.lock().unwrap() += 1).unwrap();
+
+    publisher.publish_event(&Event::Args(HTTPEvent::RequestSent));
+    publisher.publish_event(&Event::Args(HTTPEvent::IOError));
+
+    assert_eq!(*errors.lock().unwrap(), 1);
+}
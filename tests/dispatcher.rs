@@ -0,0 +1,129 @@
+extern crate event;
+
+use std::sync::{Arc, Mutex};
+use std::sync::mpsc;
+
+use event::{Backpressure, CancellationToken, Event, EventPublisher, PublishError, Shutdown};
+
+#[test]
+fn a_cancelled_dispatcher_refuses_events_as_closed() {
+    let dispatcher = Arc::new(EventPublisher::<u32>::new()).spawn_dispatcher();
+    let token = CancellationToken::new();
+    dispatcher.cancel_on(&token, Shutdown::Drain);
+    token.cancel();
+
+    match dispatcher.publish_event(Event::Args(1)) {
+        Err(PublishError::Closed(event)) => assert_eq!(event, Event::Args(1)),
+        other => panic!("expected the event to be refused as closed, got {:?}", other),
+    }
+}
+
+#[test]
+fn a_full_dispatcher_refuses_events_as_full() {
+    let publisher = Arc::new(EventPublisher::<u32>::new());
+    let (entered, entered_receiver) = mpsc::channel();
+    let (release, released) = mpsc::channel::<()>();
+    let released = Mutex::new(released);
+    publisher.subscribe_args(move |_: &u32| {
+        let _ = entered.send(());
+        let _ = released.lock().unwrap().recv();
+    }).unwrap();
+    let dispatcher = publisher.spawn_dispatcher_with_capacity(1, Backpressure::Reject);
+
+    dispatcher.publish_event(Event::Args(1)).unwrap();
+    entered_receiver.recv().unwrap();
+    dispatcher.publish_event(Event::Args(2)).unwrap();
+    let refused = dispatcher.publish_event(Event::Args(3));
+    drop(release);
+
+    match refused {
+        Err(PublishError::Full(event)) => assert_eq!(event, Event::Args(3)),
+        other => panic!("expected the event to be refused as full, got {:?}", other),
+    }
+}
+
+#[test]
+fn the_dispatcher_delivers_higher_priorities_first() {
+    let publisher = Arc::new(EventPublisher::<u32>::new());
+    let (entered, entered_receiver) = mpsc::channel();
+    let (release, released) = mpsc::channel::<()>();
+    let released = Mutex::new(released);
+    let (sender, receiver) = mpsc::channel();
+    publisher.subscribe_args(move |args: &u32| {
+        if *args == 0 {
+            let _ = entered.send(());
+            let _ = released.lock().unwrap().recv();
+        }
+    }).unwrap();
+    publisher.subscribe_channel(sender).unwrap();
+    let dispatcher = publisher.spawn_dispatcher();
+
+    // Holds the dispatcher thread up until the rest is queued.
+    dispatcher.publish_event(Event::Args(0)).unwrap();
+    entered_receiver.recv().unwrap();
+    dispatcher.publish_event(Event::Args(1)).unwrap();
+    dispatcher.publish_with_priority(Event::Args(2), 1).unwrap();
+    drop(release);
+    dispatcher.shutdown();
+
+    assert_eq!(receiver.try_iter().collect::<Vec<u32>>(), vec![0, 2, 1]);
+}
+
+#[test]
+fn cancelling_with_discard_drops_the_queued_events() {
+    let publisher = Arc::new(EventPublisher::<u32>::new());
+    let (entered, entered_receiver) = mpsc::channel();
+    let (release, released) = mpsc::channel::<()>();
+    let released = Mutex::new(released);
+    let (sender, receiver) = mpsc::channel();
+    publisher.subscribe_channel(sender).unwrap();
+    publisher.subscribe_args(move |args: &u32| {
+        if *args == 0 {
+            let _ = entered.send(());
+            let _ = released.lock().unwrap().recv();
+        }
+    }).unwrap();
+    let dispatcher = publisher.spawn_dispatcher();
+    let token = CancellationToken::new();
+    dispatcher.cancel_on(&token.clone(), Shutdown::Discard);
+
+    dispatcher.publish_event(Event::Args(0)).unwrap();
+    entered_receiver.recv().unwrap();
+    dispatcher.publish_event(Event::Args(1)).unwrap();
+    dispatcher.publish_event(Event::Args(2)).unwrap();
+    token.cancel();
+    assert!(token.is_cancelled());
+    assert_eq!(dispatcher.pending(), 0);
+    drop(release);
+
+    assert_eq!(dispatcher.shutdown_with(Shutdown::Drain), 2);
+    assert_eq!(receiver.try_iter().collect::<Vec<u32>>(), vec![0]);
+}
+
+#[test]
+fn a_token_cancelled_beforehand_shuts_the_dispatcher_down_straight_away() {
+    let token = CancellationToken::new();
+    token.cancel();
+    token.cancel();
+    let dispatcher = Arc::new(EventPublisher::<u32>::new()).spawn_dispatcher();
+
+    dispatcher.cancel_on(&token, Shutdown::Drain);
+
+    assert!(dispatcher.publish_event(Event::Args(1)).is_err());
+    assert_eq!(dispatcher.shutdown_with(Shutdown::Drain), 0);
+}
+
+#[test]
+fn shutdown_delivers_the_queued_events_first() {
+    let publisher = Arc::new(EventPublisher::<u32>::new());
+    let (sender, receiver) = mpsc::channel();
+    publisher.subscribe_channel(sender).unwrap();
+    let dispatcher = publisher.spawn_dispatcher();
+
+    for args in 1..=100 {
+        dispatcher.publish_event(Event::Args(args)).unwrap();
+    }
+    dispatcher.shutdown();
+
+    assert_eq!(receiver.try_iter().collect::<Vec<u32>>(), (1..=100).collect::<Vec<u32>>());
+}
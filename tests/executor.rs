@@ -0,0 +1,64 @@
+extern crate event;
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::sync::mpsc;
+use std::thread::{self, ThreadId};
+use std::time::Duration;
+
+use event::{Event, EventPublisher, RunOn, Task};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+// Publishes 0..20 to a handler subscribed with run_on, and returns what it received in the order it received it, together
+//     with the threads it ran on.
+fn received_on(run_on: RunOn) -> (Vec<u32>, HashSet<ThreadId>) {
+    let publisher = EventPublisher::new();
+    let (sender, receiver) = mpsc::channel();
+    publisher.subscribe_on(run_on, move |event: &Event<u32>| {
+        if let Event::Args(args) = *event {
+            sender.send((args, thread::current().id())).unwrap();
+        }
+    }).unwrap();
+
+    for args in 0..20 {
+        publisher.publish_event(&Event::Args(args));
+    }
+
+    let received: Vec<(u32, ThreadId)> = (0..20).map(|_| receiver.recv_timeout(TIMEOUT).unwrap()).collect();
+    assert!(!received.iter().any(|&(_, thread)| thread == thread::current().id()));
+    (received.iter().map(|&(args, _)| args).collect(), received.iter().map(|&(_, thread)| thread).collect())
+}
+
+#[test]
+fn pool_handlers_see_every_event_off_the_publishing_thread() {
+    let (mut received, _) = received_on(RunOn::Pool);
+
+    received.sort();
+    assert_eq!(received, (0..20).collect::<Vec<u32>>());
+}
+
+#[test]
+fn dedicated_handlers_see_every_event_in_order_on_one_thread() {
+    let (received, threads) = received_on(RunOn::Dedicated);
+
+    assert_eq!(received, (0..20).collect::<Vec<u32>>());
+    assert_eq!(threads.len(), 1);
+}
+
+#[test]
+fn executor_handlers_run_where_the_executor_runs_them() {
+    // Stands in for the event loop of a UI thread.
+    let (tasks, queued) = mpsc::channel::<Task>();
+    let event_loop = thread::spawn(move || {
+        for task in queued {
+            task();
+        }
+        thread::current().id()
+    });
+
+    let (received, threads) = received_on(RunOn::Executor(Arc::new(move |task| tasks.send(task).unwrap())));
+
+    assert_eq!(received, (0..20).collect::<Vec<u32>>());
+    assert_eq!(threads.into_iter().collect::<Vec<_>>(), vec![event_loop.join().unwrap()]);
+}
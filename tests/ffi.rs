@@ -0,0 +1,51 @@
+#![cfg(feature = "ffi")]
+
+extern crate event;
+
+use std::os::raw::c_void;
+use std::ptr;
+use std::sync::Mutex;
+
+use event::{events_publish, events_publisher_free, events_publisher_new, events_subscribe, events_unsubscribe};
+
+// Payloads received by the callback, None for Event::Missing.
+type Received = Mutex<Vec<Option<Vec<u8>>>>;
+
+extern "C" fn record(data: *const u8, len: usize, user_data: *mut c_void) {
+    let received = unsafe { &*(user_data as *const Received) };
+    let payload = if data.is_null() { None } else { Some(unsafe { std::slice::from_raw_parts(data, len) }.to_vec()) };
+    received.lock().unwrap().push(payload);
+}
+
+#[test]
+fn c_callbacks_receive_published_payloads_until_unsubscribed() {
+    let received: Received = Mutex::new(Vec::new());
+    let user_data = &received as *const Received as *mut c_void;
+    unsafe {
+        let publisher = events_publisher_new();
+        let subscription = events_subscribe(publisher, record, user_data);
+        assert_ne!(subscription, 0);
+
+        events_publish(publisher, b"hello".as_ptr(), 5);
+        events_publish(publisher, ptr::null(), 0);
+        assert!(events_unsubscribe(publisher, subscription));
+        assert!(!events_unsubscribe(publisher, subscription));
+        events_publish(publisher, b"ignored".as_ptr(), 7);
+
+        events_publisher_free(publisher);
+    }
+
+    assert_eq!(*received.lock().unwrap(), vec![Some(b"hello".to_vec()), None]);
+}
+
+#[test]
+fn null_publishers_are_ignored() {
+    let received: Received = Mutex::new(Vec::new());
+    unsafe {
+        assert_eq!(events_subscribe(ptr::null(), record, &received as *const Received as *mut c_void), 0);
+        assert!(!events_unsubscribe(ptr::null(), 1));
+        events_publish(ptr::null(), b"x".as_ptr(), 1);
+        events_publisher_free(ptr::null_mut());
+    }
+    assert!(received.lock().unwrap().is_empty());
+}
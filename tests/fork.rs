@@ -0,0 +1,54 @@
+extern crate event;
+
+use std::sync::{Arc, Mutex};
+
+use event::{Event, EventPublisher, SubscriptionId};
+
+type Log = Arc<Mutex<Vec<String>>>;
+
+fn log_as(publisher: &EventPublisher<u32>, log: &Log, name: &'static str) -> SubscriptionId {
+    let log = log.clone();
+    publisher.subscribe_args(move |args| log.lock().unwrap().push(format!("{} {}", name, args))).unwrap()
+}
+
+#[test]
+fn a_fork_starts_with_the_same_handlers_and_then_goes_its_own_way() {
+    let publisher = EventPublisher::new();
+    let log: Log = Arc::new(Mutex::new(Vec::new()));
+    let shared = log_as(&publisher, &log, "shared");
+    let removed = log_as(&publisher, &log, "removed");
+
+    let fork = publisher.fork();
+    fork.publish_event(&Event::Args(1));
+    assert_eq!(*log.lock().unwrap(), vec!["shared 1", "removed 1"]);
+
+    assert!(fork.unsubscribe(removed));
+    log_as(&fork, &log, "forked");
+    log_as(&publisher, &log, "original");
+    log.lock().unwrap().clear();
+    publisher.publish_event(&Event::Args(2));
+    fork.publish_event(&Event::Args(3));
+
+    assert_eq!(*log.lock().unwrap(), vec!["shared 2", "removed 2", "original 2", "shared 3", "forked 3"]);
+    assert!(publisher.unsubscribe(shared));
+    assert_eq!((publisher.subscriber_count(), fork.subscriber_count()), (2, 2));
+}
+
+#[test]
+fn forked_handlers_share_their_state() {
+    let publisher = EventPublisher::new();
+    let totals = Arc::new(Mutex::new(Vec::new()));
+    let reported = totals.clone();
+    publisher.subscribe_with_state(0, move |total: &mut u32, event: &Event<u32>| {
+        if let Event::Args(args) = *event {
+            *total += args;
+            reported.lock().unwrap().push(*total);
+        }
+    }).unwrap();
+
+    let fork = publisher.fork();
+    publisher.publish_event(&Event::Args(1));
+    fork.publish_event(&Event::Args(2));
+
+    assert_eq!(*totals.lock().unwrap(), vec![1, 3]);
+}
@@ -0,0 +1,60 @@
+extern crate event;
+
+use std::sync::{Arc, Mutex};
+
+use event::{Event, EventPublisher};
+
+fn recording(publisher: &EventPublisher<u32>) -> Arc<Mutex<Vec<u32>>> {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let recorded = received.clone();
+    publisher.subscribe_args(move |args| recorded.lock().unwrap().push(*args)).unwrap();
+    received
+}
+
+#[test]
+fn events_are_republished_on_the_other_publisher() {
+    let (a, b) = (EventPublisher::new(), Arc::new(EventPublisher::new()));
+    let (on_a, on_b) = (recording(&a), recording(&b));
+    let forward = a.forward_to(b.clone()).unwrap();
+
+    a.publish_event(&Event::Args(1));
+    b.publish_event(&Event::Args(2));
+    assert!(a.unsubscribe(forward));
+    a.publish_event(&Event::Args(3));
+
+    assert_eq!(*on_a.lock().unwrap(), vec![1, 3]);
+    assert_eq!(*on_b.lock().unwrap(), vec![1, 2]);
+}
+
+#[test]
+fn publishers_forwarding_to_each_other_deliver_every_event_once() {
+    let (a, b) = (Arc::new(EventPublisher::new()), Arc::new(EventPublisher::new()));
+    let (on_a, on_b) = (recording(&a), recording(&b));
+    let a_to_b = a.forward_to(b.clone()).unwrap();
+    let b_to_a = b.forward_to(a.clone()).unwrap();
+
+    a.publish_event(&Event::Args(1));
+    b.publish_event(&Event::Args(2));
+
+    assert_eq!(*on_a.lock().unwrap(), vec![1, 2]);
+    assert_eq!(*on_b.lock().unwrap(), vec![1, 2]);
+    // Break the cycle of the publishers keeping each other alive.
+    assert!(a.unsubscribe(a_to_b));
+    assert!(b.unsubscribe(b_to_a));
+    assert_eq!(Arc::strong_count(&a), 1);
+    assert_eq!(Arc::strong_count(&b), 1);
+}
+
+#[test]
+fn a_publisher_forwarding_to_itself_delivers_every_event_once() {
+    let publisher = Arc::new(EventPublisher::new());
+    let received = recording(&publisher);
+    let forward = publisher.forward_to(publisher.clone()).unwrap();
+
+    publisher.publish_event(&Event::Args(1));
+    publisher.publish_event(&Event::Args(2));
+
+    assert_eq!(*received.lock().unwrap(), vec![1, 2]);
+    assert!(publisher.unsubscribe(forward));
+    assert_eq!(Arc::strong_count(&publisher), 1);
+}
@@ -0,0 +1,58 @@
+extern crate event;
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use event::{Event, EventHistory, EventPublisher};
+
+fn sequences_and_events(history: &EventHistory<u32>, since: u64) -> Vec<(u64, Event<u32>)> {
+    history.replay_since(since).into_iter().map(|recorded| (recorded.sequence, recorded.event)).collect()
+}
+
+#[test]
+fn the_history_keeps_the_last_events_up_to_its_capacity() {
+    let publisher = EventPublisher::new();
+    let history = Arc::new(EventHistory::new(2));
+    publisher.subscribe_history(history.clone()).unwrap();
+
+    publisher.publish_event(&Event::Args(1));
+    publisher.publish_event(&Event::Missing);
+    publisher.publish_event(&Event::Args(3));
+
+    assert_eq!(history.len(), 2);
+    assert_eq!(sequences_and_events(&history, 0), vec![(2, Event::Missing), (3, Event::Args(3))]);
+    assert_eq!(sequences_and_events(&history, 2), vec![(3, Event::Args(3))]);
+    assert!(sequences_and_events(&history, 3).is_empty());
+}
+
+#[test]
+fn replay_hands_out_the_events_as_they_were_published() {
+    let publisher = EventPublisher::new();
+    publisher.set_source(Some(String::from("sensor")));
+    let history = Arc::new(EventHistory::new(10));
+    publisher.subscribe_history(history.clone()).unwrap();
+    publisher.publish_event(&Event::Args(1));
+    publisher.publish_event(&Event::Args(2));
+
+    let replayed = Mutex::new(Vec::new());
+    history.replay_to(|envelope| replayed.lock().unwrap().push((envelope.sequence, envelope.source.map(String::from), envelope.event.clone())));
+
+    assert_eq!(replayed.into_inner().unwrap(), vec![
+        (1, Some(String::from("sensor")), Event::Args(1)),
+        (2, Some(String::from("sensor")), Event::Args(2)),
+    ]);
+}
+
+#[test]
+fn a_history_with_a_time_to_live_forgets_older_events() {
+    let publisher = EventPublisher::new();
+    let history = Arc::new(EventHistory::with_ttl(10, Duration::from_millis(10)));
+    publisher.subscribe_history(history.clone()).unwrap();
+
+    publisher.publish_event(&Event::Args(1));
+    thread::sleep(Duration::from_millis(20));
+    publisher.publish_event(&Event::Args(2));
+
+    assert_eq!(sequences_and_events(&history, 0), vec![(2, Event::Args(2))]);
+}
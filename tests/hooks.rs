@@ -0,0 +1,63 @@
+extern crate event;
+
+use std::ops::ControlFlow;
+use std::sync::{Arc, Mutex};
+
+use event::{Event, EventPublisher, SubscriptionId, Unsubscribe};
+
+type Changes = Arc<Mutex<Vec<(&'static str, SubscriptionId, usize)>>>;
+
+fn hooked(publisher: &EventPublisher<u32>) -> Changes {
+    let changes: Changes = Arc::new(Mutex::new(Vec::new()));
+    let (subscribed, unsubscribed) = (changes.clone(), changes.clone());
+    publisher.set_subscribe_hook(Box::new(move |id, count| subscribed.lock().unwrap().push(("subscribe", id, count))));
+    publisher.set_unsubscribe_hook(Box::new(move |id, count| unsubscribed.lock().unwrap().push(("unsubscribe", id, count))));
+    changes
+}
+
+#[test]
+fn hooks_fire_once_per_explicit_subscribe_and_unsubscribe() {
+    let publisher = EventPublisher::new();
+    let changes = hooked(&publisher);
+
+    let first = publisher.subscribe_args(|_| {}).unwrap();
+    let second = publisher.subscribe_args(|_| {}).unwrap();
+    assert!(publisher.unsubscribe(first));
+    assert!(!publisher.unsubscribe(first));
+    let scoped = publisher.subscribe_scoped(Box::new(|_| {})).unwrap();
+    let scoped_id = scoped.id();
+    drop(scoped);
+
+    assert_eq!(*changes.lock().unwrap(), vec![
+        ("subscribe", first, 1),
+        ("subscribe", second, 2),
+        ("unsubscribe", first, 1),
+        ("subscribe", scoped_id, 2),
+        ("unsubscribe", scoped_id, 1),
+    ]);
+}
+
+#[test]
+fn hooks_fire_once_for_handlers_removing_themselves() {
+    let publisher = EventPublisher::new();
+    let changes = hooked(&publisher);
+    let once = publisher.subscribe_once(|_| {}).unwrap();
+    let until = publisher.subscribe_until(|event| if *event == Event::Args(2) { ControlFlow::Break(Unsubscribe) } else { ControlFlow::Continue(()) }).unwrap();
+    let subscriber = Arc::new(());
+    let weak = publisher.subscribe_weak(Arc::downgrade(&subscriber), |_, _| {}).unwrap();
+    changes.lock().unwrap().clear();
+
+    publisher.publish_event(&Event::Args(1));
+    drop(subscriber);
+    publisher.publish_event(&Event::Args(2));
+    publisher.publish_event(&Event::Args(3));
+
+    let mut removed: Vec<SubscriptionId> = changes.lock().unwrap().iter().map(|&(change, id, _)| {
+        assert_eq!(change, "unsubscribe");
+        id
+    }).collect();
+    assert_eq!(removed.remove(0), once);
+    removed.sort();
+    assert_eq!(removed, vec![until, weak]);
+    assert_eq!(changes.lock().unwrap().last().unwrap().2, 0);
+}
@@ -0,0 +1,77 @@
+extern crate event;
+
+use std::sync::{Arc, Mutex};
+
+use event::{Event, EventPublisher};
+
+type Log = Arc<Mutex<Vec<String>>>;
+
+fn receiving(publisher: &EventPublisher<u32>) -> Log {
+    let log: Log = Arc::new(Mutex::new(Vec::new()));
+    let received = log.clone();
+    publisher.subscribe_args(move |args| received.lock().unwrap().push(format!("handler {}", args))).unwrap();
+    log
+}
+
+#[test]
+fn interceptors_wrap_the_publish_in_the_order_they_were_added() {
+    let publisher = EventPublisher::new();
+    let log = receiving(&publisher);
+    for name in ["outer", "inner"] {
+        let log = log.clone();
+        publisher.add_interceptor(move |event: &Event<u32>, next: &dyn Fn(&Event<u32>)| {
+            log.lock().unwrap().push(format!("{} before", name));
+            next(event);
+            log.lock().unwrap().push(format!("{} after", name));
+        });
+    }
+
+    publisher.publish_event(&Event::Args(1));
+
+    assert_eq!(*log.lock().unwrap(), vec!["outer before", "inner before", "handler 1", "inner after", "outer after"]);
+}
+
+#[test]
+fn an_interceptor_not_calling_next_drops_the_event() {
+    let publisher = EventPublisher::new();
+    let log = receiving(&publisher);
+    let reached = Arc::new(Mutex::new(Vec::new()));
+    publisher.add_interceptor(|event: &Event<u32>, next: &dyn Fn(&Event<u32>)| {
+        if *event != Event::Args(2) {
+            next(event);
+        }
+    });
+    let later = reached.clone();
+    publisher.add_interceptor(move |event: &Event<u32>, next: &dyn Fn(&Event<u32>)| {
+        later.lock().unwrap().push(event.clone());
+        next(event);
+    });
+
+    publisher.publish_events(&[Event::Args(1), Event::Args(2), Event::Args(3)]);
+
+    assert_eq!(*log.lock().unwrap(), vec!["handler 1", "handler 3"]);
+    assert_eq!(*reached.lock().unwrap(), vec![Event::Args(1), Event::Args(3)]);
+}
+
+#[test]
+fn an_interceptor_may_substitute_another_event() {
+    let publisher = EventPublisher::new();
+    let log = receiving(&publisher);
+    publisher.add_interceptor(|event: &Event<u32>, next: &dyn Fn(&Event<u32>)| {
+        match *event {
+            Event::Args(args) => next(&Event::Args(args * 10)),
+            Event::Missing => next(event),
+        }
+    });
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let inner = seen.clone();
+    publisher.add_interceptor(move |event: &Event<u32>, next: &dyn Fn(&Event<u32>)| {
+        inner.lock().unwrap().push(event.clone());
+        next(event);
+    });
+
+    publisher.publish_event(&Event::Args(4));
+
+    assert_eq!(*seen.lock().unwrap(), vec![Event::Args(40)]);
+    assert_eq!(*log.lock().unwrap(), vec!["handler 40"]);
+}
@@ -0,0 +1,25 @@
+extern crate event;
+
+use std::thread;
+use std::time::Duration;
+
+use event::{Event, EventPublisher};
+
+#[test]
+fn a_subscription_without_deliveries_is_reported_as_a_suspected_leak() {
+    let publisher = EventPublisher::new();
+    let idle = publisher.subscribe_filtered(|_: &Event<u32>| false, |_| {}).unwrap();
+    publisher.subscribe_args(|_: &u32| {}).unwrap();
+    assert!(publisher.suspected_leaks(Duration::from_millis(0)).is_empty());
+
+    publisher.set_leak_detection(true);
+    thread::sleep(Duration::from_millis(50));
+    publisher.publish_event(&Event::Args(1));
+
+    let leaks: Vec<_> = publisher.suspected_leaks(Duration::from_millis(40)).into_iter().map(|info| info.id).collect();
+    assert_eq!(leaks, vec![idle]);
+    assert!(publisher.suspected_leaks(Duration::from_secs(3600)).is_empty());
+
+    publisher.set_leak_detection(false);
+    assert!(publisher.suspected_leaks(Duration::from_millis(0)).is_empty());
+}
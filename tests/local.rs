@@ -0,0 +1,142 @@
+extern crate event;
+
+use std::cell::{Cell, RefCell};
+use std::ops::ControlFlow;
+use std::rc::Rc;
+
+use event::{ErrorPolicy, Event, LocalEventPublisher, LocalHandlerBox, SubscriptionId, Unsubscribe};
+
+type Log = Rc<RefCell<Vec<&'static str>>>;
+
+fn logging(log: &Log, name: &'static str) -> LocalHandlerBox<u32> {
+    let log = log.clone();
+    Box::new(move |_: &Event<u32>| log.borrow_mut().push(name))
+}
+
+#[test]
+fn handlers_are_called_by_descending_priority_then_subscription_order() {
+    let publisher = LocalEventPublisher::new();
+    let log: Log = Rc::new(RefCell::new(Vec::new()));
+    publisher.subscribe_with_priority(logging(&log, "low"), -1).unwrap();
+    publisher.subscribe_handler(logging(&log, "first")).unwrap();
+    publisher.subscribe_with_priority(logging(&log, "high"), 5).unwrap();
+    publisher.subscribe_handler(logging(&log, "second")).unwrap();
+
+    publisher.publish_event(&Event::Args(1));
+
+    assert_eq!(*log.borrow(), vec!["high", "first", "second", "low"]);
+}
+
+#[test]
+fn a_panicking_handler_does_not_keep_the_event_from_the_others() {
+    let publisher = LocalEventPublisher::new();
+    let panics = Rc::new(RefCell::new(Vec::new()));
+    let hook_panics = panics.clone();
+    publisher.set_panic_hook(Box::new(move |panic| hook_panics.borrow_mut().push((panic.subscription, panic.message.clone()))));
+    let log: Log = Rc::new(RefCell::new(Vec::new()));
+    let panicking = publisher.subscribe_handler(Box::new(|_: &Event<u32>| panic!("handler failed"))).unwrap();
+    publisher.subscribe_handler(logging(&log, "after")).unwrap();
+
+    publisher.publish_event(&Event::Args(1));
+
+    assert_eq!(*log.borrow(), vec!["after"]);
+    assert_eq!(*panics.borrow(), vec![(panicking, Some(String::from("handler failed")))]);
+}
+
+#[test]
+fn a_publish_delivers_to_the_handlers_subscribed_when_it_started() {
+    let publisher = Rc::new(LocalEventPublisher::new());
+    let log: Log = Rc::new(RefCell::new(Vec::new()));
+    let later: Rc<RefCell<Option<SubscriptionId>>> = Rc::new(RefCell::new(None));
+
+    let (inner, inner_log, inner_later) = (Rc::downgrade(&publisher), log.clone(), later.clone());
+    publisher.subscribe_handler(Box::new(move |_: &Event<u32>| {
+        let publisher = inner.upgrade().unwrap();
+        inner_log.borrow_mut().push("changing");
+        if let Some(later) = inner_later.borrow_mut().take() {
+            publisher.unsubscribe(later);
+            publisher.subscribe_handler(logging(&inner_log, "added")).unwrap();
+        }
+    })).unwrap();
+    *later.borrow_mut() = Some(publisher.subscribe_handler(logging(&log, "removed")).unwrap());
+
+    publisher.publish_event(&Event::Args(1));
+    assert_eq!(*log.borrow(), vec!["changing", "removed"]);
+
+    log.borrow_mut().clear();
+    publisher.publish_event(&Event::Args(2));
+    assert_eq!(*log.borrow(), vec!["changing", "added"]);
+}
+
+#[test]
+fn payloads_that_are_not_send_are_handed_to_the_handlers() {
+    let publisher: LocalEventPublisher<Rc<RefCell<Vec<u32>>>> = LocalEventPublisher::new();
+    publisher.subscribe_args(|list| list.borrow_mut().push(1)).unwrap();
+    publisher.subscribe_args(|list| list.borrow_mut().push(2)).unwrap();
+
+    let list = Rc::new(RefCell::new(Vec::new()));
+    publisher.publish_event(&Event::Args(list.clone()));
+
+    assert_eq!(*list.borrow(), vec![1, 2]);
+    assert_eq!(Rc::strong_count(&list), 1);
+}
+
+#[test]
+fn handlers_capturing_rc_state_share_it_with_their_owner() {
+    let publisher = LocalEventPublisher::new();
+    let total = Rc::new(Cell::new(0));
+    let counted = total.clone();
+    publisher.subscribe_args(move |args: &u32| counted.set(counted.get() + *args)).unwrap();
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let filtered = seen.clone();
+    publisher.subscribe_filtered(|event: &Event<u32>| *event != Event::Args(2), move |event| filtered.borrow_mut().push(event.clone())).unwrap();
+
+    publisher.publish_events(&[Event::Args(1), Event::Args(2), Event::Args(3)]);
+
+    assert_eq!(total.get(), 6);
+    assert_eq!(*seen.borrow(), vec![Event::Args(1), Event::Args(3)]);
+}
+
+#[test]
+fn a_mutable_handler_publishing_to_its_own_publisher_skips_the_nested_event() {
+    let publisher: Rc<LocalEventPublisher<u32>> = Rc::new(LocalEventPublisher::new());
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let (nested, mutable_seen) = (Rc::downgrade(&publisher), seen.clone());
+    let mut calls = 0;
+    publisher.subscribe_handler_mut(move |event| {
+        calls += 1;
+        mutable_seen.borrow_mut().push((calls, event.clone()));
+        if *event == Event::Args(1) {
+            nested.upgrade().unwrap().publish_event(&Event::Args(2));
+        }
+    }).unwrap();
+
+    publisher.publish_event(&Event::Args(1));
+    publisher.publish_event(&Event::Args(3));
+
+    assert_eq!(*seen.borrow(), vec![(1, Event::Args(1)), (2, Event::Args(3))]);
+}
+
+#[test]
+fn once_until_and_fallible_handlers_behave_as_on_event_publisher() {
+    let publisher = LocalEventPublisher::new();
+    let log: Log = Rc::new(RefCell::new(Vec::new()));
+    let once_log = log.clone();
+    publisher.subscribe_once(move |_: &Event<u32>| once_log.borrow_mut().push("once")).unwrap();
+    let until_log = log.clone();
+    publisher.subscribe_until(move |event: &Event<u32>| {
+        until_log.borrow_mut().push("until");
+        if *event == Event::Args(2) { ControlFlow::Break(Unsubscribe) } else { ControlFlow::Continue(()) }
+    }).unwrap();
+    let failing = publisher.subscribe_fallible(|event: &Event<u32>| if *event == Event::Args(3) { Err("three".into()) } else { Ok(()) }).unwrap();
+
+    publisher.publish_event(&Event::Args(1));
+    publisher.publish_event(&Event::Args(2));
+    let errors = publisher.publish_event_fallible(&Event::Args(3), ErrorPolicy::CollectAll).unwrap_err();
+
+    assert_eq!(*log.borrow(), vec!["once", "until", "until"]);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].subscription, failing);
+    assert_eq!(errors[0].error.to_string(), "three");
+    assert_eq!(publisher.subscriber_count(), 1);
+}
@@ -0,0 +1,61 @@
+extern crate event;
+
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use event::{Event, EventPublisher};
+
+#[test]
+fn metrics_count_publishes_and_time_handler_calls() {
+    let publisher = EventPublisher::new();
+    assert!(publisher.metrics().is_none());
+    publisher.set_metrics(true);
+    let fast = publisher.subscribe_args(|_: &u32| {}).unwrap();
+    let slow = publisher.subscribe_args(|_: &u32| thread::sleep(Duration::from_millis(5))).unwrap();
+
+    publisher.publish_event(&Event::Args(1));
+    publisher.publish_event(&Event::Args(2));
+    publisher.unsubscribe(fast);
+    publisher.publish_event(&Event::Args(3));
+
+    let metrics = publisher.metrics().unwrap();
+    assert_eq!(metrics.events_published, 3);
+    assert_eq!(metrics.handlers_invoked, 5);
+    assert_eq!(metrics.handlers.len(), 1);
+    let slowest = metrics.slowest_handler().unwrap();
+    assert_eq!(slowest.subscription, slow);
+    assert_eq!(slowest.calls, 3);
+    assert!(slowest.slowest_call >= Duration::from_millis(5));
+    assert!(slowest.total_time >= Duration::from_millis(15));
+}
+
+#[test]
+fn only_what_happened_while_metrics_were_on_is_counted() {
+    let publisher = EventPublisher::new();
+    publisher.subscribe_args(|_: &u32| {}).unwrap();
+    publisher.publish_event(&Event::Args(1));
+
+    publisher.set_metrics(true);
+    publisher.publish_event(&Event::Args(2));
+
+    let metrics = publisher.metrics().unwrap();
+    assert_eq!(metrics.events_published, 1);
+    assert_eq!(metrics.handlers_invoked, 1);
+    assert_eq!(metrics.handlers[0].calls, 1);
+
+    publisher.set_metrics(false);
+    assert!(publisher.metrics().is_none());
+}
+
+#[test]
+fn publishers_shared_in_an_arc_can_be_configured() {
+    let publisher: Arc<EventPublisher<u32>> = Arc::new(EventPublisher::new());
+    publisher.subscribe_args(|_| {}).unwrap();
+
+    let configuring = publisher.clone();
+    thread::spawn(move || configuring.set_metrics(true)).join().unwrap();
+    publisher.publish_event(&Event::Args(1));
+
+    assert_eq!(publisher.metrics().unwrap().events_published, 1);
+}
@@ -0,0 +1,141 @@
+extern crate event;
+
+use std::collections::{BTreeMap, HashSet};
+use std::sync::{Arc, Barrier, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::ops::ControlFlow;
+use std::thread;
+use std::time::Duration;
+
+use event::{Event, EventPublisher, Unsubscribe};
+
+#[test]
+fn every_handler_runs_before_publish_returns() {
+    let publisher: EventPublisher<usize> = EventPublisher::new();
+    let total = Arc::new(AtomicUsize::new(0));
+    for _ in 0..8 {
+        let total = total.clone();
+        publisher.subscribe_args(move |args| { total.fetch_add(*args, Ordering::SeqCst); }).unwrap();
+    }
+
+    publisher.publish_event_multithreaded(&Event::Args(5));
+
+    assert_eq!(total.load(Ordering::SeqCst), 40);
+}
+
+#[test]
+fn handlers_run_on_separate_threads_concurrently() {
+    let publisher: EventPublisher<()> = EventPublisher::new();
+    // Every handler waits for all the others, so this only finishes if they run at the same time.
+    let barrier = Arc::new(Barrier::new(4));
+    let threads = Arc::new(Mutex::new(HashSet::new()));
+    for _ in 0..4 {
+        let barrier = barrier.clone();
+        let threads = threads.clone();
+        publisher.subscribe_args(move |_| {
+            barrier.wait();
+            threads.lock().unwrap().insert(thread::current().id());
+        }).unwrap();
+    }
+
+    publisher.publish_event_multithreaded(&Event::Args(()));
+
+    let threads = threads.lock().unwrap();
+    assert_eq!(threads.len(), 4);
+    assert!(!threads.contains(&thread::current().id()));
+}
+
+#[test]
+fn handlers_asking_to_unsubscribe_are_removed() {
+    let publisher: EventPublisher<()> = EventPublisher::new();
+    let calls = Arc::new(AtomicUsize::new(0));
+    let once = calls.clone();
+    publisher.subscribe_until(move |_| {
+        once.fetch_add(1, Ordering::SeqCst);
+        ControlFlow::Break(Unsubscribe)
+    }).unwrap();
+    let always = calls.clone();
+    publisher.subscribe_args(move |_| { always.fetch_add(1, Ordering::SeqCst); }).unwrap();
+
+    publisher.publish_event_multithreaded(&Event::Args(()));
+    publisher.publish_event_multithreaded(&Event::Args(()));
+
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+    assert_eq!(publisher.last_sequence(), 2);
+}
+
+#[test]
+fn handler_panic_is_reported_and_the_other_handlers_still_run() {
+    let publisher: EventPublisher<()> = EventPublisher::new();
+    let panics = Arc::new(Mutex::new(Vec::new()));
+    let reported = panics.clone();
+    publisher.set_panic_hook(Box::new(move |panic| reported.lock().unwrap().push(panic.clone())));
+    let calls = Arc::new(AtomicUsize::new(0));
+    let failing = publisher.subscribe_args(|_| panic!("handler failed")).unwrap();
+    for _ in 0..3 {
+        let calls = calls.clone();
+        publisher.subscribe_args(move |_| { calls.fetch_add(1, Ordering::SeqCst); }).unwrap();
+    }
+
+    publisher.publish_event_multithreaded(&Event::Args(()));
+
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+    let panics = panics.lock().unwrap();
+    assert_eq!(panics.len(), 1);
+    assert_eq!(panics[0].subscription, failing);
+    assert_eq!(panics[0].message, Some(String::from("handler failed")));
+}
+
+#[test]
+fn ordered_handler_sees_events_in_sequence_order() {
+    let publisher: Arc<EventPublisher<usize>> = Arc::new(EventPublisher::new());
+    let published = Arc::new(Mutex::new(BTreeMap::new()));
+    let recorded = published.clone();
+    publisher.subscribe_envelope(move |envelope| {
+        if let Event::Args(args) = *envelope.event {
+            recorded.lock().unwrap().insert(envelope.sequence, args);
+        }
+    }).unwrap();
+    // Holds up some publishes, so later ones overtake them.
+    publisher.subscribe_args(|args| thread::sleep(Duration::from_micros(*args as u64 % 7 * 100))).unwrap();
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let ordered = seen.clone();
+    publisher.subscribe_ordered(move |event| {
+        if let Event::Args(args) = *event {
+            ordered.lock().unwrap().push(args);
+        }
+    }).unwrap();
+
+    let threads: Vec<_> = (0..8).map(|thread| {
+        let publisher = publisher.clone();
+        thread::spawn(move || {
+            for i in 0..50 {
+                publisher.publish_event(&Event::Args(thread * 1000 + i));
+            }
+        })
+    }).collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+
+    let expected: Vec<usize> = published.lock().unwrap().values().cloned().collect();
+    assert_eq!(expected.len(), 400);
+    assert_eq!(*seen.lock().unwrap(), expected);
+}
+
+#[test]
+fn wait_for_returns_the_first_matching_event_from_another_thread() {
+    let publisher: Arc<EventPublisher<usize>> = Arc::new(EventPublisher::new());
+    let publishing = publisher.clone();
+    let thread = thread::spawn(move || {
+        for i in 0..20 {
+            thread::sleep(Duration::from_millis(2));
+            publishing.publish_event(&Event::Args(i));
+        }
+    });
+
+    assert_eq!(publisher.wait_for(Duration::from_secs(5), |args| *args >= 10).unwrap(), Some(10));
+    assert_eq!(publisher.subscriber_count(), 0);
+    thread.join().unwrap();
+    assert_eq!(publisher.wait_for(Duration::from_millis(10), |_| true).unwrap(), None);
+}
@@ -0,0 +1,54 @@
+#![cfg(feature = "rayon")]
+
+extern crate event;
+
+use std::ops::ControlFlow;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use event::{Event, EventPublisher, Unsubscribe};
+
+#[test]
+fn every_handler_runs_before_publish_returns() {
+    let publisher: EventPublisher<usize> = EventPublisher::new();
+    let total = Arc::new(AtomicUsize::new(0));
+    for _ in 0..16 {
+        let total = total.clone();
+        publisher.subscribe_args(move |args| { total.fetch_add(*args, Ordering::SeqCst); }).unwrap();
+    }
+    let removed = Arc::new(AtomicUsize::new(0));
+    let once = removed.clone();
+    publisher.subscribe_until(move |_| {
+        once.fetch_add(1, Ordering::SeqCst);
+        ControlFlow::Break(Unsubscribe)
+    }).unwrap();
+
+    publisher.publish_event_parallel(&Event::Args(5));
+    publisher.publish_event_parallel(&Event::Args(1));
+
+    assert_eq!(total.load(Ordering::SeqCst), 96);
+    assert_eq!(removed.load(Ordering::SeqCst), 1);
+    assert_eq!(publisher.subscriber_count(), 16);
+}
+
+#[test]
+fn handler_panic_is_reported_and_the_other_handlers_still_run() {
+    let publisher: EventPublisher<()> = EventPublisher::new();
+    let panics = Arc::new(Mutex::new(Vec::new()));
+    let reported = panics.clone();
+    publisher.set_panic_hook(Box::new(move |panic| reported.lock().unwrap().push(panic.clone())));
+    let calls = Arc::new(AtomicUsize::new(0));
+    let failing = publisher.subscribe_args(|_| panic!("handler failed")).unwrap();
+    for _ in 0..8 {
+        let calls = calls.clone();
+        publisher.subscribe_args(move |_| { calls.fetch_add(1, Ordering::SeqCst); }).unwrap();
+    }
+
+    publisher.publish_event_parallel(&Event::Args(()));
+
+    assert_eq!(calls.load(Ordering::SeqCst), 8);
+    let panics = panics.lock().unwrap();
+    assert_eq!(panics.len(), 1);
+    assert_eq!(panics[0].subscription, failing);
+    assert_eq!(panics[0].message, Some(String::from("handler failed")));
+}
@@ -0,0 +1,93 @@
+#![cfg(feature = "serde")]
+
+extern crate event;
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use event::{Event, JsonCodec, PersistentPublisher};
+
+// Removed again when dropped, so a failing test doesn't leave the next run a stale log.
+struct TempLog(PathBuf);
+
+impl TempLog {
+    fn new(name: &str) -> TempLog {
+        let path = std::env::temp_dir().join(format!("rust-events-{}-{}.log", name, std::process::id()));
+        let _ = fs::remove_file(&path);
+        TempLog(path)
+    }
+}
+
+impl Drop for TempLog {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+fn replayed(log: &TempLog) -> Vec<Event<u32>> {
+    let publisher: PersistentPublisher<u32, JsonCodec> = PersistentPublisher::open(&log.0, JsonCodec).unwrap();
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let recorder = seen.clone();
+    publisher.publisher().subscribe_handler(Box::new(move |event: &Event<u32>| recorder.lock().unwrap().push(event.clone()))).unwrap();
+    let count = publisher.replay().unwrap();
+    let seen = seen.lock().unwrap().clone();
+    assert_eq!(count, seen.len());
+    seen
+}
+
+#[test]
+fn events_published_before_reopening_are_replayed() {
+    let log = TempLog::new("reopen");
+    {
+        let publisher = PersistentPublisher::open(&log.0, JsonCodec).unwrap();
+        publisher.publish_event(&Event::Args(1)).unwrap();
+        publisher.publish_event(&Event::Missing).unwrap();
+        publisher.publish_event(&Event::Args(2)).unwrap();
+        publisher.sync().unwrap();
+    }
+
+    assert_eq!(replayed(&log), vec![Event::Args(1), Event::Missing, Event::Args(2)]);
+}
+
+#[test]
+fn a_torn_trailing_frame_is_cut_off() {
+    let log = TempLog::new("torn");
+    {
+        let publisher = PersistentPublisher::open(&log.0, JsonCodec).unwrap();
+        publisher.publish_event(&Event::Args(1)).unwrap();
+        publisher.publish_event(&Event::Args(2)).unwrap();
+    }
+    let complete = fs::metadata(&log.0).unwrap().len();
+    // A frame announcing 100 bytes of which only 3 were written before the crash.
+    let mut file = OpenOptions::new().append(true).open(&log.0).unwrap();
+    file.write_all(&100u32.to_be_bytes()).unwrap();
+    file.write_all(b"{\"A").unwrap();
+    drop(file);
+
+    {
+        let publisher = PersistentPublisher::open(&log.0, JsonCodec).unwrap();
+        assert_eq!(fs::metadata(&log.0).unwrap().len(), complete);
+        publisher.publish_event(&Event::Args(3)).unwrap();
+    }
+    assert_eq!(replayed(&log), vec![Event::Args(1), Event::Args(2), Event::Args(3)]);
+}
+
+#[test]
+fn compact_keeps_the_retained_events_and_later_publishes_append_to_the_new_log() {
+    let log = TempLog::new("compact");
+    let publisher = PersistentPublisher::open(&log.0, JsonCodec).unwrap();
+    for args in 0..6 {
+        publisher.publish_event(&Event::Args(args)).unwrap();
+    }
+
+    let kept = publisher.compact(|event| *event != Event::Args(1) && *event != Event::Args(4)).unwrap();
+    assert_eq!(kept, 4);
+    publisher.publish_event(&Event::Args(6)).unwrap();
+    drop(publisher);
+
+    let compacted = log.0.with_extension("log.compact");
+    assert!(!compacted.exists());
+    assert_eq!(replayed(&log), vec![Event::Args(0), Event::Args(2), Event::Args(3), Event::Args(5), Event::Args(6)]);
+}
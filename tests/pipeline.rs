@@ -0,0 +1,39 @@
+extern crate event;
+
+use std::sync::{Arc, Mutex};
+
+use event::{Event, EventPublisher};
+
+#[test]
+fn map_and_filter_chain_into_a_pipeline() {
+    let raw: EventPublisher<u32> = EventPublisher::new();
+    let even = raw.filter(|event| matches!(*event, Event::Args(args) if args % 2 == 0)).unwrap();
+    let labels = even.map(|args| format!("#{}", args)).unwrap();
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let recorded = received.clone();
+    labels.subscribe_handler(Box::new(move |event: &Event<String>| recorded.lock().unwrap().push(event.clone()))).unwrap();
+
+    raw.publish_events(&[Event::Args(1), Event::Args(2), Event::Args(3), Event::Args(4), Event::Missing]);
+
+    assert_eq!(*received.lock().unwrap(), vec![Event::Args(String::from("#2")), Event::Args(String::from("#4"))]);
+}
+
+#[test]
+fn a_dropped_derived_publisher_is_unsubscribed_from_its_source() {
+    let raw: EventPublisher<u32> = EventPublisher::new();
+    let mapped = raw.map(|args| args * 2).unwrap();
+    let filtered = raw.filter(|_| true).unwrap();
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let recorded = received.clone();
+    mapped.subscribe_args(move |args| recorded.lock().unwrap().push(*args)).unwrap();
+    raw.publish_event(&Event::Args(1));
+    assert_eq!(raw.subscriber_count(), 2);
+
+    drop(mapped);
+    drop(filtered);
+    assert_eq!(raw.subscriber_count(), 2);
+    raw.publish_event(&Event::Args(2));
+
+    assert_eq!(raw.subscriber_count(), 0);
+    assert_eq!(*received.lock().unwrap(), vec![2]);
+}
@@ -0,0 +1,120 @@
+extern crate event;
+
+use std::ops::ControlFlow;
+use std::sync::{Arc, Mutex};
+
+use event::{Event, EventPublisher, Unsubscribe, WhilePaused};
+
+#[test]
+fn owned_handlers_keep_the_payload_and_run_after_the_others() {
+    let publisher = EventPublisher::new();
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let kept: Arc<Mutex<Option<Arc<String>>>> = Arc::new(Mutex::new(None));
+    let (owned_calls, owned_kept) = (calls.clone(), kept.clone());
+    publisher.subscribe_owned(move |args: Arc<String>| {
+        owned_calls.lock().unwrap().push("owned");
+        *owned_kept.lock().unwrap() = Some(args);
+    }).unwrap();
+    let borrowed_calls = calls.clone();
+    publisher.subscribe_args(move |_: &String| borrowed_calls.lock().unwrap().push("borrowed")).unwrap();
+
+    publisher.publish_owned(String::from("payload"));
+
+    assert_eq!(*calls.lock().unwrap(), vec!["borrowed", "owned"]);
+    assert_eq!(kept.lock().unwrap().as_ref().map(|args| args.as_str()), Some("payload"));
+}
+
+#[test]
+fn owned_publishes_skip_interceptors_and_pausing_but_not_the_dead_event_handler() {
+    let publisher = EventPublisher::new();
+    let intercepted = Arc::new(Mutex::new(0));
+    let counter = intercepted.clone();
+    publisher.add_interceptor(move |event: &Event<u32>, next: &dyn Fn(&Event<u32>)| {
+        *counter.lock().unwrap() += 1;
+        next(event);
+    });
+    let dead = Arc::new(Mutex::new(Vec::new()));
+    let dead_events = dead.clone();
+    publisher.set_dead_event_handler(Box::new(move |event: &Event<u32>| dead_events.lock().unwrap().push(event.clone())));
+
+    publisher.publish_owned(1);
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let owned_received = received.clone();
+    publisher.subscribe_owned(move |args: Arc<u32>| owned_received.lock().unwrap().push(*args)).unwrap();
+    publisher.pause(WhilePaused::Buffer(10));
+    publisher.publish_owned(2);
+
+    assert_eq!(*intercepted.lock().unwrap(), 0);
+    assert_eq!(*dead.lock().unwrap(), vec![Event::Args(1)]);
+    assert_eq!(*received.lock().unwrap(), vec![2]);
+    assert_eq!(publisher.resume(), 0);
+}
+
+#[test]
+fn publish_and_collect_gathers_the_replies_of_one_type_in_call_order() {
+    let publisher = EventPublisher::new();
+    publisher.subscribe_responder(|_: &Event<u32>| String::from("first")).unwrap();
+    publisher.subscribe_responder(|_: &Event<u32>| 7u64).unwrap();
+    let handled = Arc::new(Mutex::new(0));
+    let counter = handled.clone();
+    publisher.subscribe_args(move |_: &u32| *counter.lock().unwrap() += 1).unwrap();
+    publisher.subscribe_responder(|event: &Event<u32>| match *event {
+        Event::Args(args) => format!("second {}", args),
+        Event::Missing => String::from("second"),
+    }).unwrap();
+
+    let replies: Vec<String> = publisher.publish_and_collect(&Event::Args(1));
+    let numbers: Vec<u64> = publisher.publish_and_collect(&Event::Args(2));
+    let none: Vec<bool> = publisher.publish_and_collect(&Event::Args(3));
+
+    assert_eq!(replies, vec!["first", "second 1"]);
+    assert_eq!(numbers, vec![7]);
+    assert!(none.is_empty());
+    assert_eq!(*handled.lock().unwrap(), 3);
+}
+
+#[test]
+fn the_dead_event_handler_only_sees_events_no_handler_received() {
+    let publisher = EventPublisher::new();
+    let dead = Arc::new(Mutex::new(Vec::new()));
+    let dead_events = dead.clone();
+    publisher.set_dead_event_handler(Box::new(move |event: &Event<u32>| dead_events.lock().unwrap().push(event.clone())));
+
+    publisher.publish_event(&Event::Args(1));
+    publisher.subscribe_filtered(|event: &Event<u32>| *event == Event::Args(3), |_| {}).unwrap();
+    publisher.publish_event(&Event::Args(2));
+    publisher.publish_event(&Event::Args(3));
+    let plain = publisher.subscribe_args(|_: &u32| {}).unwrap();
+    publisher.publish_event(&Event::Args(4));
+    publisher.unsubscribe(plain);
+    publisher.publish_event(&Event::Missing);
+
+    assert_eq!(*dead.lock().unwrap(), vec![Event::Args(1), Event::Args(2), Event::Missing]);
+}
+
+#[test]
+fn a_batch_is_delivered_in_order_to_the_handlers_subscribed_when_it_started() {
+    let publisher = Arc::new(EventPublisher::new());
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let (subscribing, first) = (Arc::downgrade(&publisher), received.clone());
+    publisher.subscribe_args(move |args: &u32| {
+        first.lock().unwrap().push(format!("first {}", args));
+        if *args == 1 {
+            let later = first.clone();
+            subscribing.upgrade().unwrap().subscribe_args(move |args: &u32| later.lock().unwrap().push(format!("later {}", args))).unwrap();
+        }
+    }).unwrap();
+    let until = received.clone();
+    publisher.subscribe_until(move |event: &Event<u32>| {
+        until.lock().unwrap().push(format!("until {:?}", event));
+        if *event == Event::Args(2) { ControlFlow::Break(Unsubscribe) } else { ControlFlow::Continue(()) }
+    }).unwrap();
+
+    let batch: Vec<Event<u32>> = (1..4).map(Event::Args).collect();
+    publisher.publish_events(&batch);
+    publisher.publish_events([Event::Args(4)].iter());
+
+    assert_eq!(*received.lock().unwrap(), vec![
+        "first 1", "until Args(1)", "first 2", "until Args(2)", "first 3", "first 4", "later 4",
+    ]);
+}
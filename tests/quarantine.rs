@@ -0,0 +1,67 @@
+extern crate event;
+
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use event::{ErrorPolicy, Event, EventPublisher};
+
+#[test]
+fn a_handler_failing_repeatedly_is_quarantined_until_released() {
+    let publisher = EventPublisher::new();
+    publisher.set_panic_hook(Box::new(|_| {}));
+    publisher.set_quarantine_threshold(Some(3));
+    let quarantines = Arc::new(Mutex::new(Vec::new()));
+    let reported = quarantines.clone();
+    publisher.set_quarantine_hook(Box::new(move |id, failures| reported.lock().unwrap().push((id, failures))));
+    let (failing, calls) = (Arc::new(AtomicBool::new(true)), Arc::new(AtomicUsize::new(0)));
+    let (fails, counted) = (failing.clone(), calls.clone());
+    let flaky = publisher.subscribe_args(move |_: &u32| {
+        counted.fetch_add(1, Ordering::SeqCst);
+        if fails.load(Ordering::SeqCst) {
+            panic!("handler failed");
+        }
+    }).unwrap();
+
+    for args in 0..3 {
+        publisher.publish_event(&Event::Args(args));
+    }
+    assert_eq!(publisher.quarantined(), vec![flaky]);
+    assert_eq!(*quarantines.lock().unwrap(), vec![(flaky, 3)]);
+
+    publisher.publish_event(&Event::Args(3));
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+    assert_eq!(publisher.subscriber_count(), 1);
+
+    failing.store(false, Ordering::SeqCst);
+    assert!(publisher.release_quarantine(flaky));
+    assert!(!publisher.release_quarantine(flaky));
+    publisher.publish_event(&Event::Args(4));
+    assert_eq!(calls.load(Ordering::SeqCst), 4);
+    assert!(publisher.quarantined().is_empty());
+}
+
+#[test]
+fn a_successful_delivery_starts_the_count_again() {
+    let publisher = EventPublisher::new();
+    publisher.set_quarantine_threshold(Some(2));
+    let quarantines = Arc::new(AtomicUsize::new(0));
+    let reported = quarantines.clone();
+    publisher.set_quarantine_hook(Box::new(move |_, _| { reported.fetch_add(1, Ordering::SeqCst); }));
+    let flaky = publisher.subscribe_fallible(|event: &Event<u32>| match *event {
+        Event::Args(args) if args % 2 == 0 => Err("even".into()),
+        _ => Ok(()),
+    }).unwrap();
+
+    // Fails every other event, so never twice in a row.
+    for args in 0..6 {
+        let _ = publisher.publish_event_fallible(&Event::Args(args), ErrorPolicy::CollectAll);
+    }
+    assert!(publisher.quarantined().is_empty());
+    assert_eq!(quarantines.load(Ordering::SeqCst), 0);
+
+    for args in [6, 8] {
+        let _ = publisher.publish_event_fallible(&Event::Args(args), ErrorPolicy::CollectAll);
+    }
+    assert_eq!(publisher.quarantined(), vec![flaky]);
+    assert_eq!(quarantines.load(Ordering::SeqCst), 1);
+}
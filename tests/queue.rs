@@ -0,0 +1,151 @@
+extern crate event;
+
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use event::{Backpressure, Event, EventPublisher, PublishError, QueuedPublisher, Shutdown};
+
+fn delivered(queued: &QueuedPublisher<u32>) -> Vec<u32> {
+    let (sender, receiver) = mpsc::channel();
+    let id = queued.publisher().subscribe_channel(sender).unwrap();
+    queued.dispatch_pending();
+    queued.publisher().unsubscribe(id);
+    receiver.try_iter().collect()
+}
+
+#[test]
+fn drop_oldest_discards_the_oldest_of_the_lowest_priority() {
+    let queued = QueuedPublisher::with_capacity(EventPublisher::new(), 3, Backpressure::DropOldest);
+    queued.publish_with_priority(Event::Args(1), 1).unwrap();
+    queued.publish_with_priority(Event::Args(2), 0).unwrap();
+    queued.publish_with_priority(Event::Args(3), 0).unwrap();
+    queued.publish_with_priority(Event::Args(4), 0).unwrap();
+
+    assert_eq!(delivered(&queued), vec![1, 3, 4]);
+}
+
+#[test]
+fn drop_oldest_discards_an_event_of_a_lower_priority_than_every_queued_one() {
+    let queued = QueuedPublisher::with_capacity(EventPublisher::new(), 2, Backpressure::DropOldest);
+    queued.publish_with_priority(Event::Args(1), 2).unwrap();
+    queued.publish_with_priority(Event::Args(2), 1).unwrap();
+    queued.publish_with_priority(Event::Args(3), 0).unwrap();
+
+    assert_eq!(delivered(&queued), vec![1, 2]);
+}
+
+#[test]
+fn events_are_only_delivered_by_dispatch_pending() {
+    let queued = QueuedPublisher::new();
+    let (sender, receiver) = mpsc::channel();
+    queued.publisher().subscribe_channel(sender).unwrap();
+
+    queued.publish_event(Event::Args(1)).unwrap();
+    queued.publish_event(Event::Args(2)).unwrap();
+    assert_eq!(queued.len(), 2);
+    assert!(receiver.try_recv().is_err());
+
+    assert_eq!(queued.dispatch_pending(), 2);
+    assert!(queued.is_empty());
+    assert_eq!(receiver.try_iter().collect::<Vec<u32>>(), vec![1, 2]);
+}
+
+#[test]
+fn events_queued_by_handlers_wait_for_the_next_dispatch() {
+    let queued = Arc::new(QueuedPublisher::new());
+    let (sender, receiver) = mpsc::channel();
+    queued.publisher().subscribe_channel(sender).unwrap();
+    let requeue = Arc::downgrade(&queued);
+    queued.publisher().subscribe_args(move |args: &u32| {
+        if let Some(queued) = requeue.upgrade() {
+            queued.publish_event(Event::Args(args + 1)).unwrap();
+        }
+    }).unwrap();
+
+    queued.publish_event(Event::Args(1)).unwrap();
+    assert_eq!(queued.dispatch_pending(), 1);
+    assert_eq!(queued.dispatch_pending(), 1);
+
+    assert_eq!(receiver.try_iter().collect::<Vec<u32>>(), vec![1, 2]);
+    assert_eq!(queued.len(), 1);
+}
+
+#[test]
+fn higher_priorities_are_dispatched_first_in_the_order_they_were_queued() {
+    let queued = QueuedPublisher::new();
+    queued.publish_event(Event::Args(1)).unwrap();
+    queued.publish_with_priority(Event::Args(2), 5).unwrap();
+    queued.publish_with_priority(Event::Args(3), 1).unwrap();
+    queued.publish_with_priority(Event::Args(4), 5).unwrap();
+    queued.publish_event(Event::Args(5)).unwrap();
+
+    assert_eq!(delivered(&queued), vec![2, 4, 3, 1, 5]);
+}
+
+#[test]
+fn events_past_their_time_to_live_are_dropped() {
+    let queued = QueuedPublisher::new();
+    queued.publish_with_ttl(Event::Args(1), Duration::from_millis(10)).unwrap();
+    queued.publish_with_ttl(Event::Args(2), Duration::from_secs(60)).unwrap();
+    queued.publish_event(Event::Args(3)).unwrap();
+    thread::sleep(Duration::from_millis(20));
+
+    assert_eq!(queued.len(), 3);
+    assert_eq!(queued.discarded(), 0);
+    assert_eq!(delivered(&queued), vec![2, 3]);
+    assert_eq!(queued.discarded(), 1);
+}
+
+#[test]
+fn events_expiring_while_the_dispatcher_drains_are_counted_as_discarded() {
+    let publisher: Arc<EventPublisher<u32>> = Arc::new(EventPublisher::new());
+    let (started, started_events) = mpsc::channel();
+    let (release, released) = mpsc::channel::<()>();
+    let (started, released) = (Mutex::new(started), Mutex::new(released));
+    publisher.subscribe_args(move |args| {
+        started.lock().unwrap().send(*args).unwrap();
+        let _ = released.lock().unwrap().recv();
+    }).unwrap();
+    let dispatcher = publisher.clone().spawn_dispatcher();
+
+    dispatcher.publish_event(Event::Args(1)).unwrap();
+    assert_eq!(started_events.recv().unwrap(), 1);
+    dispatcher.publish_with_ttl(Event::Args(2), Duration::from_millis(10)).unwrap();
+    dispatcher.publish_event(Event::Args(3)).unwrap();
+    thread::sleep(Duration::from_millis(20));
+    drop(release);
+
+    assert_eq!(dispatcher.shutdown_with(Shutdown::Drain), 1);
+    assert_eq!(started_events.try_iter().collect::<Vec<_>>(), vec![3]);
+}
+
+#[test]
+fn drop_newest_and_reject_refuse_events_to_a_full_queue() {
+    let dropping = QueuedPublisher::with_capacity(EventPublisher::new(), 1, Backpressure::DropNewest);
+    dropping.publish_event(Event::Args(1)).unwrap();
+    dropping.publish_event(Event::Args(2)).unwrap();
+    assert_eq!(delivered(&dropping), vec![1]);
+
+    let rejecting = QueuedPublisher::with_capacity(EventPublisher::new(), 1, Backpressure::Reject);
+    rejecting.publish_event(Event::Args(1)).unwrap();
+    match rejecting.publish_event(Event::Args(2)) {
+        Err(PublishError::Full(event)) => assert_eq!(event, Event::Args(2)),
+        other => panic!("expected the event to be refused as full, got {:?}", other),
+    }
+    assert_eq!(delivered(&rejecting), vec![1]);
+}
+
+#[test]
+fn block_waits_for_room_in_a_full_queue() {
+    let queued = Arc::new(QueuedPublisher::with_capacity(EventPublisher::new(), 1, Backpressure::Block));
+    queued.publish_event(Event::Args(1)).unwrap();
+    let blocked = queued.clone();
+    let publishing = thread::spawn(move || blocked.publish_event(Event::Args(2)).unwrap());
+    thread::sleep(Duration::from_millis(20));
+    assert_eq!(queued.len(), 1);
+
+    assert_eq!(delivered(&queued), vec![1]);
+    publishing.join().unwrap();
+    assert_eq!(delivered(&queued), vec![2]);
+}
@@ -0,0 +1,56 @@
+extern crate event;
+
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc;
+
+use event::{Event, EventPublisher, RecordingPublisher};
+
+// Code under test, publishing on whatever publisher it is handed.
+fn checkout(publisher: &EventPublisher<String>, items: u32) {
+    for item in 0..items {
+        publisher.publish_event(&Event::Args(format!("item {}", item)));
+    }
+    publisher.publish_event(&Event::Missing);
+}
+
+#[test]
+fn published_events_are_captured_in_order_and_still_delivered() {
+    let recording = RecordingPublisher::new();
+    let (sender, receiver) = mpsc::channel();
+    recording.publisher().subscribe_channel(sender).unwrap();
+
+    checkout(recording.publisher(), 2);
+
+    assert_eq!(recording.events(), vec![Event::Args(String::from("item 0")), Event::Args(String::from("item 1")), Event::Missing]);
+    assert_eq!(recording.len(), 3);
+    assert!(recording.records().windows(2).all(|pair| pair[0].published_at <= pair[1].published_at));
+    assert_eq!(receiver.try_iter().count(), 2);
+    assert_eq!(recording.publisher().subscriber_count(), 1);
+
+    recording.clear();
+    assert!(recording.is_empty());
+}
+
+#[test]
+fn assertions_pass_or_panic_on_what_was_captured() {
+    let recording = RecordingPublisher::new();
+    checkout(recording.publisher(), 1);
+
+    recording.assert_published(|event| *event == Event::Args(String::from("item 0")));
+    recording.assert_not_published(|event| *event == Event::Args(String::from("item 1")));
+    assert!(panic::catch_unwind(AssertUnwindSafe(|| recording.assert_published(|event| *event == Event::Args(String::from("item 1"))))).is_err());
+    assert!(panic::catch_unwind(AssertUnwindSafe(|| recording.assert_not_published(|event| *event == Event::Missing))).is_err());
+}
+
+#[test]
+fn captured_events_can_be_replayed_on_another_publisher() {
+    let recording = RecordingPublisher::new();
+    checkout(recording.publisher(), 2);
+    let other = EventPublisher::new();
+    let (sender, receiver) = mpsc::channel();
+    other.subscribe_channel(sender).unwrap();
+
+    recording.replay_to(&other);
+
+    assert_eq!(receiver.try_iter().collect::<Vec<String>>(), vec![String::from("item 0"), String::from("item 1")]);
+}
@@ -0,0 +1,150 @@
+extern crate event;
+
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use event::{Event, EventPublisher, SubscriptionId};
+
+#[test]
+fn handler_subscribed_from_a_handler_sees_the_next_event() {
+    let publisher: Arc<EventPublisher<usize>> = Arc::new(EventPublisher::new());
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let subscriber = publisher.clone();
+    let subscribed = seen.clone();
+    publisher.subscribe_once(move |_| {
+        let seen = subscribed.clone();
+        subscriber.subscribe_args(move |args| seen.lock().unwrap().push(*args)).unwrap();
+    }).unwrap();
+
+    publisher.publish_event(&Event::Args(1));
+    publisher.publish_event(&Event::Args(2));
+
+    assert_eq!(*seen.lock().unwrap(), vec![2]);
+}
+
+#[test]
+fn handler_unsubscribing_itself_is_not_called_again() {
+    let publisher: Arc<EventPublisher<()>> = Arc::new(EventPublisher::new());
+    let calls = Arc::new(AtomicUsize::new(0));
+    let own_id: Arc<Mutex<Option<SubscriptionId>>> = Arc::new(Mutex::new(None));
+    let unsubscriber = publisher.clone();
+    let handler_id = own_id.clone();
+    let counted = calls.clone();
+    let id = publisher.subscribe_args(move |_| {
+        counted.fetch_add(1, Ordering::SeqCst);
+        let id = handler_id.lock().unwrap().expect("subscribed before publishing");
+        assert!(unsubscriber.unsubscribe(id));
+    }).unwrap();
+    *own_id.lock().unwrap() = Some(id);
+
+    publisher.publish_event(&Event::Args(()));
+    publisher.publish_event(&Event::Args(()));
+
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn handler_unsubscribed_during_a_publish_still_sees_that_event() {
+    let publisher: Arc<EventPublisher<()>> = Arc::new(EventPublisher::new());
+    let later_id: Arc<Mutex<Option<SubscriptionId>>> = Arc::new(Mutex::new(None));
+    let unsubscriber = publisher.clone();
+    let target = later_id.clone();
+    publisher.subscribe_args(move |_| {
+        if let Some(id) = target.lock().unwrap().take() {
+            unsubscriber.unsubscribe(id);
+        }
+    }).unwrap();
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counted = calls.clone();
+    let id = publisher.subscribe_args(move |_| { counted.fetch_add(1, Ordering::SeqCst); }).unwrap();
+    *later_id.lock().unwrap() = Some(id);
+
+    publisher.publish_event(&Event::Args(()));
+    publisher.publish_event(&Event::Args(()));
+
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn publish_from_a_handler_sees_handlers_subscribed_before_it() {
+    let publisher: Arc<EventPublisher<usize>> = Arc::new(EventPublisher::new());
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let nested = publisher.clone();
+    let subscribed = seen.clone();
+    publisher.subscribe_once(move |_| {
+        let seen = subscribed.clone();
+        nested.subscribe_args(move |args| seen.lock().unwrap().push(*args)).unwrap();
+        nested.publish_event(&Event::Args(2));
+    }).unwrap();
+
+    publisher.publish_event(&Event::Args(1));
+
+    assert_eq!(*seen.lock().unwrap(), vec![2]);
+}
+
+#[test]
+fn mutable_handler_keeps_its_state_between_events() {
+    let publisher: EventPublisher<u32> = EventPublisher::new();
+    let totals = Arc::new(Mutex::new(Vec::new()));
+    let seen = totals.clone();
+    let mut total = 0;
+    publisher.subscribe_handler_mut(move |event| {
+        if let Event::Args(args) = *event {
+            total += args;
+            seen.lock().unwrap().push(total);
+        }
+    }).unwrap();
+
+    for args in 1..4 {
+        publisher.publish_event(&Event::Args(args));
+    }
+
+    assert_eq!(*totals.lock().unwrap(), vec![1, 3, 6]);
+}
+
+#[test]
+fn mutable_handler_publishing_to_its_own_publisher_skips_the_nested_event() {
+    let publisher: Arc<EventPublisher<u32>> = Arc::new(EventPublisher::new());
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let (nested, mutable_seen) = (publisher.clone(), seen.clone());
+    publisher.subscribe_handler_mut(move |event| {
+        if let Event::Args(args) = *event {
+            mutable_seen.lock().unwrap().push(args);
+            if args == 1 {
+                nested.publish_event(&Event::Args(2));
+            }
+        }
+    }).unwrap();
+    let others_seen = Arc::new(Mutex::new(Vec::new()));
+    let other = others_seen.clone();
+    publisher.subscribe_args(move |args| other.lock().unwrap().push(*args)).unwrap();
+
+    publisher.publish_event(&Event::Args(1));
+    publisher.publish_event(&Event::Args(3));
+
+    assert_eq!(*seen.lock().unwrap(), vec![1, 3]);
+    assert_eq!(*others_seen.lock().unwrap(), vec![2, 1, 3]);
+}
+
+#[test]
+fn mutable_handler_takes_turns_between_publishing_threads() {
+    let publisher: Arc<EventPublisher<u32>> = Arc::new(EventPublisher::new());
+    let total = Arc::new(Mutex::new(0));
+    let counted = total.clone();
+    let mut calls = 0;
+    publisher.subscribe_handler_mut(move |_| {
+        calls += 1;
+        *counted.lock().unwrap() = calls;
+    }).unwrap();
+
+    let threads: Vec<_> = (0..4).map(|_| {
+        let publisher = publisher.clone();
+        thread::spawn(move || for args in 0..100 { publisher.publish_event(&Event::Args(args)); })
+    }).collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+
+    assert_eq!(*total.lock().unwrap(), 400);
+}
@@ -0,0 +1,153 @@
+#![cfg(all(feature = "serde", not(target_arch = "wasm32")))]
+
+extern crate event;
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use event::{Codec, Endpoint, Event, EventPublisher, JsonCodec, RemotePublisher, RemoteSubscriber};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+fn listen(endpoint: &Endpoint) -> (RemoteSubscriber, Receiver<u32>) {
+    let publisher: Arc<EventPublisher<u32>> = Arc::new(EventPublisher::new());
+    let (sender, receiver) = mpsc::channel();
+    publisher.subscribe_channel(sender).unwrap();
+    (publisher.listen_remote(endpoint, JsonCodec).unwrap(), receiver)
+}
+
+fn loopback() -> Endpoint {
+    Endpoint::Tcp("127.0.0.1:0".parse().unwrap())
+}
+
+// Publishes until the event arrives, as events written while the old connection was going down may be lost.
+fn publish_until_received(remote: &RemotePublisher<u32, JsonCodec>, receiver: &Receiver<u32>, args: u32) {
+    let deadline = Instant::now() + TIMEOUT;
+    while Instant::now() < deadline {
+        let _ = remote.publish_event(&Event::Args(args));
+        if let Ok(received) = receiver.recv_timeout(Duration::from_millis(50)) {
+            assert_eq!(received, args);
+            return;
+        }
+    }
+    panic!("event was not received after reconnecting");
+}
+
+#[test]
+fn events_are_delivered_over_tcp() {
+    let (subscriber, receiver) = listen(&loopback());
+    let remote = RemotePublisher::connect(subscriber.endpoint().clone(), JsonCodec).unwrap();
+
+    for args in 0..10 {
+        remote.publish_event(&Event::Args(args)).unwrap();
+    }
+    let received: Vec<u32> = (0..10).map(|_| receiver.recv_timeout(TIMEOUT).unwrap()).collect();
+    assert_eq!(received, (0..10).collect::<Vec<u32>>());
+}
+
+#[cfg(unix)]
+#[test]
+fn events_are_delivered_over_a_unix_socket() {
+    let path = std::env::temp_dir().join(format!("rust-events-remote-{}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let (subscriber, receiver) = listen(&Endpoint::Unix(path.clone()));
+    let remote = RemotePublisher::connect(Endpoint::Unix(path.clone()), JsonCodec).unwrap();
+
+    remote.publish_event(&Event::Args(7)).unwrap();
+    assert_eq!(receiver.recv_timeout(TIMEOUT).unwrap(), 7);
+    subscriber.shutdown();
+    assert!(!path.exists());
+}
+
+#[test]
+fn publisher_reconnects_after_the_subscriber_restarts() {
+    let (subscriber, receiver) = listen(&loopback());
+    let endpoint = subscriber.endpoint().clone();
+    let remote = RemotePublisher::connect(endpoint.clone(), JsonCodec).unwrap();
+    remote.publish_event(&Event::Args(1)).unwrap();
+    assert_eq!(receiver.recv_timeout(TIMEOUT).unwrap(), 1);
+
+    subscriber.shutdown();
+    let (_subscriber, receiver) = listen(&endpoint);
+    publish_until_received(&remote, &receiver, 2);
+    assert!(remote.is_connected());
+}
+
+#[cfg(unix)]
+#[test]
+fn publisher_reconnects_after_the_unix_subscriber_restarts() {
+    let path = std::env::temp_dir().join(format!("rust-events-reconnect-{}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let (subscriber, _) = listen(&Endpoint::Unix(path.clone()));
+    let remote = RemotePublisher::connect(Endpoint::Unix(path.clone()), JsonCodec).unwrap();
+
+    subscriber.shutdown();
+    let (subscriber, receiver) = listen(&Endpoint::Unix(path.clone()));
+    publish_until_received(&remote, &receiver, 3);
+    subscriber.shutdown();
+}
+
+#[test]
+fn frames_that_fail_to_decode_are_skipped() {
+    let (subscriber, receiver) = listen(&loopback());
+    let addr = match *subscriber.endpoint() {
+        Endpoint::Tcp(addr) => addr,
+        #[cfg(unix)]
+        _ => unreachable!(),
+    };
+    let mut stream = TcpStream::connect(addr).unwrap();
+    let garbage = b"not an event";
+    let valid = JsonCodec.encode(&Event::Args(5u32)).unwrap();
+    for frame in [&garbage[..], &valid[..]] {
+        stream.write_all(&(frame.len() as u32).to_be_bytes()).unwrap();
+        stream.write_all(frame).unwrap();
+    }
+
+    assert_eq!(receiver.recv_timeout(TIMEOUT).unwrap(), 5);
+    assert!(receiver.recv_timeout(Duration::from_millis(100)).is_err());
+}
+
+#[test]
+fn shutdown_returns_for_a_listener_on_an_unspecified_address() {
+    let addr: SocketAddr = "0.0.0.0:0".parse().unwrap();
+    let (subscriber, _receiver) = listen(&Endpoint::Tcp(addr));
+    let port = match *subscriber.endpoint() {
+        Endpoint::Tcp(addr) => addr.port(),
+        #[cfg(unix)]
+        _ => unreachable!(),
+    };
+    // An open connection must not keep shutdown waiting either.
+    let _connection = TcpStream::connect(("127.0.0.1", port)).unwrap();
+
+    let (done, shut_down) = mpsc::channel();
+    thread::spawn(move || {
+        subscriber.shutdown();
+        done.send(()).unwrap();
+    });
+    shut_down.recv_timeout(TIMEOUT).expect("shutdown hung");
+}
+
+#[test]
+fn shutdown_closes_the_open_connections() {
+    let (subscriber, receiver) = listen(&loopback());
+    let addr = match *subscriber.endpoint() {
+        Endpoint::Tcp(addr) => addr,
+        #[cfg(unix)]
+        _ => unreachable!(),
+    };
+    let mut stream = TcpStream::connect(addr).unwrap();
+    let frame = JsonCodec.encode(&Event::Args(5u32)).unwrap();
+    stream.write_all(&(frame.len() as u32).to_be_bytes()).unwrap();
+    stream.write_all(&frame).unwrap();
+    // Received, so the connection has been accepted.
+    assert_eq!(receiver.recv_timeout(TIMEOUT).unwrap(), 5);
+
+    subscriber.shutdown();
+
+    stream.set_read_timeout(Some(TIMEOUT)).unwrap();
+    assert_eq!(stream.read(&mut [0; 1]).unwrap(), 0);
+}
@@ -0,0 +1,57 @@
+extern crate event;
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use event::{Backoff, BoxError, ErrorPolicy, Event, EventPublisher, RetryPolicy};
+
+// Handler returning an error from its first failures calls, counting its calls in calls.
+fn flaky(failures: u32, calls: &Arc<Mutex<u32>>) -> impl Fn(&Event<u32>) -> Result<(), BoxError> + Send + Sync + 'static {
+    let calls = calls.clone();
+    move |_: &Event<u32>| {
+        let mut calls = calls.lock().unwrap();
+        *calls += 1;
+        if *calls <= failures { Err(BoxError::from(format!("attempt {} failed", calls))) } else { Ok(()) }
+    }
+}
+
+#[test]
+fn a_failing_handler_is_retried_until_it_succeeds() {
+    let publisher = EventPublisher::new();
+    let calls = Arc::new(Mutex::new(0));
+    let handler = flaky(2, &calls);
+    let policy = RetryPolicy { max_attempts: 3, backoff: Backoff::Fixed(Duration::from_millis(1)) };
+    publisher.subscribe_fallible_with_retry(policy, handler).unwrap();
+
+    assert!(publisher.publish_event_fallible(&Event::Args(1), ErrorPolicy::CollectAll).is_ok());
+    assert_eq!(*calls.lock().unwrap(), 3);
+}
+
+#[test]
+fn only_the_error_of_the_last_attempt_is_passed_on() {
+    let publisher = EventPublisher::new();
+    let calls = Arc::new(Mutex::new(0));
+    let handler = flaky(u32::MAX, &calls);
+    let policy = RetryPolicy { max_attempts: 3, backoff: Backoff::Exponential { initial: Duration::from_millis(10), max: Duration::from_millis(15) } };
+    let id = publisher.subscribe_fallible_with_retry(policy, handler).unwrap();
+
+    let started = Instant::now();
+    let errors = publisher.publish_event_fallible(&Event::Args(1), ErrorPolicy::CollectAll).unwrap_err();
+
+    assert_eq!(*calls.lock().unwrap(), 3);
+    assert!(started.elapsed() >= Duration::from_millis(25));
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].subscription, id);
+    assert_eq!(errors[0].error.to_string(), "attempt 3 failed");
+}
+
+#[test]
+fn zero_attempts_call_the_handler_once() {
+    let publisher = EventPublisher::new();
+    let calls = Arc::new(Mutex::new(0));
+    let handler = flaky(u32::MAX, &calls);
+    publisher.subscribe_fallible_with_retry(RetryPolicy { max_attempts: 0, backoff: Backoff::Fixed(Duration::from_secs(60)) }, handler).unwrap();
+
+    assert!(publisher.publish_event_fallible(&Event::Args(1), ErrorPolicy::CollectAll).is_err());
+    assert_eq!(*calls.lock().unwrap(), 1);
+}
@@ -0,0 +1,74 @@
+extern crate event;
+
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
+
+use event::{CancellationToken, Event, EventPublisher, Shutdown};
+
+fn publisher() -> (Arc<EventPublisher<u32>>, mpsc::Receiver<u32>) {
+    let publisher = Arc::new(EventPublisher::new());
+    let (sender, receiver) = mpsc::channel();
+    publisher.subscribe_channel(sender).unwrap();
+    (publisher, receiver)
+}
+
+#[test]
+fn events_are_published_in_the_order_they_are_due() {
+    let (publisher, receiver) = publisher();
+    let scheduler = publisher.spawn_scheduler();
+
+    scheduler.publish_after(Event::Args(2), Duration::from_millis(40));
+    scheduler.publish_after(Event::Args(1), Duration::from_millis(20));
+    let cancelled = scheduler.publish_after(Event::Args(3), Duration::from_millis(30));
+    cancelled.cancel();
+    assert!(cancelled.is_cancelled());
+    assert_eq!(scheduler.shutdown_with(Shutdown::Drain), 0);
+
+    assert_eq!(receiver.try_iter().collect::<Vec<u32>>(), vec![1, 2]);
+}
+
+#[test]
+fn repeating_events_are_published_until_cancelled() {
+    let (publisher, receiver) = publisher();
+    let scheduler = publisher.spawn_scheduler();
+
+    let repeating = scheduler.publish_every(Event::Args(1), Duration::from_millis(5));
+    for _ in 0..3 {
+        assert_eq!(receiver.recv_timeout(Duration::from_secs(5)).unwrap(), 1);
+    }
+    repeating.cancel();
+    scheduler.shutdown();
+
+    // At most a publish that was already in progress.
+    assert!(receiver.try_iter().count() <= 1);
+}
+
+#[test]
+fn shutting_down_with_discard_drops_what_is_scheduled() {
+    let (publisher, receiver) = publisher();
+    let scheduler = publisher.spawn_scheduler();
+
+    scheduler.publish_after(Event::Args(1), Duration::from_secs(3600));
+    scheduler.publish_every(Event::Args(2), Duration::from_secs(3600));
+    assert_eq!(scheduler.pending(), 2);
+
+    assert_eq!(scheduler.shutdown_with(Shutdown::Discard), 2);
+    assert!(receiver.try_recv().is_err());
+}
+
+#[test]
+fn a_cancelled_token_stops_the_scheduler() {
+    let (publisher, receiver) = publisher();
+    let scheduler = publisher.spawn_scheduler();
+    let token = CancellationToken::new();
+    scheduler.cancel_on(&token, Shutdown::Discard);
+
+    scheduler.publish_after(Event::Args(1), Duration::from_secs(3600));
+    token.cancel();
+    let late = scheduler.publish_after(Event::Args(2), Duration::from_millis(1));
+
+    // Both the event scheduled before and the one refused after the cancellation count as discarded.
+    assert!(late.is_cancelled());
+    assert_eq!(scheduler.shutdown_with(Shutdown::Drain), 2);
+    assert!(receiver.try_recv().is_err());
+}
@@ -0,0 +1,106 @@
+#![cfg(feature = "serde")]
+
+extern crate event;
+extern crate serde;
+
+use std::fs;
+use std::sync::Arc;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use event::{Codec, CodecError, Event, JsonCodec, PersistentPublisher, Schema};
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+struct UserV1 {
+    name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+struct UserV2 {
+    name: String,
+    admin: bool,
+}
+
+fn schema_v2() -> Schema<UserV2> {
+    Schema::with_upgrade(2, |version, bytes| match version {
+        1 => JsonCodec.decode::<UserV1>(bytes).map(|user| UserV2 { name: user.name, admin: false }),
+        _ => Err(CodecError::new(format!("unknown version {}", version))),
+    })
+}
+
+fn user_v1(name: &str) -> Event<UserV1> {
+    Event::Args(UserV1 { name: String::from(name) })
+}
+
+#[test]
+fn the_current_version_round_trips() {
+    let schema = schema_v2();
+    let event = Event::Args(UserV2 { name: String::from("ann"), admin: true });
+
+    let bytes = schema.encode(&JsonCodec, &event).unwrap();
+    assert_eq!(&bytes[..4], &2u32.to_be_bytes());
+    assert_eq!(schema.decode(&JsonCodec, &bytes).unwrap(), event);
+    let missing = schema.encode(&JsonCodec, &Event::Missing).unwrap();
+    assert_eq!(schema.decode(&JsonCodec, &missing).unwrap(), Event::Missing);
+}
+
+#[test]
+fn an_older_version_goes_through_the_upgrade_hook() {
+    let old = Schema::new(1).encode(&JsonCodec, &user_v1("bob")).unwrap();
+
+    assert_eq!(schema_v2().decode(&JsonCodec, &old).unwrap(), Event::Args(UserV2 { name: String::from("bob"), admin: false }));
+    assert!(Schema::<UserV2>::new(2).decode(&JsonCodec, &old).is_err());
+}
+
+#[test]
+fn a_newer_version_is_an_error() {
+    let newer = Schema::<UserV2>::new(3).encode(&JsonCodec, &Event::Args(UserV2 { name: String::from("cy"), admin: false })).unwrap();
+
+    assert!(schema_v2().decode(&JsonCodec, &newer).is_err());
+}
+
+#[test]
+fn a_short_header_is_an_error() {
+    let schema = schema_v2();
+    for len in 0..5 {
+        assert!(schema.decode(&JsonCodec, &[0, 0, 0, 2, 1][..len]).is_err());
+    }
+}
+
+#[test]
+fn a_versioned_log_is_upgraded_on_replay() {
+    let path = std::env::temp_dir().join(format!("rust-events-schema-{}.log", std::process::id()));
+    let _ = fs::remove_file(&path);
+    {
+        let publisher = PersistentPublisher::open_versioned(&path, JsonCodec, Schema::new(1)).unwrap();
+        publisher.publish_event(&user_v1("dee")).unwrap();
+    }
+
+    let publisher = PersistentPublisher::open_versioned(&path, JsonCodec, schema_v2()).unwrap();
+    publisher.publish_event(&Event::Args(UserV2 { name: String::from("eve"), admin: true })).unwrap();
+    let (sender, receiver) = mpsc::channel();
+    publisher.publisher().subscribe_channel(sender).unwrap();
+    assert_eq!(publisher.replay().unwrap(), 2);
+    let _ = fs::remove_file(&path);
+
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), vec![
+        UserV2 { name: String::from("dee"), admin: false },
+        UserV2 { name: String::from("eve"), admin: true },
+    ]);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[test]
+fn remote_events_of_an_older_version_are_upgraded() {
+    use event::{Endpoint, EventPublisher, RemotePublisher};
+
+    let receiving: Arc<EventPublisher<UserV2>> = Arc::new(EventPublisher::new());
+    let (sender, receiver) = mpsc::channel();
+    receiving.subscribe_channel(sender).unwrap();
+    let subscriber = receiving.listen_remote_versioned(&Endpoint::Tcp("127.0.0.1:0".parse().unwrap()), JsonCodec, schema_v2()).unwrap();
+    let mut remote = RemotePublisher::connect(subscriber.endpoint().clone(), JsonCodec).unwrap();
+    remote.set_schema_version(1);
+
+    remote.publish_event(&user_v1("fay")).unwrap();
+    assert_eq!(receiver.recv_timeout(Duration::from_secs(5)).unwrap(), UserV2 { name: String::from("fay"), admin: false });
+}
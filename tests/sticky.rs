@@ -0,0 +1,37 @@
+extern crate event;
+
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use event::{Event, EventPublisher};
+
+#[test]
+fn the_sticky_event_is_replayed_to_late_subscribers() {
+    let publisher = EventPublisher::new();
+    publisher.publish_sticky(Event::Args(1));
+    publisher.publish_sticky(Event::Args(2));
+
+    let (sender, receiver) = mpsc::channel();
+    publisher.subscribe_channel(sender).unwrap();
+    assert_eq!(receiver.try_iter().collect::<Vec<u32>>(), vec![2]);
+
+    publisher.clear_sticky();
+    let (sender, receiver) = mpsc::channel::<u32>();
+    publisher.subscribe_channel(sender).unwrap();
+    assert!(receiver.try_recv().is_err());
+}
+
+#[test]
+fn a_sticky_event_past_its_time_to_live_is_not_replayed() {
+    let publisher = EventPublisher::new();
+    publisher.publish_sticky_with_ttl(Event::Args(1), Duration::from_millis(10));
+    let (sender, receiver) = mpsc::channel();
+    publisher.subscribe_channel(sender).unwrap();
+    assert_eq!(receiver.try_iter().collect::<Vec<u32>>(), vec![1]);
+
+    thread::sleep(Duration::from_millis(20));
+    let (sender, receiver) = mpsc::channel::<u32>();
+    publisher.subscribe_channel(sender).unwrap();
+    assert!(receiver.try_recv().is_err());
+}
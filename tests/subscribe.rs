@@ -0,0 +1,257 @@
+extern crate event;
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use event::{Event, EventContext, EventHandler, EventPublisher, HandlerBox, SubscribeError};
+
+type Log = Arc<Mutex<Vec<String>>>;
+
+fn logging(log: &Log, name: &'static str) -> HandlerBox<u32> {
+    let log = log.clone();
+    Box::new(move |event: &Event<u32>| {
+        if let Event::Args(args) = *event {
+            log.lock().unwrap().push(format!("{} {}", name, args));
+        }
+    })
+}
+
+#[test]
+fn subscribe_all_subscribes_none_of_the_handlers_if_they_do_not_all_fit() {
+    let publisher = EventPublisher::with_max_subscribers(3);
+    let log: Log = Arc::new(Mutex::new(Vec::new()));
+    publisher.subscribe_handler(logging(&log, "existing")).unwrap();
+
+    let rejected = publisher.subscribe_all(vec![logging(&log, "a"), logging(&log, "b"), logging(&log, "c")]);
+    assert_eq!(rejected.err(), Some(SubscribeError::Full));
+    assert_eq!(publisher.subscriber_count(), 1);
+
+    let all = publisher.subscribe_all(vec![logging(&log, "a"), logging(&log, "b")]).unwrap();
+    assert_eq!(all.ids().len(), 2);
+    publisher.publish_event(&Event::Args(1));
+    assert_eq!(*log.lock().unwrap(), vec!["existing 1", "a 1", "b 1"]);
+}
+
+#[test]
+fn dropping_the_composite_subscription_unsubscribes_every_handler() {
+    let publisher = EventPublisher::new();
+    let log: Log = Arc::new(Mutex::new(Vec::new()));
+    publisher.subscribe_handler(logging(&log, "kept")).unwrap();
+    let all = publisher.subscribe_all(vec![logging(&log, "a"), logging(&log, "b")]).unwrap();
+    let ids = all.ids().to_vec();
+
+    drop(all);
+    publisher.publish_event(&Event::Args(1));
+
+    assert_eq!(*log.lock().unwrap(), vec!["kept 1"]);
+    assert_eq!(publisher.subscriber_count(), 1);
+    assert!(ids.into_iter().all(|id| !publisher.unsubscribe(id)));
+}
+
+#[test]
+fn stopping_propagation_skips_the_handlers_called_after_the_stopping_one() {
+    let publisher = EventPublisher::new();
+    let log: Log = Arc::new(Mutex::new(Vec::new()));
+    publisher.subscribe_with_priority(logging(&log, "high"), 5).unwrap();
+    publisher.subscribe_with_priority(logging(&log, "low"), -1).unwrap();
+    let stopping = log.clone();
+    publisher.subscribe_cancellable(move |context: &mut EventContext<u32>| {
+        if let Event::Args(args) = *context.event {
+            stopping.lock().unwrap().push(format!("stopping {}", args));
+            if args == 1 {
+                context.stop_propagation();
+            }
+        }
+    }).unwrap();
+    publisher.subscribe_handler(logging(&log, "after")).unwrap();
+
+    publisher.publish_event(&Event::Args(1));
+    publisher.publish_event(&Event::Args(2));
+
+    assert_eq!(*log.lock().unwrap(), vec!["high 1", "stopping 1", "high 2", "stopping 2", "after 2", "low 2"]);
+}
+
+#[test]
+fn handler_state_persists_between_publishes_on_any_thread() {
+    let publisher = Arc::new(EventPublisher::new());
+    let totals = Arc::new(Mutex::new(Vec::new()));
+    let reported = totals.clone();
+    publisher.subscribe_with_state(0, move |total: &mut u32, event: &Event<u32>| {
+        if let Event::Args(args) = *event {
+            *total += args;
+            reported.lock().unwrap().push(*total);
+        }
+    }).unwrap();
+
+    publisher.publish_events(&[Event::Args(1), Event::Args(2), Event::Args(3)]);
+    assert_eq!(*totals.lock().unwrap(), vec![1, 3, 6]);
+
+    let threads: Vec<_> = (0..4).map(|_| {
+        let publisher = publisher.clone();
+        thread::spawn(move || publisher.publish_event(&Event::Args(10)))
+    }).collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert_eq!(totals.lock().unwrap().last(), Some(&46));
+}
+
+#[test]
+fn subscribing_beyond_the_limit_fails_until_a_slot_is_freed() {
+    let publisher = EventPublisher::with_max_subscribers(2);
+    let log: Log = Arc::new(Mutex::new(Vec::new()));
+    let first = publisher.subscribe_handler(logging(&log, "first")).unwrap();
+    publisher.subscribe_handler(logging(&log, "second")).unwrap();
+
+    assert_eq!(publisher.subscribe_handler(logging(&log, "third")), Err(SubscribeError::Full));
+    assert_eq!(publisher.subscriber_count(), 2);
+
+    assert!(publisher.unsubscribe(first));
+    publisher.subscribe_handler(logging(&log, "third")).unwrap();
+    publisher.publish_event(&Event::Args(1));
+    assert_eq!(*log.lock().unwrap(), vec!["second 1", "third 1"]);
+}
+
+#[test]
+fn max_subscribers_reports_the_limit_to_check_the_room_left() {
+    assert_eq!(EventPublisher::<u32>::new().max_subscribers(), None);
+    let publisher = EventPublisher::with_max_subscribers(2);
+    let log: Log = Arc::new(Mutex::new(Vec::new()));
+    assert_eq!(publisher.max_subscribers(), Some(2));
+
+    let first = publisher.subscribe_handler(logging(&log, "first")).unwrap();
+    publisher.subscribe_handler(logging(&log, "second")).unwrap();
+    assert_eq!(publisher.max_subscribers().map(|max| max - publisher.subscriber_count()), Some(0));
+    assert_eq!(publisher.subscribe_handler(logging(&log, "third")), Err(SubscribeError::Full));
+
+    publisher.unsubscribe(first);
+    assert_eq!(publisher.max_subscribers().map(|max| max - publisher.subscriber_count()), Some(1));
+    assert!(publisher.subscribe_handler(logging(&log, "third")).is_ok());
+}
+
+#[test]
+fn retain_subscriptions_removes_the_rejected_ones_and_counts_them() {
+    let publisher = EventPublisher::new();
+    let log: Log = Arc::new(Mutex::new(Vec::new()));
+    publisher.subscribe_with_priority(logging(&log, "low"), -1).unwrap();
+    publisher.subscribe_handler(logging(&log, "kept")).unwrap();
+    publisher.subscribe_with_priority(logging(&log, "also low"), -2).unwrap();
+
+    assert_eq!(publisher.retain_subscriptions(|info| info.priority >= 0), 2);
+    assert_eq!(publisher.retain_subscriptions(|info| info.priority >= 0), 0);
+    publisher.publish_event(&Event::Args(1));
+
+    assert_eq!(*log.lock().unwrap(), vec!["kept 1"]);
+    assert_eq!(publisher.subscriber_count(), 1);
+}
+
+#[test]
+fn dropping_a_scoped_subscription_unsubscribes_the_handler() {
+    let publisher = EventPublisher::new();
+    let log: Log = Arc::new(Mutex::new(Vec::new()));
+    {
+        let _scoped = publisher.subscribe_scoped(logging(&log, "scoped")).unwrap();
+        publisher.publish_event(&Event::Args(1));
+        assert_eq!(publisher.subscriber_count(), 1);
+    }
+    publisher.publish_event(&Event::Args(2));
+    let detached = publisher.subscribe_scoped(logging(&log, "detached")).unwrap().detach();
+    publisher.publish_event(&Event::Args(3));
+
+    assert_eq!(*log.lock().unwrap(), vec!["scoped 1", "detached 3"]);
+    assert!(publisher.unsubscribe(detached));
+}
+
+#[test]
+fn a_weak_subscription_removes_itself_once_its_subscriber_is_dropped() {
+    struct Counter(Mutex<Vec<u32>>);
+
+    let publisher = EventPublisher::new();
+    let counter = Arc::new(Counter(Mutex::new(Vec::new())));
+    publisher.subscribe_weak(Arc::downgrade(&counter), |counter: &Counter, event: &Event<u32>| {
+        if let Event::Args(args) = *event {
+            counter.0.lock().unwrap().push(args);
+        }
+    }).unwrap();
+
+    publisher.publish_event(&Event::Args(1));
+    assert_eq!(*counter.0.lock().unwrap(), vec![1]);
+    assert_eq!(Arc::strong_count(&counter), 1);
+
+    drop(counter);
+    assert_eq!(publisher.subscriber_count(), 1);
+    publisher.publish_event(&Event::Args(2));
+    assert_eq!(publisher.subscriber_count(), 0);
+}
+
+#[test]
+fn a_shared_event_handler_is_called_and_stays_inspectable_by_its_owner() {
+    struct Recorder(Mutex<Vec<u32>>);
+
+    impl EventHandler<u32> for Recorder {
+        fn handle(&self, event: &Event<u32>) {
+            if let Event::Args(args) = *event {
+                self.0.lock().unwrap().push(args);
+            }
+        }
+    }
+
+    let publisher = EventPublisher::new();
+    let recorder = Arc::new(Recorder(Mutex::new(Vec::new())));
+    let first = publisher.subscribe(recorder.clone()).unwrap();
+    publisher.subscribe(recorder.clone()).unwrap();
+
+    publisher.publish_event(&Event::Args(1));
+    publisher.unsubscribe(first);
+    publisher.publish_event(&Event::Args(2));
+
+    assert_eq!(*recorder.0.lock().unwrap(), vec![1, 1, 2]);
+}
+
+#[test]
+fn handlers_are_called_by_descending_priority_then_subscription_order() {
+    let publisher = EventPublisher::new();
+    let log: Log = Arc::new(Mutex::new(Vec::new()));
+    publisher.subscribe_with_priority(logging(&log, "low"), -1).unwrap();
+    publisher.subscribe_handler(logging(&log, "first")).unwrap();
+    publisher.subscribe_with_priority(logging(&log, "high"), 5).unwrap();
+    publisher.subscribe_with_priority(logging(&log, "second"), 0).unwrap();
+    publisher.subscribe_with_priority(logging(&log, "also high"), 5).unwrap();
+
+    publisher.publish_event(&Event::Args(1));
+
+    assert_eq!(*log.lock().unwrap(), vec!["high 1", "also high 1", "first 1", "second 1", "low 1"]);
+}
+
+#[test]
+fn a_filtered_handler_is_only_called_for_matching_events() {
+    let publisher = EventPublisher::new();
+    let log: Log = Arc::new(Mutex::new(Vec::new()));
+    let even = logging(&log, "even");
+    publisher.subscribe_filtered(|event: &Event<u32>| matches!(*event, Event::Args(args) if args % 2 == 0), move |event| even(event)).unwrap();
+    publisher.subscribe_handler(logging(&log, "all")).unwrap();
+
+    publisher.publish_events(&[Event::Args(1), Event::Args(2), Event::Args(3), Event::Args(4)]);
+
+    assert_eq!(*log.lock().unwrap(), vec!["all 1", "even 2", "all 2", "all 3", "even 4", "all 4"]);
+}
+
+#[test]
+fn unsubscribe_group_removes_every_handler_of_the_group() {
+    let publisher = EventPublisher::new();
+    let log: Log = Arc::new(Mutex::new(Vec::new()));
+    for (group, name) in [("ui", "button"), ("network", "socket"), ("ui", "label")] {
+        let handler = logging(&log, name);
+        publisher.subscribe_named(group, move |event| handler(event)).unwrap();
+    }
+    publisher.subscribe_handler(logging(&log, "ungrouped")).unwrap();
+    let groups: Vec<Option<String>> = publisher.subscriptions().into_iter().map(|info| info.group).collect();
+    assert_eq!(groups, vec![Some(String::from("ui")), Some(String::from("network")), Some(String::from("ui")), None]);
+
+    assert_eq!(publisher.unsubscribe_group("ui"), 2);
+    assert_eq!(publisher.unsubscribe_group("ui"), 0);
+    assert_eq!(publisher.unsubscribe_group("unknown"), 0);
+    publisher.publish_event(&Event::Args(1));
+
+    assert_eq!(*log.lock().unwrap(), vec!["socket 1", "ungrouped 1"]);
+}
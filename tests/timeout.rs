@@ -0,0 +1,86 @@
+extern crate event;
+
+use std::sync::{Arc, Mutex};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use event::{Event, EventPublisher, HandlerTimeout, TimeoutPolicy};
+
+#[test]
+fn handlers_exceeding_the_timeout_are_reported_and_unsubscribed_after_repeated_timeouts() {
+    let publisher = Arc::new(EventPublisher::new());
+    publisher.set_handler_timeout(Some(TimeoutPolicy { timeout: Duration::from_millis(10), unsubscribe_after: Some(2) }));
+    let timeouts: Arc<Mutex<Vec<HandlerTimeout>>> = Arc::new(Mutex::new(Vec::new()));
+    let hook_timeouts = timeouts.clone();
+    publisher.set_timeout_hook(Box::new(move |timeout| hook_timeouts.lock().unwrap().push(*timeout)));
+    let fast_calls = Arc::new(Mutex::new(0));
+    let counter = fast_calls.clone();
+    publisher.subscribe_args(move |_: &u32| *counter.lock().unwrap() += 1).unwrap();
+    let slow = publisher.subscribe_args(|_: &u32| thread::sleep(Duration::from_millis(100))).unwrap();
+
+    for args in 0..3 {
+        publisher.publish_event_with_timeout(&Event::Args(args));
+    }
+
+    assert_eq!(*timeouts.lock().unwrap(), vec![
+        HandlerTimeout { subscription: slow, timeout: Duration::from_millis(10), violations: 1, unsubscribed: false },
+        HandlerTimeout { subscription: slow, timeout: Duration::from_millis(10), violations: 2, unsubscribed: true },
+    ]);
+    assert_eq!(publisher.subscriber_count(), 1);
+    assert_eq!(*fast_calls.lock().unwrap(), 3);
+}
+
+#[test]
+fn the_publish_returns_once_the_timeout_passed_and_late_handlers_finish_on_their_own() {
+    let publisher = Arc::new(EventPublisher::new());
+    publisher.set_handler_timeout(Some(TimeoutPolicy { timeout: Duration::from_millis(10), unsubscribe_after: None }));
+    let (release, released) = mpsc::channel::<()>();
+    let released = Mutex::new(released);
+    let (finished, finished_calls) = mpsc::channel();
+    let finished = Mutex::new(finished);
+    publisher.subscribe_args(move |args: &u32| {
+        let _ = released.lock().unwrap().recv();
+        finished.lock().unwrap().send(*args).unwrap();
+    }).unwrap();
+
+    let started = Instant::now();
+    publisher.publish_event_with_timeout(&Event::Args(7));
+
+    // The handler is still blocked, so the publish can only have returned because of the timeout.
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert!(finished_calls.try_recv().is_err());
+    release.send(()).unwrap();
+    assert_eq!(finished_calls.recv_timeout(Duration::from_secs(5)), Ok(7));
+}
+
+#[test]
+fn without_a_timeout_every_handler_is_waited_for() {
+    let publisher = Arc::new(EventPublisher::new());
+    let calls = Arc::new(Mutex::new(0));
+    let counter = calls.clone();
+    publisher.subscribe_args(move |_: &u32| {
+        thread::sleep(Duration::from_millis(20));
+        *counter.lock().unwrap() += 1;
+    }).unwrap();
+
+    publisher.publish_event_with_timeout(&Event::Args(1));
+
+    assert_eq!(*calls.lock().unwrap(), 1);
+}
+
+#[test]
+fn the_timeout_is_ignored_by_the_other_publish_functions() {
+    let publisher = EventPublisher::new();
+    publisher.set_handler_timeout(Some(TimeoutPolicy { timeout: Duration::from_millis(1), unsubscribe_after: Some(1) }));
+    let timeouts = Arc::new(Mutex::new(0));
+    let hook_timeouts = timeouts.clone();
+    publisher.set_timeout_hook(Box::new(move |_| *hook_timeouts.lock().unwrap() += 1));
+    publisher.subscribe_args(|_: &u32| thread::sleep(Duration::from_millis(10))).unwrap();
+
+    publisher.publish_event(&Event::Args(1));
+    publisher.publish_event_multithreaded(&Event::Args(2));
+
+    assert_eq!(*timeouts.lock().unwrap(), 0);
+    assert_eq!(publisher.subscriber_count(), 1);
+}
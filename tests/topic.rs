@@ -0,0 +1,58 @@
+extern crate event;
+
+use std::sync::{Arc, Mutex};
+
+use event::{Event, SubscriptionId, TopicPublisher};
+
+type Seen = Arc<Mutex<Vec<String>>>;
+
+fn subscribe(publisher: &TopicPublisher<u32>, seen: &Seen, pattern: &'static str) -> SubscriptionId {
+    let seen = seen.clone();
+    publisher.subscribe(pattern, move |topic, _| seen.lock().unwrap().push(format!("{} <- {}", pattern, topic)))
+}
+
+#[test]
+fn wildcards_match_one_or_any_number_of_words() {
+    let publisher = TopicPublisher::new();
+    let seen: Seen = Arc::new(Mutex::new(Vec::new()));
+    subscribe(&publisher, &seen, "orders.*");
+    subscribe(&publisher, &seen, "orders.#");
+    subscribe(&publisher, &seen, "#");
+    subscribe(&publisher, &seen, "orders.created");
+
+    for topic in &["orders", "orders.created", "orders.created.eu", "users.created"] {
+        publisher.publish(topic, &Event::Args(1));
+    }
+
+    assert_eq!(*seen.lock().unwrap(), vec![
+        "orders.# <- orders", "# <- orders",
+        "orders.* <- orders.created", "orders.# <- orders.created", "# <- orders.created", "orders.created <- orders.created",
+        "orders.# <- orders.created.eu", "# <- orders.created.eu",
+        "# <- users.created",
+    ]);
+}
+
+#[test]
+fn wildcards_in_published_topics_are_taken_literally() {
+    let publisher = TopicPublisher::new();
+    let seen: Seen = Arc::new(Mutex::new(Vec::new()));
+    subscribe(&publisher, &seen, "orders.created");
+
+    publisher.publish("orders.*", &Event::Args(1));
+    publisher.publish("#", &Event::Args(1));
+
+    assert!(seen.lock().unwrap().is_empty());
+}
+
+#[test]
+fn unsubscribed_handlers_no_longer_receive_events() {
+    let publisher = TopicPublisher::new();
+    let seen: Seen = Arc::new(Mutex::new(Vec::new()));
+    let id = subscribe(&publisher, &seen, "#");
+
+    assert!(publisher.unsubscribe(id));
+    assert!(!publisher.unsubscribe(id));
+    publisher.publish("orders", &Event::Args(1));
+
+    assert!(seen.lock().unwrap().is_empty());
+}
@@ -0,0 +1,87 @@
+#![cfg(feature = "tracing")]
+
+extern crate event;
+extern crate tracing;
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use event::{Event, EventPublisher};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Metadata, Subscriber};
+
+// Writes down every span and event as a line of text, naming the span an event happened in.
+#[derive(Default)]
+struct Capture {
+    next_id: AtomicU64,
+    spans: Mutex<Vec<String>>,
+    entered: Mutex<Vec<u64>>,
+    lines: Mutex<Vec<String>>,
+}
+
+struct Fields(String);
+
+impl Visit for Fields {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.0.push_str(&format!(" {:?}", value));
+        } else {
+            self.0.push_str(&format!(" {}={:?}", field.name(), value));
+        }
+    }
+}
+
+impl Subscriber for Capture {
+    fn enabled(&self, _: &Metadata) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes) -> Id {
+        let mut fields = Fields(format!("span {}", span.metadata().name()));
+        span.record(&mut fields);
+        self.lines.lock().unwrap().push(fields.0);
+        self.spans.lock().unwrap().push(span.metadata().name().to_string());
+        Id::from_u64(self.next_id.fetch_add(1, Ordering::SeqCst) + 1)
+    }
+
+    fn record(&self, _: &Id, _: &Record) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, event: &tracing::Event) {
+        let mut fields = Fields(format!("{}", event.metadata().level()));
+        event.record(&mut fields);
+        if let Some(&span) = self.entered.lock().unwrap().last() {
+            fields.0.push_str(&format!(" in {}", self.spans.lock().unwrap()[span as usize - 1]));
+        }
+        self.lines.lock().unwrap().push(fields.0);
+    }
+
+    fn enter(&self, span: &Id) {
+        self.entered.lock().unwrap().push(span.into_u64());
+    }
+
+    fn exit(&self, _: &Id) {
+        self.entered.lock().unwrap().pop();
+    }
+}
+
+#[test]
+fn publishes_are_spans_and_handler_calls_events_within_them() {
+    let capture = Arc::new(Capture::default());
+    let publisher = EventPublisher::new();
+    publisher.set_source(Some(String::from("input")));
+    publisher.subscribe_args(|_: &u32| {}).unwrap();
+    publisher.subscribe_named("ui", |_| {}).unwrap();
+
+    tracing::subscriber::with_default(capture.clone(), || publisher.publish_event(&Event::Args(1)));
+
+    assert_eq!(*capture.lines.lock().unwrap(), vec![
+        String::from("span publish source=\"input\""),
+        String::from("TRACE calling handler subscription=1 in publish"),
+        String::from("TRACE calling handler subscription=2 group=\"ui\" in publish"),
+    ]);
+    assert!(capture.entered.lock().unwrap().is_empty());
+}